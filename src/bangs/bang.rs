@@ -0,0 +1,170 @@
+//! Bang definition and URL building
+
+use serde::{Deserialize, Serialize};
+
+/// Placeholder for the search terms in a bang URL template
+const PLACEHOLDER: &str = "{{{s}}}";
+
+/// URL formatting rules for a bang
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BangFormat {
+    /// Open the site root when the query is empty
+    OpenBasePath,
+    /// Percent-encode the search terms
+    UrlEncodePlaceholder,
+    /// Encode spaces as `+` instead of `%20`
+    UrlEncodeSpaceToPlus,
+    /// Rule not understood by this implementation
+    #[serde(other)]
+    Unknown,
+}
+
+/// A single external bang, in DuckDuckGo `bang.js` format
+///
+/// Long field names (`trigger`, `name`, `url`, ...) are accepted as aliases
+/// so custom bangs in settings.yml stay readable.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Bang {
+    /// Trigger without the `!` (e.g. "w")
+    #[serde(rename = "t", alias = "trigger")]
+    pub trigger: String,
+    /// Display name
+    #[serde(rename = "s", alias = "name")]
+    pub name: String,
+    /// URL template with `{{{s}}}` placeholder
+    #[serde(rename = "u", alias = "url")]
+    pub url: String,
+    /// Site domain
+    #[serde(rename = "d", alias = "domain", default)]
+    pub domain: String,
+    /// Category
+    #[serde(rename = "c", alias = "category", default)]
+    pub category: Option<String>,
+    /// Subcategory
+    #[serde(rename = "sc", alias = "subcategory", default)]
+    pub subcategory: Option<String>,
+    /// Popularity rank (higher is more popular)
+    #[serde(rename = "r", alias = "rank", default)]
+    pub rank: u64,
+    /// URL formatting rules (defaults apply when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fmt: Option<Vec<BangFormat>>,
+}
+
+impl Bang {
+    /// Create a bang with default formatting rules
+    pub fn new(
+        trigger: impl Into<String>,
+        name: impl Into<String>,
+        url: impl Into<String>,
+    ) -> Self {
+        let url = url.into();
+        let domain = url::Url::parse(&url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .unwrap_or_default();
+
+        Self {
+            trigger: trigger.into(),
+            name: name.into(),
+            url,
+            domain,
+            category: None,
+            subcategory: None,
+            rank: 0,
+            fmt: None,
+        }
+    }
+
+    /// Check if a formatting rule applies
+    ///
+    /// Bangs without explicit rules use all of them, matching DuckDuckGo.
+    pub fn has_format(&self, format: BangFormat) -> bool {
+        match self.fmt {
+            Some(ref rules) => rules.contains(&format),
+            None => true,
+        }
+    }
+
+    /// Build the redirect URL for a query
+    pub fn build_url(&self, query: &str) -> String {
+        let query = query.trim();
+
+        if query.is_empty() && self.has_format(BangFormat::OpenBasePath) {
+            return self.base_url();
+        }
+
+        let terms = if self.has_format(BangFormat::UrlEncodePlaceholder) {
+            let encoded = urlencoding::encode(query).into_owned();
+            if self.has_format(BangFormat::UrlEncodeSpaceToPlus) {
+                encoded.replace("%20", "+")
+            } else {
+                encoded
+            }
+        } else if self.has_format(BangFormat::UrlEncodeSpaceToPlus) {
+            query.replace(' ', "+")
+        } else {
+            query.to_string()
+        };
+
+        self.url.replace(PLACEHOLDER, &terms)
+    }
+
+    /// Root URL of the bang's site
+    fn base_url(&self) -> String {
+        if !self.domain.is_empty() {
+            return format!("https://{}", self.domain);
+        }
+
+        url::Url::parse(&self.url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| format!("{}://{}", u.scheme(), h)))
+            .unwrap_or_else(|| self.url.replace(PLACEHOLDER, ""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_format() {
+        let bang = Bang::new(
+            "w",
+            "Wikipedia",
+            "https://en.wikipedia.org/w?search={{{s}}}",
+        );
+        assert_eq!(
+            bang.build_url("rust lang & c++"),
+            "https://en.wikipedia.org/w?search=rust+lang+%26+c%2B%2B"
+        );
+        assert_eq!(bang.build_url(""), "https://en.wikipedia.org");
+    }
+
+    #[test]
+    fn test_explicit_format() {
+        let mut bang = Bang::new("wb", "Wayback", "https://web.archive.org/web/*/{{{s}}}");
+        bang.fmt = Some(vec![BangFormat::OpenBasePath]);
+        assert_eq!(
+            bang.build_url("example.com/a b"),
+            "https://web.archive.org/web/*/example.com/a b"
+        );
+
+        bang.fmt = Some(vec![BangFormat::UrlEncodePlaceholder]);
+        assert_eq!(bang.build_url("a b"), "https://web.archive.org/web/*/a%20b");
+        assert_eq!(bang.build_url(""), "https://web.archive.org/web/*/");
+    }
+
+    #[test]
+    fn test_ddg_format() {
+        let json = r#"{"c":"Tech","d":"github.com","r":1234,"s":"GitHub","sc":"Programming","t":"gh","u":"https://github.com/search?q={{{s}}}","fmt":["open_base_path","some_new_rule"]}"#;
+        let bang: Bang = serde_json::from_str(json).unwrap();
+        assert_eq!(bang.trigger, "gh");
+        assert_eq!(bang.rank, 1234);
+        assert_eq!(
+            bang.fmt,
+            Some(vec![BangFormat::OpenBasePath, BangFormat::Unknown])
+        );
+    }
+}
@@ -0,0 +1,26 @@
+//! External bangs
+//!
+//! External bangs redirect a query to another site's search, e.g. `!!w rust`
+//! opens Wikipedia. Bangs are loaded from a bundled database in DuckDuckGo's
+//! `bang.js` format, an optional external database file and custom bangs
+//! from settings.yml.
+
+mod bang;
+mod registry;
+
+pub use bang::{Bang, BangFormat};
+pub use registry::BangRegistry;
+
+use once_cell::sync::Lazy;
+
+/// Bundled bang database (DuckDuckGo `bang.js` format)
+pub const BUNDLED_BANGS: &str = include_str!("../data/external_bangs.json");
+
+/// Registry with only the bundled bangs
+static DEFAULT_REGISTRY: Lazy<BangRegistry> =
+    Lazy::new(|| BangRegistry::bundled().expect("Bundled bang database is invalid"));
+
+/// Get the registry of bundled bangs
+pub fn default_registry() -> &'static BangRegistry {
+    &DEFAULT_REGISTRY
+}
@@ -0,0 +1,277 @@
+//! Bang registry with prefix-tree lookup
+
+use super::bang::Bang;
+use crate::config::BangSettings;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// Prefix tree over bang triggers
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: BTreeMap<char, TrieNode>,
+    /// Index into the registry's bang list
+    bang: Option<usize>,
+}
+
+impl TrieNode {
+    fn insert(&mut self, trigger: &str, index: usize) {
+        let mut node = self;
+        for c in trigger.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        node.bang = Some(index);
+    }
+
+    fn find(&self, prefix: &str) -> Option<&TrieNode> {
+        let mut node = self;
+        for c in prefix.chars() {
+            node = node.children.get(&c)?;
+        }
+        Some(node)
+    }
+
+    /// Collect bang indices below this node in trigger order
+    fn collect(&self, out: &mut Vec<usize>) {
+        if let Some(index) = self.bang {
+            out.push(index);
+        }
+        for child in self.children.values() {
+            child.collect(out);
+        }
+    }
+}
+
+/// Registry of all known external bangs
+#[derive(Debug, Default)]
+pub struct BangRegistry {
+    bangs: Vec<Bang>,
+    trie: TrieNode,
+}
+
+impl BangRegistry {
+    /// Create a new empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the bundled bang database
+    pub fn bundled() -> Result<Self> {
+        let mut registry = Self::new();
+        registry.extend(Self::parse_json(super::BUNDLED_BANGS)?);
+        Ok(registry)
+    }
+
+    /// Load the bundled database, an optional database file and custom bangs
+    pub fn load(settings: &BangSettings) -> Result<Self> {
+        let mut registry = Self::bundled()?;
+
+        if let Some(ref path) = settings.file {
+            match std::fs::read_to_string(path) {
+                Ok(json) => {
+                    let bangs = Self::parse_json(&json)?;
+                    info!("Loaded {} bangs from {}", bangs.len(), path);
+                    registry.extend(bangs);
+                }
+                Err(e) => warn!("Failed to read bang database {}: {}", path, e),
+            }
+        }
+
+        registry.extend(settings.custom.iter().cloned());
+
+        Ok(registry)
+    }
+
+    /// Parse a bang database in DuckDuckGo `bang.js` format
+    pub fn parse_json(json: &str) -> Result<Vec<Bang>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Add a bang, replacing any existing bang with the same trigger
+    pub fn add(&mut self, mut bang: Bang) {
+        bang.trigger = Self::normalize(&bang.trigger);
+        if bang.trigger.is_empty() {
+            return;
+        }
+
+        match self.trie.find(&bang.trigger).and_then(|node| node.bang) {
+            Some(existing) => self.bangs[existing] = bang,
+            None => {
+                self.trie.insert(&bang.trigger, self.bangs.len());
+                self.bangs.push(bang);
+            }
+        }
+    }
+
+    /// Add multiple bangs
+    pub fn extend(&mut self, bangs: impl IntoIterator<Item = Bang>) {
+        for bang in bangs {
+            self.add(bang);
+        }
+    }
+
+    /// Get a bang by trigger (with or without `!`)
+    pub fn get(&self, trigger: &str) -> Option<&Bang> {
+        let trigger = Self::normalize(trigger);
+        self.trie
+            .find(&trigger)
+            .and_then(|node| node.bang)
+            .map(|index| &self.bangs[index])
+    }
+
+    /// Check if a trigger is a known bang
+    pub fn contains(&self, trigger: &str) -> bool {
+        self.get(trigger).is_some()
+    }
+
+    /// Get the redirect URL for a bang and query
+    pub fn redirect_url(&self, trigger: &str, query: &str) -> Option<String> {
+        self.get(trigger).map(|bang| bang.build_url(query))
+    }
+
+    /// Find bangs whose trigger starts with a prefix, in trigger order
+    pub fn with_prefix(&self, prefix: &str) -> Vec<&Bang> {
+        let mut indices = Vec::new();
+        if let Some(node) = self.trie.find(&Self::normalize(prefix)) {
+            node.collect(&mut indices);
+        }
+        indices.into_iter().map(|i| &self.bangs[i]).collect()
+    }
+
+    /// Search bangs by trigger prefix or name
+    ///
+    /// Exact trigger matches come first, then trigger prefix matches, then
+    /// bangs whose name contains the text; each group is ordered by rank.
+    pub fn search(&self, text: &str) -> Vec<&Bang> {
+        let needle = Self::normalize(text);
+        if needle.is_empty() {
+            return self.iter().collect();
+        }
+
+        let mut prefixed = self.with_prefix(&needle);
+        prefixed.sort_by(|a, b| {
+            (b.trigger == needle)
+                .cmp(&(a.trigger == needle))
+                .then(b.rank.cmp(&a.rank))
+        });
+
+        let mut by_name: Vec<&Bang> = self
+            .bangs
+            .iter()
+            .filter(|b| !b.trigger.starts_with(&needle))
+            .filter(|b| b.name.to_lowercase().contains(&needle))
+            .collect();
+        by_name.sort_by_key(|b| std::cmp::Reverse(b.rank));
+
+        prefixed.extend(by_name);
+        prefixed
+    }
+
+    /// Iterate over all bangs in trigger order
+    pub fn iter(&self) -> impl Iterator<Item = &Bang> {
+        let mut indices = Vec::new();
+        self.trie.collect(&mut indices);
+        indices.into_iter().map(move |i| &self.bangs[i])
+    }
+
+    /// Get all triggers
+    pub fn triggers(&self) -> Vec<&str> {
+        self.iter().map(|b| b.trigger.as_str()).collect()
+    }
+
+    /// Get number of bangs
+    pub fn len(&self) -> usize {
+        self.bangs.len()
+    }
+
+    /// Check if registry is empty
+    pub fn is_empty(&self) -> bool {
+        self.bangs.is_empty()
+    }
+
+    fn normalize(trigger: &str) -> String {
+        trigger.trim().trim_start_matches('!').to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_bangs() {
+        let registry = BangRegistry::bundled().unwrap();
+        assert!(!registry.is_empty());
+
+        // Every bang that used to be hard-coded is still available
+        for trigger in ["g", "yt", "w", "wa", "gh", "so", "ddg", "amazon", "imdb"] {
+            assert!(registry.contains(trigger), "missing bang {}", trigger);
+        }
+
+        assert_eq!(
+            registry.redirect_url("!yt", "rust tutorial").unwrap(),
+            "https://www.youtube.com/results?search_query=rust+tutorial"
+        );
+    }
+
+    #[test]
+    fn test_prefix_lookup() {
+        let mut registry = BangRegistry::new();
+        registry.add(Bang::new(
+            "gh",
+            "GitHub",
+            "https://github.com/search?q={{{s}}}",
+        ));
+        registry.add(Bang::new(
+            "g",
+            "Google",
+            "https://google.com/search?q={{{s}}}",
+        ));
+        registry.add(Bang::new(
+            "gl",
+            "GitLab",
+            "https://gitlab.com/search?search={{{s}}}",
+        ));
+        registry.add(Bang::new(
+            "w",
+            "Wikipedia",
+            "https://wikipedia.org/?s={{{s}}}",
+        ));
+
+        assert_eq!(registry.triggers(), vec!["g", "gh", "gl", "w"]);
+        let prefixed: Vec<_> = registry
+            .with_prefix("g")
+            .iter()
+            .map(|b| b.trigger.as_str())
+            .collect();
+        assert_eq!(prefixed, vec!["g", "gh", "gl"]);
+        assert!(registry.with_prefix("x").is_empty());
+
+        // Name matches follow trigger matches
+        let found: Vec<_> = registry
+            .search("git")
+            .iter()
+            .map(|b| b.trigger.as_str())
+            .collect();
+        assert_eq!(found, vec!["gh", "gl"]);
+    }
+
+    #[test]
+    fn test_custom_bang_overrides() {
+        let settings = BangSettings {
+            file: None,
+            custom: vec![serde_yaml::from_str(
+                "trigger: w\nname: Intranet wiki\nurl: https://wiki.internal/search?q={{{s}}}",
+            )
+            .unwrap()],
+        };
+        let registry = BangRegistry::load(&settings).unwrap();
+        let bundled = BangRegistry::bundled().unwrap();
+
+        assert_eq!(registry.len(), bundled.len());
+        assert_eq!(
+            registry.redirect_url("W", "x").unwrap(),
+            "https://wiki.internal/search?q=x"
+        );
+    }
+}
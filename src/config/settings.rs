@@ -1,5 +1,6 @@
 //! Settings structures for SearXNG-RS configuration
 
+use crate::bangs::Bang;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -16,6 +17,7 @@ pub struct Settings {
     pub engines: Vec<EngineConfig>,
     pub plugins: PluginsSettings,
     pub ui: UiSettings,
+    pub bangs: BangSettings,
     pub redis: Option<RedisSettings>,
 }
 
@@ -29,6 +31,7 @@ impl Default for Settings {
             engines: default_engines(),
             plugins: PluginsSettings::default(),
             ui: UiSettings::default(),
+            bangs: BangSettings::default(),
             redis: None,
         }
     }
@@ -286,14 +289,14 @@ pub struct ProxySettings {
 }
 
 /// Individual engine configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct EngineConfig {
     /// Engine name (unique identifier)
     pub name: String,
     /// Engine module to use
     pub engine: String,
-    /// Categories this engine belongs to
+    /// Categories this engine belongs to (empty = engine defaults)
     pub categories: Vec<String>,
     /// Short name for UI
     pub shortcut: String,
@@ -301,31 +304,44 @@ pub struct EngineConfig {
     pub disabled: bool,
     /// Custom timeout for this engine
     pub timeout: Option<f64>,
-    /// Engine weight for scoring
-    pub weight: f64,
+    /// Engine weight for scoring (engine default if unset)
+    pub weight: Option<f64>,
     /// Display name
     pub display_name: Option<String>,
     /// API key if required
     pub api_key: Option<String>,
+    /// Access tokens; if set, the engine is private and only visible to
+    /// users presenting one of them
+    pub tokens: Vec<String>,
     /// Additional engine-specific settings
     #[serde(flatten)]
     pub extra: HashMap<String, serde_yaml::Value>,
 }
 
-impl Default for EngineConfig {
-    fn default() -> Self {
-        Self {
-            name: String::new(),
-            engine: String::new(),
-            categories: vec!["general".to_string()],
-            shortcut: String::new(),
-            disabled: false,
-            timeout: None,
-            weight: 1.0,
-            display_name: None,
-            api_key: None,
-            extra: HashMap::new(),
-        }
+impl EngineConfig {
+    /// Check if the engine is restricted to token holders
+    pub fn is_private(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// Check if a user presenting `tokens` may use this engine
+    pub fn is_accessible(&self, tokens: &[String]) -> bool {
+        !self.is_private()
+            || tokens
+                .iter()
+                .any(|t| self.tokens.iter().any(|own| constant_time_eq(own, t)))
+    }
+
+    /// Get an engine-specific setting, if present and of the expected type
+    pub fn extra_value<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.extra
+            .get(key)
+            .and_then(|v| serde_yaml::from_value(v.clone()).ok())
+    }
+
+    /// Get an engine-specific string setting
+    pub fn extra_str(&self, key: &str) -> Option<String> {
+        self.extra_value(key)
     }
 }
 
@@ -386,6 +402,16 @@ impl Default for UiSettings {
     }
 }
 
+/// External bang settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BangSettings {
+    /// Additional bang database in DuckDuckGo `bang.js` format
+    pub file: Option<String>,
+    /// Custom bangs (override bundled bangs with the same trigger)
+    pub custom: Vec<Bang>,
+}
+
 /// Redis/Valkey settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RedisSettings {
@@ -401,6 +427,15 @@ fn generate_secret_key() -> String {
         .collect()
 }
 
+/// Compare secrets without returning early at the first differing byte
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
 /// Default engine configurations
 fn default_engines() -> Vec<EngineConfig> {
     vec![
@@ -439,6 +474,41 @@ fn default_engines() -> Vec<EngineConfig> {
             shortcut: "wp".to_string(),
             ..Default::default()
         },
+        EngineConfig {
+            name: "crates.io".to_string(),
+            engine: "crates_io".to_string(),
+            categories: vec!["it".to_string(), "packages".to_string()],
+            shortcut: "crates".to_string(),
+            ..Default::default()
+        },
+        EngineConfig {
+            name: "docs.rs".to_string(),
+            engine: "docs_rs".to_string(),
+            categories: vec!["it".to_string(), "packages".to_string()],
+            shortcut: "docsrs".to_string(),
+            ..Default::default()
+        },
+        EngineConfig {
+            name: "pypi".to_string(),
+            engine: "pypi".to_string(),
+            categories: vec!["it".to_string(), "packages".to_string()],
+            shortcut: "pypi".to_string(),
+            ..Default::default()
+        },
+        EngineConfig {
+            name: "npm".to_string(),
+            engine: "npm".to_string(),
+            categories: vec!["it".to_string(), "packages".to_string()],
+            shortcut: "npm".to_string(),
+            ..Default::default()
+        },
+        EngineConfig {
+            name: "openstreetmap".to_string(),
+            engine: "nominatim".to_string(),
+            categories: vec!["maps".to_string()],
+            shortcut: "osm".to_string(),
+            ..Default::default()
+        },
         EngineConfig {
             name: "google images".to_string(),
             engine: "google_images".to_string(),
@@ -474,6 +544,27 @@ fn default_engines() -> Vec<EngineConfig> {
             shortcut: "arx".to_string(),
             ..Default::default()
         },
+        EngineConfig {
+            name: "crossref".to_string(),
+            engine: "crossref".to_string(),
+            categories: vec!["science".to_string()],
+            shortcut: "cr".to_string(),
+            ..Default::default()
+        },
+        EngineConfig {
+            name: "semantic scholar".to_string(),
+            engine: "semantic_scholar".to_string(),
+            categories: vec!["science".to_string()],
+            shortcut: "se".to_string(),
+            ..Default::default()
+        },
+        EngineConfig {
+            name: "pubmed".to_string(),
+            engine: "pubmed".to_string(),
+            categories: vec!["science".to_string()],
+            shortcut: "pub".to_string(),
+            ..Default::default()
+        },
         EngineConfig {
             name: "github".to_string(),
             engine: "github".to_string(),
@@ -483,7 +574,7 @@ fn default_engines() -> Vec<EngineConfig> {
         },
         EngineConfig {
             name: "stackoverflow".to_string(),
-            engine: "stackoverflow".to_string(),
+            engine: "stackexchange".to_string(),
             categories: vec!["it".to_string()],
             shortcut: "so".to_string(),
             ..Default::default()
@@ -510,4 +601,13 @@ mod tests {
         assert!(google.is_some());
         assert_eq!(google.unwrap().shortcut, "g");
     }
+
+    #[test]
+    fn test_engine_tokens() {
+        let config: EngineConfig =
+            serde_yaml::from_str("name: wiki\nengine: sqlite\ntokens: [s3cret]").unwrap();
+        assert!(config.is_accessible(&["other".to_string(), "s3cret".to_string()]));
+        assert!(!config.is_accessible(&["s3cre".to_string(), "s3cret!".to_string()]));
+        assert!(!config.is_accessible(&[]));
+    }
 }
@@ -1,153 +1,171 @@
 //! arXiv search engine implementation
 //!
 //! Uses the arXiv API to search for scholarly articles in physics, mathematics,
-//! computer science, and other fields.
+//! computer science, and other fields. Queries may use arXiv field prefixes
+//! (`au:`, `ti:`, `cat:`, ...); results are sorted by relevance unless
+//! `sort_by` and `sort_order` are configured.
 
+use super::syndication::{self, FeedEntry};
 use super::traits::*;
+use crate::config::EngineConfig;
+use crate::query::{OperatorKind, QueryOperator};
 use crate::results::{Result, ResultType};
 use anyhow::Result as AnyhowResult;
-use std::collections::HashMap;
+
+const ARXIV_NS: &str = "http://arxiv.org/schemas/atom";
+const OPENSEARCH_NS: &str = "http://a9.com/-/spec/opensearch/1.1/";
+
+/// Field prefixes understood by the arXiv query syntax
+const FIELD_PREFIXES: [&str; 9] = [
+    "all:", "ti:", "au:", "abs:", "co:", "jr:", "cat:", "rn:", "id:",
+];
 
 /// arXiv search engine for scientific papers
 pub struct ArXiv {
     api_url: String,
+    sort_by: &'static str,
+    sort_order: &'static str,
 }
 
 impl ArXiv {
     pub fn new() -> Self {
         Self {
             api_url: "https://export.arxiv.org/api/query".to_string(),
+            sort_by: "relevance",
+            sort_order: "descending",
         }
     }
 
-    /// Parse the Atom XML response
-    fn parse_atom_response(&self, xml: &str) -> Vec<Result> {
-        let mut results = Vec::new();
-
-        // Simple XML parsing without a full XML library
-        // Look for <entry> elements
-        let mut position = 1u32;
-
-        for entry_str in xml.split("<entry>").skip(1) {
-            let entry_end = match entry_str.find("</entry>") {
-                Some(pos) => pos,
-                None => continue,
-            };
-            let entry = &entry_str[..entry_end];
-
-            // Extract title
-            let title = Self::extract_tag(entry, "title")
-                .map(|t| t.replace('\n', " ").trim().to_string())
-                .unwrap_or_default();
+    /// Build a result from an Atom entry
+    fn entry_result(&self, entry: FeedEntry, position: u32) -> Option<Result> {
+        let title = entry.title.clone().filter(|t| !t.is_empty())?;
+        let url = entry.link().or(entry.id.as_deref())?.to_string();
 
-            if title.is_empty() {
-                continue;
-            }
+        let mut result = Result::new(url, title, self.name().to_string()).with_position(position);
+        result.result_type = ResultType::Paper;
 
-            // Extract URL (id)
-            let url = Self::extract_tag(entry, "id").unwrap_or_default();
+        if let Some(summary) = &entry.summary {
+            result = result.with_content(summary.clone());
+        }
 
-            if url.is_empty() {
-                continue;
+        // The primary category comes first, followed by the cross-lists
+        let primary = entry
+            .extension(ARXIV_NS, "primary_category")
+            .and_then(|c| c.attribute("term"))
+            .map(str::to_string);
+        let mut categories: Vec<String> = primary.into_iter().collect();
+        for category in &entry.categories {
+            if !categories.contains(category) {
+                categories.push(category.clone());
             }
+        }
 
-            // Extract summary/abstract
-            let abstract_text = Self::extract_tag(entry, "summary")
-                .map(|s| s.replace('\n', " ").trim().to_string());
+        let meta = &mut result.metadata;
+        meta.template = Some("paper.html".to_string());
+        meta.author = Some(entry.authors.join(", ")).filter(|a| !a.is_empty());
+        meta.doi = entry.extension_text(ARXIV_NS, "doi").map(str::to_string);
+        meta.journal = entry
+            .extension_text(ARXIV_NS, "journal_ref")
+            .map(str::to_string);
+        meta.comment = entry
+            .extension_text(ARXIV_NS, "comment")
+            .map(str::to_string);
+        meta.pdf_url = entry
+            .find_link(|l| l.title.as_deref() == Some("pdf"))
+            .map(|l| l.href.clone());
+        meta.published_date = entry.published;
+        meta.updated_date = entry.updated.map(format_date);
+        meta.open_access = true;
+        meta.tags = categories;
+        meta.authors = entry.authors;
+
+        Some(result)
+    }
 
-            // Extract authors
-            let authors: Vec<String> = entry
-                .split("<author>")
-                .skip(1)
-                .filter_map(|author_block| Self::extract_tag(author_block, "name"))
-                .collect();
+    /// Check if a query uses arXiv field prefixes (`ti:`, `au:`, ...)
+    fn has_field_prefix(query: &str) -> bool {
+        query.split_whitespace().any(|word| {
+            let word = word.trim_start_matches('(');
+            FIELD_PREFIXES.iter().any(|p| word.starts_with(p))
+        })
+    }
 
-            let author_str = if authors.is_empty() {
-                None
-            } else {
-                Some(authors.join(", "))
-            };
+    /// Build the `search_query` parameter
+    ///
+    /// Plain queries search all fields. In queries with field prefixes the
+    /// unprefixed terms are searched in all fields and implicitly ANDed, so
+    /// `cat:cs.CL transformers` becomes `cat:cs.CL AND all:transformers`.
+    fn search_query(query: &str) -> String {
+        if !Self::has_field_prefix(query) {
+            return format!("all:{}", query);
+        }
 
-            // Extract PDF link
-            let pdf_url = if entry.contains("title=\"pdf\"") {
-                // Find the href in the pdf link
-                let pdf_start = entry.find("title=\"pdf\"");
-                if let Some(start) = pdf_start {
-                    let before = &entry[..start];
-                    let href_start = before.rfind("href=\"");
-                    if let Some(hs) = href_start {
-                        let href_content = &before[hs + 6..];
-                        let href_end = href_content.find('"');
-                        href_end.map(|he| href_content[..he].to_string())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
+        let mut parts: Vec<String> = Vec::new();
+        let mut expect_term = true;
 
-            // Extract categories/tags
-            let categories: Vec<String> = entry
-                .split("<category term=\"")
-                .skip(1)
-                .filter_map(|cat| cat.find('"').map(|end| cat[..end].to_string()))
-                .collect();
-
-            // Extract published date
-            let published = Self::extract_tag(entry, "published");
-
-            // Build result
-            let mut result = Result::new(url, title, self.name().to_string());
-            result.result_type = ResultType::Paper;
-            result = result.with_position(position);
-
-            if let Some(abstract_text) = abstract_text {
-                // Truncate abstract if too long
-                let truncated = if abstract_text.len() > 300 {
-                    format!("{}...", &abstract_text[..300])
-                } else {
-                    abstract_text
-                };
-                result = result.with_content(truncated);
+        for token in Self::tokenize(query) {
+            if matches!(token.as_str(), "AND" | "OR" | "ANDNOT") {
+                parts.push(token);
+                expect_term = true;
+                continue;
             }
-
-            result.metadata.author = author_str;
-            result.metadata.published_date = published;
-            result.metadata.template = Some("paper.html".to_string());
-
-            // Store PDF URL in file_type field (repurposed)
-            if let Some(pdf) = pdf_url {
-                result.metadata.file_type = Some(format!("PDF: {}", pdf));
+            if !expect_term {
+                parts.push("AND".to_string());
             }
-
-            // Store categories
-            if !categories.is_empty() {
-                result.category = Some(categories.join(", "));
+            if Self::has_field_prefix(&token) {
+                parts.push(token);
+            } else {
+                let term = token.trim_start_matches('(');
+                let parens = &token[..token.len() - term.len()];
+                parts.push(format!("{}all:{}", parens, term));
             }
-
-            results.push(result);
-            position += 1;
+            expect_term = false;
         }
 
-        results
+        parts.join(" ")
     }
 
-    /// Extract text content from an XML tag
-    fn extract_tag(xml: &str, tag: &str) -> Option<String> {
-        let start_tag = format!("<{}", tag);
-        let end_tag = format!("</{}>", tag);
+    /// Split a query on whitespace, keeping quoted phrases together
+    fn tokenize(query: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut current = String::new();
+        let mut quoted = false;
+
+        for c in query.chars() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                    current.push(c);
+                }
+                c if c.is_whitespace() && !quoted => {
+                    if !current.is_empty() {
+                        tokens.push(std::mem::take(&mut current));
+                    }
+                }
+                c => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
 
-        let start = xml.find(&start_tag)?;
-        let content_start = xml[start..].find('>')? + start + 1;
-        let end = xml[content_start..].find(&end_tag)? + content_start;
+        tokens
+    }
 
-        Some(xml[content_start..end].to_string())
+    /// Quote a value for use after a field prefix
+    fn field_value(value: &str) -> String {
+        if value.contains(char::is_whitespace) {
+            format!("\"{}\"", value)
+        } else {
+            value.to_string()
+        }
     }
 }
 
+fn format_date(date: chrono::DateTime<chrono::Utc>) -> String {
+    date.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
 impl Default for ArXiv {
     fn default() -> Self {
         Self::new()
@@ -174,21 +192,85 @@ impl Engine for ArXiv {
         true
     }
 
-    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
-        let mut query_params = HashMap::new();
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        if let Some(sort_by) = config.extra_str("sort_by") {
+            self.sort_by = match sort_by.as_str() {
+                "relevance" => "relevance",
+                "submitted_date" => "submittedDate",
+                "last_updated_date" => "lastUpdatedDate",
+                other => {
+                    return Err(anyhow::anyhow!(
+                    "Unknown arXiv sort_by '{}' (expected relevance, submitted_date or last_updated_date)",
+                    other
+                ))
+                }
+            };
+        }
+        if let Some(sort_order) = config.extra_str("sort_order") {
+            self.sort_order = match sort_order.as_str() {
+                "ascending" => "ascending",
+                "descending" => "descending",
+                other => {
+                    return Err(anyhow::anyhow!(
+                        "Unknown arXiv sort_order '{}' (expected ascending or descending)",
+                        other
+                    ))
+                }
+            };
+        }
+        Ok(())
+    }
 
-        // Build search query with "all:" prefix to search all fields
-        query_params.insert("search_query".to_string(), format!("all:{}", params.query));
+    fn supported_operators(&self) -> Vec<OperatorKind> {
+        vec![
+            OperatorKind::InTitle,
+            OperatorKind::Phrase,
+            OperatorKind::Exclude,
+        ]
+    }
 
-        // Pagination
-        let start = (params.pageno - 1) * 10;
-        query_params.insert("start".to_string(), start.to_string());
-        query_params.insert("max_results".to_string(), "10".to_string());
+    fn format_query(
+        &self,
+        terms: &str,
+        operators: &[QueryOperator],
+    ) -> (String, Vec<QueryOperator>) {
+        // Terms stay unprefixed so `search_query` keeps their field prefixes
+        // and only searches the bare ones in all fields
+        let mut clauses = Vec::new();
+        if !terms.is_empty() {
+            clauses.push(terms.to_string());
+        }
+        let mut excluded = Vec::new();
 
-        let mut request = EngineRequest::get(&self.api_url);
-        request.params = query_params;
+        for op in operators {
+            match op {
+                QueryOperator::InTitle(text) => {
+                    clauses.push(format!("ti:{}", Self::field_value(text)))
+                }
+                QueryOperator::Phrase(phrase) => clauses.push(format!("all:\"{}\"", phrase)),
+                QueryOperator::Exclude(_) => excluded.push(op),
+                _ => {}
+            }
+        }
 
-        Ok(request)
+        let mut query = clauses.join(" AND ");
+        if query.is_empty() {
+            // ANDNOT needs a left-hand side; arXiv has no pure negation
+            return (query, excluded.into_iter().cloned().collect());
+        }
+        for op in excluded {
+            query = format!("{} ANDNOT all:{}", query, Self::field_value(op.value()));
+        }
+        (query, Vec::new())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Ok(EngineRequest::get(&self.api_url)
+            .param("search_query", Self::search_query(&params.query))
+            .param("start", ((params.pageno.max(1) - 1) * 10).to_string())
+            .param("max_results", "10")
+            .param("sortBy", self.sort_by)
+            .param("sortOrder", self.sort_order))
     }
 
     fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
@@ -196,9 +278,33 @@ impl Engine for ArXiv {
             return Err(anyhow::anyhow!("HTTP error: {}", response.status));
         }
 
-        let results = self.parse_atom_response(&response.text);
+        let feed = syndication::parse_feed(&response.text)?;
+
+        // Invalid queries are reported as a single entry with an error id
+        if let Some(error) = feed.entries.iter().find(|e| {
+            e.id.as_deref()
+                .is_some_and(|id| id.contains("arxiv.org/api/errors"))
+        }) {
+            return Err(anyhow::anyhow!(
+                "arXiv API error: {}",
+                error.summary.as_deref().unwrap_or("unknown error")
+            ));
+        }
+
+        let number_of_results = feed
+            .extension_text(OPENSEARCH_NS, "totalResults")
+            .and_then(|n| n.parse().ok());
 
-        Ok(EngineResults::with_results(results))
+        let mut results = Vec::new();
+        for entry in feed.entries {
+            if let Some(result) = self.entry_result(entry, results.len() as u32 + 1) {
+                results.push(result);
+            }
+        }
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = number_of_results;
+        Ok(engine_results)
     }
 }
 
@@ -218,19 +324,153 @@ mod tests {
             request.params.get("search_query"),
             Some(&"all:machine learning".to_string())
         );
+        assert_eq!(request.params.get("sortBy"), Some(&"relevance".to_string()));
+    }
+
+    #[test]
+    fn test_arxiv_sort_config() {
+        let mut arxiv = ArXiv::new();
+        let config: EngineConfig = serde_yaml::from_str(
+            "name: arxiv\nengine: arxiv\nsort_by: submitted_date\nsort_order: ascending",
+        )
+        .unwrap();
+        arxiv.init(&config).unwrap();
+
+        let request = arxiv.request(&RequestParams::new("qubits")).unwrap();
+        assert_eq!(
+            request.params.get("sortBy"),
+            Some(&"submittedDate".to_string())
+        );
+        assert_eq!(
+            request.params.get("sortOrder"),
+            Some(&"ascending".to_string())
+        );
+
+        let config: EngineConfig =
+            serde_yaml::from_str("name: arxiv\nengine: arxiv\nsort_by: citations").unwrap();
+        assert!(ArXiv::new().init(&config).is_err());
+    }
+
+    #[test]
+    fn test_arxiv_field_prefixes() {
+        assert_eq!(
+            ArXiv::search_query("au:hinton ti:\"deep learning\""),
+            r#"au:hinton AND ti:"deep learning""#
+        );
+        assert_eq!(
+            ArXiv::search_query("cat:cs.CL transformers"),
+            "cat:cs.CL AND all:transformers"
+        );
+        assert_eq!(
+            ArXiv::search_query("(au:lecun OR au:bengio) ANDNOT cat:stat.ML"),
+            "(au:lecun OR au:bengio) ANDNOT cat:stat.ML"
+        );
+    }
+
+    #[test]
+    fn test_arxiv_operators() {
+        let arxiv = ArXiv::new();
+        let (query, local) = arxiv.format_query(
+            "transformers",
+            &[
+                QueryOperator::InTitle("attention".to_string()),
+                QueryOperator::Phrase("self supervised".to_string()),
+                QueryOperator::Exclude("vision".to_string()),
+            ],
+        );
+        assert_eq!(
+            query,
+            r#"transformers AND ti:attention AND all:"self supervised" ANDNOT all:vision"#
+        );
+        assert!(local.is_empty());
+
+        // Only the bare terms of rewritten queries get a prefix
+        let request = arxiv.request(&RequestParams::new(query)).unwrap();
+        assert_eq!(
+            request.params.get("search_query").unwrap(),
+            r#"all:transformers AND ti:attention AND all:"self supervised" ANDNOT all:vision"#
+        );
+
+        // Without anything to exclude from, exclusions are filtered locally
+        let exclude = QueryOperator::Exclude("vision".to_string());
+        let (query, local) = arxiv.format_query("", std::slice::from_ref(&exclude));
+        assert_eq!(query, "");
+        assert_eq!(local, vec![exclude]);
     }
 
     #[test]
-    fn test_extract_tag() {
-        let xml = "<entry><title>Test Title</title><summary>Abstract text</summary></entry>";
+    fn test_arxiv_response() {
+        let arxiv = ArXiv::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/arxiv_search.xml"));
+        let engine_results = arxiv.response(response).unwrap();
+        assert_eq!(engine_results.number_of_results, Some(81234));
+
+        let results = engine_results.results;
+        assert_eq!(results.len(), 2);
+
+        let paper = &results[0];
+        assert_eq!(paper.url, "http://arxiv.org/abs/1706.03762v7");
+        assert_eq!(paper.title, "Attention Is All You Need");
+        assert!(paper
+            .content
+            .as_deref()
+            .unwrap()
+            .contains("neural networks & include"));
+        assert_eq!(
+            paper.metadata.authors,
+            vec!["Ashish Vaswani", "Noam Shazeer", "Niki Parmar"]
+        );
+        assert_eq!(paper.metadata.tags, vec!["cs.CL", "cs.LG"]);
         assert_eq!(
-            ArXiv::extract_tag(xml, "title"),
-            Some("Test Title".to_string())
+            paper.metadata.doi.as_deref(),
+            Some("10.48550/arXiv.1706.03762")
         );
         assert_eq!(
-            ArXiv::extract_tag(xml, "summary"),
-            Some("Abstract text".to_string())
+            paper.metadata.journal.as_deref(),
+            Some("Advances in Neural Information Processing Systems 30 (2017)")
         );
-        assert_eq!(ArXiv::extract_tag(xml, "missing"), None);
+        assert_eq!(
+            paper.metadata.comment.as_deref(),
+            Some("15 pages, 5 figures")
+        );
+        assert_eq!(
+            paper.metadata.pdf_url.as_deref(),
+            Some("http://arxiv.org/pdf/1706.03762v7")
+        );
+        assert_eq!(
+            paper.metadata.published_date,
+            "2017-06-12T17:57:34Z".parse().ok()
+        );
+        assert_eq!(
+            paper.metadata.updated_date.as_deref(),
+            Some("2023-08-02T00:41:18Z")
+        );
+        assert!(paper.metadata.open_access);
+
+        let second = &results[1];
+        assert_eq!(second.title, "Sparse <Attention> for Long Sequences");
+        assert_eq!(second.metadata.doi, None);
+        assert_eq!(second.metadata.tags, vec!["cs.LG"]);
+    }
+
+    #[test]
+    fn test_arxiv_error_response() {
+        let arxiv = ArXiv::new();
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <id>http://arxiv.org/api/errors#incorrect_id_format_for_1234</id>
+    <title>Error</title>
+    <summary>incorrect id format for 1234</summary>
+  </entry>
+</feed>"#;
+        let error = arxiv
+            .response(EngineResponse::new(200, xml))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("incorrect id format"));
+
+        assert!(arxiv
+            .response(EngineResponse::new(200, "<html>Maintenance</html>"))
+            .is_err());
     }
 }
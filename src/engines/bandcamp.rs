@@ -0,0 +1,241 @@
+//! Bandcamp search engine implementation
+//!
+//! Scrapes bandcamp.com's search page. Albums and tracks embed Bandcamp's
+//! player; artists and labels are plain results.
+
+use super::dates;
+use super::traits::*;
+use crate::results::Result;
+use anyhow::Result as AnyhowResult;
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+
+/// `data-search` attribute of a search result
+#[derive(Debug, Deserialize)]
+struct SearchData {
+    id: u64,
+    /// `a` for albums, `t` for tracks, `b` for artists and labels
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// Bandcamp search engine
+pub struct Bandcamp {
+    base_url: String,
+}
+
+impl Bandcamp {
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://bandcamp.com".to_string(),
+        }
+    }
+
+    fn text(element: &ElementRef, selector: &Selector) -> Option<String> {
+        let text = element
+            .select(selector)
+            .next()?
+            .text()
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(text).filter(|t| !t.is_empty())
+    }
+
+    fn embed_url(&self, data: &SearchData) -> Option<String> {
+        let kind = match data.kind.as_str() {
+            "a" => "album",
+            "t" => "track",
+            _ => return None,
+        };
+        Some(format!(
+            "{}/EmbeddedPlayer/{}={}/size=large/bgcol=ffffff/linkcol=0687f5/tracklist=false/artwork=small/transparent=true/",
+            self.base_url, kind, data.id
+        ))
+    }
+
+    fn parse_results(&self, html: &str) -> Vec<Result> {
+        let document = Html::parse_document(html);
+        let result_selector = Selector::parse("li.searchresult").unwrap();
+        let heading_selector = Selector::parse(".heading a").unwrap();
+        let subhead_selector = Selector::parse(".subhead").unwrap();
+        let itemtype_selector = Selector::parse(".itemtype").unwrap();
+        let released_selector = Selector::parse(".released").unwrap();
+        let length_selector = Selector::parse(".length").unwrap();
+        let tags_selector = Selector::parse(".tags").unwrap();
+        let art_selector = Selector::parse(".art img").unwrap();
+
+        let mut results = Vec::new();
+        for element in document.select(&result_selector) {
+            let Some(heading) = element.select(&heading_selector).next() else {
+                continue;
+            };
+            let Some(href) = heading.value().attr("href") else {
+                continue;
+            };
+            // Drop the search tracking parameters
+            let url = href.split('?').next().unwrap_or(href).to_string();
+            let title = heading.text().collect::<String>().trim().to_string();
+            if title.is_empty() {
+                continue;
+            }
+
+            let mut result = Result::new(url, title, self.name().to_string())
+                .with_position(results.len() as u32 + 1);
+
+            // "by Artist", or "from Album by Artist" for tracks
+            let subhead = Self::text(&element, &subhead_selector);
+            let author = subhead.as_deref().and_then(|s| {
+                s.strip_prefix("by ")
+                    .or_else(|| s.rsplit_once(" by ").map(|(_, artist)| artist))
+                    .map(str::to_string)
+            });
+            let content: Vec<String> = [
+                Self::text(&element, &itemtype_selector).map(|t| t.to_lowercase()),
+                subhead.filter(|s| s.starts_with("from ")),
+                Self::text(&element, &length_selector),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            if !content.is_empty() {
+                result = result.with_content(content.join(" · "));
+            }
+
+            let data = element
+                .value()
+                .attr("data-search")
+                .and_then(|d| serde_json::from_str::<SearchData>(d).ok());
+
+            let meta = &mut result.metadata;
+            meta.template = Some("audio.html".to_string());
+            meta.author = author;
+            meta.thumbnail = element
+                .select(&art_selector)
+                .next()
+                .and_then(|img| img.value().attr("src"))
+                .map(str::to_string);
+            meta.published_date = Self::text(&element, &released_selector)
+                .and_then(|r| dates::parse(r.trim_start_matches("released ")));
+            meta.tags = Self::text(&element, &tags_selector)
+                .map(|t| {
+                    t.trim_start_matches("tags:")
+                        .split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+            meta.iframe_src = data.and_then(|d| self.embed_url(&d));
+
+            results.push(result);
+        }
+
+        results
+    }
+}
+
+impl Default for Bandcamp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Bandcamp {
+    fn name(&self) -> &str {
+        "bandcamp"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://bandcamp.com")
+            .official_api(false)
+            .results_format("HTML")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["music"]
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Ok(EngineRequest::get(format!("{}/search", self.base_url))
+            .param("q", &params.query)
+            .param("page", params.pageno.max(1).to_string()))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        Ok(EngineResults::with_results(
+            self.parse_results(&response.text),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bandcamp_request() {
+        let engine = Bandcamp::new();
+        let mut params = RequestParams::new("ambient");
+        params.pageno = 2;
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://bandcamp.com/search");
+        assert_eq!(request.params.get("q"), Some(&"ambient".to_string()));
+        assert_eq!(request.params.get("page"), Some(&"2".to_string()));
+    }
+
+    #[test]
+    fn test_bandcamp_response() {
+        let engine = Bandcamp::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/bandcamp_search.html"));
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.results.len(), 3);
+
+        let album = &results.results[0];
+        assert_eq!(album.url, "https://artist.bandcamp.com/album/drift");
+        assert_eq!(album.title, "Drift");
+        assert_eq!(album.metadata.author.as_deref(), Some("Some Artist"));
+        assert_eq!(
+            album.content.as_deref(),
+            Some("album · 10 tracks, 45 minutes")
+        );
+        assert_eq!(
+            album.metadata.published_date,
+            "2020-03-01T00:00:00Z".parse().ok()
+        );
+        assert_eq!(album.metadata.tags, vec!["ambient", "electronic"]);
+        assert_eq!(
+            album.metadata.thumbnail.as_deref(),
+            Some("https://f4.bcbits.com/img/a123_7.jpg")
+        );
+        assert!(album
+            .metadata
+            .iframe_src
+            .as_deref()
+            .unwrap()
+            .starts_with("https://bandcamp.com/EmbeddedPlayer/album=123/"));
+
+        let track = &results.results[1];
+        assert_eq!(track.metadata.author.as_deref(), Some("Some Artist"));
+        assert_eq!(
+            track.content.as_deref(),
+            Some("track · from Drift by Some Artist")
+        );
+        assert!(track
+            .metadata
+            .iframe_src
+            .as_deref()
+            .unwrap()
+            .contains("/track=456/"));
+
+        // Artists have no player
+        assert_eq!(results.results[2].metadata.iframe_src, None);
+    }
+}
@@ -1,7 +1,10 @@
 //! Bing search engine implementation
 
+use super::dates;
+use super::media;
 use super::traits::*;
-use crate::results::Result;
+use crate::query::OperatorKind;
+use crate::results::{Result, ResultType};
 use anyhow::Result as AnyhowResult;
 use scraper::{Html, Selector};
 use std::collections::HashMap;
@@ -140,6 +143,10 @@ impl Engine for Bing {
         "bing"
     }
 
+    fn languages(&self) -> Vec<&str> {
+        crate::locales::language_codes()
+    }
+
     fn about(&self) -> EngineAbout {
         EngineAbout::new()
             .website("https://www.bing.com")
@@ -163,6 +170,11 @@ impl Engine for Bing {
         true
     }
 
+    fn supported_operators(&self) -> Vec<OperatorKind> {
+        // Standard web search syntax is passed through unchanged
+        OperatorKind::all()
+    }
+
     fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
         let mut query_params = HashMap::new();
         query_params.insert("q".to_string(), params.query.clone());
@@ -316,6 +328,131 @@ impl Engine for BingImages {
     }
 }
 
+/// Bing News search engine
+pub struct BingNews {
+    base_url: String,
+}
+
+impl BingNews {
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://www.bing.com".to_string(),
+        }
+    }
+
+    fn parse_results(&self, html: &str) -> Vec<Result> {
+        let document = Html::parse_fragment(html);
+        let result_selector = Selector::parse("div.newsitem").unwrap();
+        let snippet_selector = Selector::parse("div.snippet").unwrap();
+        // Relative dates such as "3h" or "2d"
+        let age_selector = Selector::parse("div.source span[aria-label]").unwrap();
+        let image_selector = Selector::parse("a.imagelink img").unwrap();
+
+        let mut results = Vec::new();
+        for element in document.select(&result_selector) {
+            let item = element.value();
+            let (Some(url), Some(title)) = (item.attr("data-url"), item.attr("data-title")) else {
+                continue;
+            };
+
+            let mut result =
+                Result::new(url.to_string(), title.to_string(), self.name().to_string())
+                    .with_position(results.len() as u32 + 1);
+            result.result_type = ResultType::News;
+            if let Some(snippet) = element.select(&snippet_selector).next() {
+                let snippet = snippet.text().collect::<String>().trim().to_string();
+                if !snippet.is_empty() {
+                    result = result.with_content(snippet);
+                }
+            }
+
+            let meta = &mut result.metadata;
+            meta.template = Some("news.html".to_string());
+            meta.author = item.attr("data-author").map(str::to_string);
+            meta.published_date = element
+                .select(&age_selector)
+                .next()
+                .and_then(|age| age.value().attr("aria-label"))
+                .and_then(dates::parse);
+            // Images load lazily; the `src` is often a placeholder
+            meta.thumbnail = element.select(&image_selector).next().and_then(|img| {
+                ["data-src-hq", "data-src", "src"]
+                    .iter()
+                    .filter_map(|attr| img.value().attr(attr))
+                    .find(|src| !src.starts_with("data:"))
+                    .map(|src| media::absolute_url(&self.base_url, src))
+            });
+
+            results.push(result);
+        }
+
+        results
+    }
+}
+
+impl Default for BingNews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for BingNews {
+    fn name(&self) -> &str {
+        "bing_news"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://www.bing.com/news")
+            .official_api(false)
+            .results_format("HTML")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["news"]
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let page = params.pageno.max(1);
+        let mut request = EngineRequest::get(format!("{}/news/infinitescrollajax", self.base_url))
+            .param("q", &params.query)
+            .param("InfiniteScroll", "1")
+            .param("first", ((page - 1) * 10 + 1).to_string())
+            .param("SFX", page.to_string())
+            .param("form", "PTFTNR");
+        if !params.lang.is_empty() && params.lang != "all" {
+            request = request.param("setlang", &params.lang);
+        }
+
+        // Bing News has no filter for the past year
+        let interval = match params.time_range {
+            Some(crate::query::TimeRange::Day) => Some("4"),
+            Some(crate::query::TimeRange::Week) => Some("7"),
+            Some(crate::query::TimeRange::Month) => Some("9"),
+            _ => None,
+        };
+        if let Some(interval) = interval {
+            request = request.param("qft", format!("interval=\"{}\"", interval));
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        Ok(EngineResults::with_results(
+            self.parse_results(&response.text),
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,4 +466,46 @@ mod tests {
         assert!(request.url.contains("bing.com"));
         assert!(request.params.contains_key("q"));
     }
+
+    #[test]
+    fn test_bing_news_request() {
+        let bing = BingNews::new();
+        let mut params = RequestParams::new("rust");
+        params.pageno = 3;
+        params.time_range = Some(crate::query::TimeRange::Week);
+        let request = bing.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://www.bing.com/news/infinitescrollajax");
+        assert_eq!(request.params.get("first"), Some(&"21".to_string()));
+        assert_eq!(
+            request.params.get("qft"),
+            Some(&"interval=\"7\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bing_news_response() {
+        let bing = BingNews::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/bing_news.html"));
+        let results = bing.response(response).unwrap();
+        assert_eq!(results.results.len(), 2);
+
+        let article = &results.results[0];
+        assert_eq!(article.url, "https://www.example.com/news/rust-2024");
+        assert_eq!(article.title, "Rust 2024 edition ships");
+        assert_eq!(article.result_type, ResultType::News);
+        assert_eq!(article.metadata.author.as_deref(), Some("Example News"));
+        assert_eq!(
+            article.content.as_deref(),
+            Some("The new edition brings async closures.")
+        );
+        assert_eq!(
+            article.metadata.thumbnail.as_deref(),
+            Some("https://www.bing.com/th?id=OVFT.abc&pid=News")
+        );
+        let age = chrono::Utc::now() - article.metadata.published_date.unwrap();
+        assert!(age >= chrono::Duration::hours(3) && age < chrono::Duration::hours(4));
+
+        assert_eq!(results.results[1].metadata.thumbnail, None);
+    }
 }
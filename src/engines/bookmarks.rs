@@ -0,0 +1,650 @@
+//! Bookmarks engine implementation
+//!
+//! Searches bookmark files exported from browsers in the Netscape HTML format
+//! and, optionally, a Firefox `places.sqlite` database. Titles, URLs, tags,
+//! descriptions and folder paths are matched against the query. The files
+//! are reloaded when they change, checked every `refresh_interval` seconds.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::Result;
+use anyhow::Result as AnyhowResult;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+
+/// Default refresh interval in seconds
+const DEFAULT_REFRESH_INTERVAL: u64 = 60;
+
+/// Default weight, so curated links outrank web results
+const DEFAULT_WEIGHT: f64 = 3.0;
+
+/// Folder separator in displayed folder paths
+const FOLDER_SEPARATOR: &str = " › ";
+
+/// Indexed bookmark
+#[derive(Debug, Clone, Default)]
+struct Bookmark {
+    url: String,
+    title: String,
+    description: Option<String>,
+    tags: Vec<String>,
+    /// Folders from the top level down
+    folders: Vec<String>,
+    added: Option<DateTime<Utc>>,
+}
+
+impl Bookmark {
+    /// Keyword relevance: every term must match somewhere
+    fn relevance(&self, terms: &[String]) -> f64 {
+        let title = self.title.to_lowercase();
+        let tags: Vec<String> = self.tags.iter().map(|t| t.to_lowercase()).collect();
+        let url = self.url.to_lowercase();
+        let rest = format!(
+            "{} {}",
+            self.folders.join(" "),
+            self.description.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+
+        let mut relevance = 0.0;
+        for term in terms {
+            relevance += if tags.iter().any(|t| t == term) {
+                3.0
+            } else if title.contains(term.as_str()) {
+                2.0
+            } else if url.contains(term.as_str()) || rest.contains(term.as_str()) {
+                1.0
+            } else {
+                return 0.0;
+            };
+        }
+        relevance
+    }
+}
+
+/// Loaded bookmarks and the modification times of their files
+#[derive(Debug, Default)]
+struct Index {
+    bookmarks: Vec<Bookmark>,
+    mtimes: HashMap<PathBuf, SystemTime>,
+}
+
+/// Search engine over bookmark exports
+pub struct Bookmarks {
+    name: String,
+    /// Netscape HTML bookmark files
+    files: Vec<PathBuf>,
+    /// Firefox `places.sqlite` database
+    places: Option<PathBuf>,
+    refresh_interval: Duration,
+    index: RwLock<Index>,
+}
+
+impl Bookmarks {
+    pub fn new() -> Self {
+        Self {
+            name: "bookmarks".to_string(),
+            files: Vec::new(),
+            places: None,
+            refresh_interval: Duration::from_secs(DEFAULT_REFRESH_INTERVAL),
+            index: RwLock::new(Index::default()),
+        }
+    }
+
+    /// All configured files
+    fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter().chain(&self.places)
+    }
+
+    /// Current modification times of the files
+    fn mtimes(&self) -> AnyhowResult<HashMap<PathBuf, SystemTime>> {
+        let mut mtimes = self
+            .paths()
+            .map(|path| {
+                let mtime = std::fs::metadata(path)
+                    .and_then(|m| m.modified())
+                    .map_err(|e| anyhow::anyhow!("Cannot read '{}': {}", path.display(), e))?;
+                Ok((path.clone(), mtime))
+            })
+            .collect::<AnyhowResult<HashMap<_, _>>>()?;
+
+        // Firefox writes new bookmarks to the write-ahead log first
+        if let Some(wal) = self.places.as_deref().map(wal_path) {
+            if let Ok(mtime) = std::fs::metadata(&wal).and_then(|m| m.modified()) {
+                mtimes.insert(wal, mtime);
+            }
+        }
+        Ok(mtimes)
+    }
+
+    /// Reload all files if any of them changed
+    fn reload(&self) -> AnyhowResult<()> {
+        let mtimes = self.mtimes()?;
+        if self.index.read().unwrap().mtimes == mtimes {
+            return Ok(());
+        }
+
+        let mut bookmarks = Vec::new();
+        for path in &self.files {
+            let html = std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Cannot read '{}': {}", path.display(), e))?;
+            bookmarks.extend(parse_netscape(&html));
+        }
+        if let Some(path) = &self.places {
+            bookmarks.extend(
+                read_places(path)
+                    .map_err(|e| anyhow::anyhow!("Cannot read '{}': {}", path.display(), e))?,
+            );
+        }
+
+        // The same link is often exported into several files
+        let mut seen = HashSet::new();
+        bookmarks.retain(|b| seen.insert(b.url.clone()));
+
+        *self.index.write().unwrap() = Index { bookmarks, mtimes };
+        Ok(())
+    }
+
+    fn bookmark_result(&self, bookmark: &Bookmark, position: u32) -> Result {
+        let mut result = Result::new(
+            bookmark.url.clone(),
+            bookmark.title.clone(),
+            self.name.clone(),
+        )
+        .with_position(position);
+
+        let folder = Some(bookmark.folders.join(FOLDER_SEPARATOR)).filter(|f| !f.is_empty());
+        if let Some(content) = bookmark.description.clone().or(folder) {
+            result = result.with_content(content);
+        }
+        result.metadata.tags = bookmark.tags.clone();
+        result.metadata.published_date = bookmark.added;
+        result
+    }
+}
+
+/// Parse a Netscape HTML bookmark file
+///
+/// Folders are `<DT><H3>` headings followed by a nested `<DL>`; bookmarks
+/// are `<DT><A>` links, optionally followed by a `<DD>` description.
+fn parse_netscape(html: &str) -> Vec<Bookmark> {
+    let document = Html::parse_document(html);
+    let links = Selector::parse("a[href]").unwrap();
+
+    document
+        .select(&links)
+        .filter_map(|link| {
+            let url = link.value().attr("href")?.trim().to_string();
+            // Bookmarklets and browser-internal pages can't be opened from results
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return None;
+            }
+            let title = Some(element_text(link))
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| url.clone());
+
+            let tags = link
+                .value()
+                .attr("tags")
+                .into_iter()
+                .flat_map(|tags| tags.split(','))
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+            let added = link
+                .value()
+                .attr("add_date")
+                .and_then(|d| d.trim().parse().ok())
+                .and_then(|secs| DateTime::from_timestamp(secs, 0));
+
+            // The <DD> description follows the link's <DT>
+            let dt = link.parent().and_then(ElementRef::wrap);
+            let description = dt
+                .and_then(|dt| dt.next_siblings().find_map(ElementRef::wrap))
+                .filter(|e| e.value().name() == "dd")
+                .map(element_text)
+                .filter(|d| !d.is_empty());
+
+            Some(Bookmark {
+                url,
+                title,
+                description,
+                tags,
+                folders: folders(link),
+                added,
+            })
+        })
+        .collect()
+}
+
+/// Folder path of a link: the headings of the `<DT>`s enclosing its `<DL>`s
+fn folders(link: ElementRef) -> Vec<String> {
+    let mut folders: Vec<String> = link
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().name() == "dl")
+        .filter_map(|dl| {
+            let heading = dl
+                .prev_siblings()
+                .filter_map(ElementRef::wrap)
+                .find(|e| e.value().name() == "h3")?;
+            Some(element_text(heading))
+        })
+        .collect();
+    folders.reverse();
+    folders
+}
+
+fn element_text(element: ElementRef) -> String {
+    element
+        .text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Bookmark row of `moz_bookmarks`
+struct PlacesRow {
+    id: i64,
+    kind: i64,
+    parent: i64,
+    title: Option<String>,
+    guid: String,
+    url: Option<String>,
+    added: Option<i64>,
+}
+
+/// Write-ahead log of an SQLite database
+fn wal_path(path: &Path) -> PathBuf {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    PathBuf::from(wal)
+}
+
+/// Read the bookmarks of a Firefox `places.sqlite` database
+///
+/// Firefox stores tags as folders below the tags root, holding a bookmark
+/// per tagged page.
+fn read_places(path: &Path) -> AnyhowResult<Vec<Bookmark>> {
+    // Firefox keeps the database locked while it runs, and recent changes
+    // only live in its write-ahead log; read a copy of both
+    let dir = tempfile::tempdir()?;
+    let copy = dir.path().join("places.sqlite");
+    std::fs::copy(path, &copy)?;
+    let wal = wal_path(path);
+    if wal.exists() {
+        std::fs::copy(&wal, wal_path(&copy))?;
+    }
+    let connection = Connection::open(&copy)?;
+    let mut statement = connection.prepare(
+        "SELECT b.id, b.type, b.parent, b.title, b.guid, p.url, b.dateAdded
+         FROM moz_bookmarks b LEFT JOIN moz_places p ON b.fk = p.id",
+    )?;
+    let rows: Vec<PlacesRow> = statement
+        .query_map([], |row| {
+            Ok(PlacesRow {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                parent: row.get(2)?,
+                title: row.get(3)?,
+                guid: row.get(4)?,
+                url: row.get(5)?,
+                added: row.get(6)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let by_id: HashMap<i64, &PlacesRow> = rows.iter().map(|r| (r.id, r)).collect();
+    let tags_root = rows
+        .iter()
+        .find(|r| r.guid == "tagsroot________")
+        .map(|r| r.id);
+    let is_tag_folder = |id: i64| by_id.get(&id).is_some_and(|r| Some(r.parent) == tags_root);
+
+    // Tags per URL
+    let mut tags: HashMap<&str, Vec<String>> = HashMap::new();
+    for row in rows
+        .iter()
+        .filter(|r| r.kind == 1 && is_tag_folder(r.parent))
+    {
+        if let (Some(url), Some(tag)) = (&row.url, by_id[&row.parent].title.as_ref()) {
+            tags.entry(url).or_default().push(tag.clone());
+        }
+    }
+
+    let folders = |mut id: i64| {
+        let mut folders = Vec::new();
+        // The root folder is its own parent's child 0
+        while let Some(folder) = by_id.get(&id).filter(|f| f.parent != 0) {
+            if let Some(title) = folder.title.as_ref().filter(|t| !t.is_empty()) {
+                folders.push(title.clone());
+            }
+            id = folder.parent;
+        }
+        folders.reverse();
+        folders
+    };
+
+    Ok(rows
+        .iter()
+        .filter(|r| r.kind == 1 && !is_tag_folder(r.parent))
+        .filter_map(|row| {
+            let url = row.url.clone()?;
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return None;
+            }
+            Some(Bookmark {
+                title: row
+                    .title
+                    .clone()
+                    .filter(|t| !t.is_empty())
+                    .unwrap_or_else(|| url.clone()),
+                tags: tags.get(url.as_str()).cloned().unwrap_or_default(),
+                folders: folders(row.parent),
+                // Microseconds since the epoch
+                added: row
+                    .added
+                    .and_then(|us| DateTime::from_timestamp(us / 1_000_000, 0)),
+                description: None,
+                url,
+            })
+        })
+        .collect())
+}
+
+impl Default for Bookmarks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Bookmarks {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new().results_format("local")
+    }
+
+    fn weight(&self) -> f64 {
+        DEFAULT_WEIGHT
+    }
+
+    fn engine_type(&self) -> EngineType {
+        EngineType::Offline
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        self.files = config.extra_value("files").unwrap_or_default();
+        self.places = config.extra_value("places");
+        if self.files.is_empty() && self.places.is_none() {
+            return Err(anyhow::anyhow!(
+                "Bookmarks engine '{}' needs `files` or `places`",
+                config.name
+            ));
+        }
+        if let Some(secs) = config.extra_value::<u64>("refresh_interval") {
+            self.refresh_interval = Duration::from_secs(secs.max(1));
+        }
+        self.reload()
+    }
+
+    fn search(&self, params: &RequestParams) -> AnyhowResult<EngineResults> {
+        let terms: Vec<String> = params
+            .query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        if terms.is_empty() {
+            return Ok(EngineResults::new());
+        }
+
+        let index = self.index.read().unwrap();
+        let mut matches: Vec<(f64, &Bookmark)> = index
+            .bookmarks
+            .iter()
+            .filter_map(|b| {
+                let relevance = b.relevance(&terms);
+                (relevance > 0.0).then_some((relevance, b))
+            })
+            .collect();
+        // Stable, so equally relevant bookmarks keep their file order
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let per_page = self.results_per_page() as usize;
+        let offset = (params.pageno.max(1) as usize - 1) * per_page;
+        let total = matches.len();
+        let results = matches
+            .into_iter()
+            .skip(offset)
+            .take(per_page)
+            .enumerate()
+            .map(|(i, (_, b))| self.bookmark_result(b, (offset + i) as u32 + 1))
+            .collect();
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = Some(total as u64);
+        Ok(engine_results)
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        Some(self.refresh_interval)
+    }
+
+    fn refresh(&self, _responses: Vec<AnyhowResult<EngineResponse>>) -> AnyhowResult<()> {
+        // Keeps the last loaded bookmarks on failure
+        self.reload()
+    }
+
+    fn request(&self, _params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Err(anyhow::anyhow!("The bookmarks engine searches local files"))
+    }
+
+    fn response(&self, _response: EngineResponse) -> AnyhowResult<EngineResults> {
+        Err(anyhow::anyhow!("The bookmarks engine searches local files"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmarks(files: &[&Path], places: Option<&Path>) -> Bookmarks {
+        let mut config: EngineConfig =
+            serde_yaml::from_str("name: team\nengine: bookmarks").unwrap();
+        let files: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+        config
+            .extra
+            .insert("files".to_string(), serde_yaml::to_value(files).unwrap());
+        if let Some(places) = places {
+            config
+                .extra
+                .insert("places".to_string(), places.display().to_string().into());
+        }
+
+        let mut engine = Bookmarks::new();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    fn titles(results: &EngineResults) -> Vec<&str> {
+        results.results.iter().map(|r| r.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_netscape() {
+        let bookmarks = parse_netscape(include_str!("fixtures/bookmarks.html"));
+        let urls: Vec<&str> = bookmarks.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://doc.rust-lang.org/book/",
+                "https://tokio.rs/tokio/tutorial",
+                "https://grafana.example.org/d/deploys",
+                "https://news.ycombinator.com/",
+            ]
+        );
+
+        let tokio = &bookmarks[1];
+        assert_eq!(tokio.title, "Tokio tutorial");
+        assert_eq!(tokio.folders, vec!["Engineering", "Rust"]);
+        assert_eq!(tokio.tags, vec!["async", "rust"]);
+        assert_eq!(
+            tokio.description.as_deref(),
+            Some("Learn async Rust with Tokio")
+        );
+        assert_eq!(
+            tokio.added.map(|d| d.to_rfc3339()).as_deref(),
+            Some("2023-11-14T22:13:20+00:00")
+        );
+        assert_eq!(bookmarks[2].folders, vec!["Engineering", "Dashboards"]);
+        assert!(bookmarks[3].folders.is_empty());
+    }
+
+    #[test]
+    fn test_bookmarks_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("bookmarks.html");
+        std::fs::write(&file, include_str!("fixtures/bookmarks.html")).unwrap();
+        let engine = bookmarks(&[&file], None);
+
+        // Tags outrank title matches
+        let results = engine.search(&RequestParams::new("rust")).unwrap();
+        assert_eq!(titles(&results), vec!["Tokio tutorial", "The Rust Book"]);
+        assert_eq!(
+            results.results[1].content.as_deref(),
+            Some("Engineering › Rust")
+        );
+
+        // Folders and URLs match too, and every term must match
+        let results = engine
+            .search(&RequestParams::new("dashboards grafana"))
+            .unwrap();
+        assert_eq!(titles(&results), vec!["Deploys"]);
+        let results = engine.search(&RequestParams::new("rust grafana")).unwrap();
+        assert!(results.results.is_empty());
+
+        assert_eq!(engine.weight(), DEFAULT_WEIGHT);
+    }
+
+    #[test]
+    fn test_bookmarks_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("bookmarks.html");
+        std::fs::write(&file, include_str!("fixtures/bookmarks.html")).unwrap();
+        let engine = bookmarks(&[&file], None);
+
+        std::fs::write(
+            &file,
+            r#"<DL><p><DT><A HREF="https://docs.rs/" TAGS="rust">Docs.rs</A></DL>"#,
+        )
+        .unwrap();
+        // Make sure the modification time differs on coarse file systems
+        let mtime = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+        engine.refresh(vec![]).unwrap();
+
+        let results = engine.search(&RequestParams::new("rust")).unwrap();
+        assert_eq!(titles(&results), vec!["Docs.rs"]);
+
+        // A file that can't be read keeps the loaded bookmarks
+        std::fs::remove_file(&file).unwrap();
+        assert!(engine.refresh(vec![]).is_err());
+        let results = engine.search(&RequestParams::new("rust")).unwrap();
+        assert_eq!(titles(&results), vec!["Docs.rs"]);
+    }
+
+    #[test]
+    fn test_firefox_places() {
+        let dir = tempfile::tempdir().unwrap();
+        let places = dir.path().join("places.sqlite");
+        Connection::open(&places)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT);
+                 CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER,
+                   parent INTEGER, title TEXT, guid TEXT, dateAdded INTEGER);
+                 INSERT INTO moz_places VALUES (1, 'https://crates.io/'), (2, 'place:sort=8');
+                 INSERT INTO moz_bookmarks VALUES
+                   (1, 2, NULL, 0, '', 'root________', 0),
+                   (2, 2, NULL, 1, 'toolbar', 'toolbar_____', 0),
+                   (3, 2, NULL, 1, 'tags', 'tagsroot________', 0),
+                   (4, 2, NULL, 2, 'Rust', 'folder000001', 0),
+                   (5, 1, 1, 4, 'crates.io', 'bookmark0001', 1700000000000000),
+                   (6, 2, NULL, 3, 'registry', 'tag000000001', 0),
+                   (7, 1, 1, 6, NULL, 'tagentry0001', 0),
+                   (8, 1, 2, 2, 'Most visited', 'smartbmark01', 0);",
+            )
+            .unwrap();
+
+        let engine = bookmarks(&[], Some(&places));
+        let results = engine.search(&RequestParams::new("registry")).unwrap();
+        assert_eq!(titles(&results), vec!["crates.io"]);
+        let result = &results.results[0];
+        assert_eq!(result.url, "https://crates.io/");
+        assert_eq!(result.metadata.tags, vec!["registry"]);
+        assert_eq!(result.content.as_deref(), Some("toolbar › Rust"));
+        assert_eq!(
+            result.metadata.published_date,
+            "2023-11-14T22:13:20Z".parse().ok()
+        );
+        assert_eq!(engine.index.read().unwrap().bookmarks.len(), 1);
+    }
+
+    #[test]
+    fn test_firefox_places_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let places = dir.path().join("places.sqlite");
+        // Like a running Firefox: changes stay in the write-ahead log
+        let firefox = Connection::open(&places).unwrap();
+        firefox
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                 PRAGMA wal_autocheckpoint = 0;
+                 CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT);
+                 CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER,
+                   parent INTEGER, title TEXT, guid TEXT, dateAdded INTEGER);
+                 INSERT INTO moz_places VALUES (1, 'https://crates.io/');
+                 INSERT INTO moz_bookmarks VALUES
+                   (1, 2, NULL, 0, '', 'root________', 0),
+                   (2, 1, 1, 1, 'crates.io', 'bookmark0001', 0);",
+            )
+            .unwrap();
+        assert!(wal_path(&places).exists());
+
+        let engine = bookmarks(&[], Some(&places));
+        let results = engine.search(&RequestParams::new("crates")).unwrap();
+        assert_eq!(titles(&results), vec!["crates.io"]);
+
+        // A change to the log alone is picked up
+        firefox
+            .execute_batch(
+                "INSERT INTO moz_places VALUES (2, 'https://docs.rs/');
+                 INSERT INTO moz_bookmarks VALUES (3, 1, 2, 1, 'Docs.rs', 'bookmark0002', 0);",
+            )
+            .unwrap();
+        let mtime = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(wal_path(&places))
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+        engine.refresh(vec![]).unwrap();
+        let results = engine.search(&RequestParams::new("docs")).unwrap();
+        assert_eq!(titles(&results), vec!["Docs.rs"]);
+    }
+}
@@ -1,9 +1,13 @@
 //! Brave search engine implementation
 
+use super::dates;
+use super::media;
 use super::traits::*;
-use crate::results::Result;
+use crate::query::OperatorKind;
+use crate::results::{Result, ResultType};
 use anyhow::Result as AnyhowResult;
 use scraper::{Html, Selector};
+use serde::Deserialize;
 use std::collections::HashMap;
 
 /// Brave web search engine
@@ -131,6 +135,11 @@ impl Engine for Brave {
         true
     }
 
+    fn supported_operators(&self) -> Vec<OperatorKind> {
+        // Standard web search syntax is passed through unchanged
+        OperatorKind::all()
+    }
+
     fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
         let mut query_params = HashMap::new();
         query_params.insert("q".to_string(), params.query.clone());
@@ -183,6 +192,265 @@ impl Engine for Brave {
     }
 }
 
+/// `GET /api/images` response
+#[derive(Debug, Deserialize)]
+struct ImagesResponse {
+    #[serde(default)]
+    results: Vec<ImageResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageResult {
+    title: String,
+    /// Page the image is on
+    url: String,
+    source: Option<String>,
+    thumbnail: Option<Thumbnail>,
+    properties: ImageProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct Thumbnail {
+    src: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageProperties {
+    /// Full size image
+    url: String,
+    width: Option<u64>,
+    height: Option<u64>,
+    format: Option<String>,
+}
+
+/// Brave Images search engine
+pub struct BraveImages {
+    base_url: String,
+}
+
+impl BraveImages {
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://search.brave.com/api/images".to_string(),
+        }
+    }
+
+    fn image_result(&self, image: ImageResult, position: u32) -> Result {
+        let mut result =
+            Result::new(image.url, image.title, self.name().to_string()).with_position(position);
+        result.result_type = ResultType::Image;
+
+        let properties = image.properties;
+        let meta = &mut result.metadata;
+        meta.template = Some("images.html".to_string());
+        meta.img_src = Some(properties.url);
+        meta.thumbnail = image.thumbnail.map(|t| t.src);
+        meta.resolution = properties
+            .width
+            .zip(properties.height)
+            .map(|(w, h)| media::format_resolution(w, h));
+        meta.file_type = properties.format.filter(|f| !f.is_empty());
+        meta.author = image.source.filter(|s| !s.is_empty());
+        result
+    }
+}
+
+impl Default for BraveImages {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for BraveImages {
+    fn name(&self) -> &str {
+        "brave_images"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://search.brave.com/images")
+            .official_api(false)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["images"]
+    }
+
+    fn supports_paging(&self) -> bool {
+        false
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let safesearch_cookie = match params.safesearch {
+            2 => "strict",
+            1 => "moderate",
+            _ => "off",
+        };
+        Ok(EngineRequest::get(&self.base_url)
+            .param("q", &params.query)
+            .param("source", "web")
+            .cookie("safesearch", safesearch_cookie)
+            .cookie("useLocation", "0"))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: ImagesResponse = response.json()?;
+        let results = data
+            .results
+            .into_iter()
+            .enumerate()
+            .map(|(i, image)| self.image_result(image, i as u32 + 1))
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+/// Brave News search engine
+pub struct BraveNews {
+    base_url: String,
+}
+
+impl BraveNews {
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://search.brave.com/news".to_string(),
+        }
+    }
+
+    fn parse_results(&self, html: &str) -> Vec<Result> {
+        let document = Html::parse_document(html);
+        let result_selector = Selector::parse(r#"div.snippet[data-type="news"]"#).unwrap();
+        let link_selector = Selector::parse("a.result-header").unwrap();
+        let title_selector = Selector::parse(".snippet-title").unwrap();
+        let description_selector = Selector::parse(".snippet-description, p.desc").unwrap();
+        let source_selector = Selector::parse(".netloc").unwrap();
+        // Relative dates such as "3 hours ago"
+        let age_selector = Selector::parse(".age").unwrap();
+        let thumbnail_selector = Selector::parse(".thumbnail img").unwrap();
+
+        let text_of = |element: &scraper::ElementRef, selector: &Selector| {
+            element
+                .select(selector)
+                .next()
+                .map(|e| e.text().collect::<String>().trim().to_string())
+                .filter(|t| !t.is_empty())
+        };
+
+        let mut results = Vec::new();
+        for element in document.select(&result_selector) {
+            let Some(url) = element
+                .select(&link_selector)
+                .next()
+                .and_then(|a| a.value().attr("href"))
+            else {
+                continue;
+            };
+            let Some(title) = text_of(&element, &title_selector) else {
+                continue;
+            };
+
+            let mut result = Result::new(url.to_string(), title, self.name().to_string())
+                .with_position(results.len() as u32 + 1);
+            result.result_type = ResultType::News;
+            if let Some(description) = text_of(&element, &description_selector) {
+                result = result.with_content(description);
+            }
+
+            let meta = &mut result.metadata;
+            meta.template = Some("news.html".to_string());
+            meta.author = text_of(&element, &source_selector);
+            meta.published_date = text_of(&element, &age_selector).and_then(|a| dates::parse(&a));
+            meta.thumbnail = element
+                .select(&thumbnail_selector)
+                .next()
+                .and_then(|img| img.value().attr("src"))
+                .map(str::to_string);
+
+            results.push(result);
+        }
+
+        results
+    }
+}
+
+impl Default for BraveNews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for BraveNews {
+    fn name(&self) -> &str {
+        "brave_news"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://search.brave.com/news")
+            .official_api(false)
+            .results_format("HTML")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["news"]
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut request = EngineRequest::get(&self.base_url)
+            .param("q", &params.query)
+            .param("source", "web");
+        if params.pageno > 1 {
+            request = request.param("offset", (params.pageno - 1).to_string());
+        }
+        if let Some(time_range) = params.time_range {
+            let tf = match time_range {
+                crate::query::TimeRange::Day => "pd",
+                crate::query::TimeRange::Week => "pw",
+                crate::query::TimeRange::Month => "pm",
+                crate::query::TimeRange::Year => "py",
+            };
+            request = request.param("tf", tf);
+        }
+
+        let safesearch_cookie = match params.safesearch {
+            2 => "strict",
+            1 => "moderate",
+            _ => "off",
+        };
+        Ok(request
+            .cookie("safesearch", safesearch_cookie)
+            .cookie("useLocation", "0"))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        Ok(EngineResults::with_results(
+            self.parse_results(&response.text),
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,4 +464,85 @@ mod tests {
         assert!(request.url.contains("brave.com"));
         assert!(request.params.contains_key("q"));
     }
+
+    #[test]
+    fn test_brave_images_request() {
+        let brave = BraveImages::new();
+        let mut params = RequestParams::new("ferris");
+        params.safesearch = 2;
+        let request = brave.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://search.brave.com/api/images");
+        assert_eq!(request.params.get("q"), Some(&"ferris".to_string()));
+        assert_eq!(
+            request.cookies.get("safesearch"),
+            Some(&"strict".to_string())
+        );
+        assert!(!brave.supports_paging());
+    }
+
+    #[test]
+    fn test_brave_images_response() {
+        let brave = BraveImages::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/brave_images.json"));
+        let results = brave.response(response).unwrap();
+        assert_eq!(results.results.len(), 2);
+
+        let image = &results.results[0];
+        assert_eq!(image.url, "https://www.rustacean.net/");
+        assert_eq!(image.result_type, ResultType::Image);
+        assert_eq!(
+            image.metadata.img_src.as_deref(),
+            Some("https://www.rustacean.net/assets/rustacean-flat-happy.png")
+        );
+        assert_eq!(
+            image.metadata.thumbnail.as_deref(),
+            Some("https://imgs.search.brave.com/abc")
+        );
+        assert_eq!(image.metadata.resolution.as_deref(), Some("1200 x 800"));
+        assert_eq!(image.metadata.file_type.as_deref(), Some("png"));
+        assert_eq!(image.metadata.author.as_deref(), Some("rustacean.net"));
+
+        // Size is optional
+        assert_eq!(results.results[1].metadata.resolution, None);
+    }
+
+    #[test]
+    fn test_brave_news_request() {
+        let brave = BraveNews::new();
+        let mut params = RequestParams::new("rust");
+        params.pageno = 2;
+        params.time_range = Some(crate::query::TimeRange::Day);
+        let request = brave.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://search.brave.com/news");
+        assert_eq!(request.params.get("offset"), Some(&"1".to_string()));
+        assert_eq!(request.params.get("tf"), Some(&"pd".to_string()));
+    }
+
+    #[test]
+    fn test_brave_news_response() {
+        let brave = BraveNews::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/brave_news.html"));
+        let results = brave.response(response).unwrap();
+        assert_eq!(results.results.len(), 2);
+
+        let article = &results.results[0];
+        assert_eq!(article.url, "https://www.example.com/news/rust-2024");
+        assert_eq!(article.title, "Rust 2024 edition ships");
+        assert_eq!(article.result_type, ResultType::News);
+        assert_eq!(article.metadata.author.as_deref(), Some("example.com"));
+        assert_eq!(
+            article.metadata.thumbnail.as_deref(),
+            Some("https://imgs.search.brave.com/abc")
+        );
+        let age = chrono::Utc::now() - article.metadata.published_date.unwrap();
+        assert!(age >= chrono::Duration::hours(5) && age < chrono::Duration::hours(6));
+
+        // Absolute dates are kept as is
+        assert_eq!(
+            results.results[1].metadata.published_date,
+            "2024-02-08T00:00:00Z".parse().ok()
+        );
+    }
 }
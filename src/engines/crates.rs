@@ -0,0 +1,276 @@
+//! Rust package engines: crates.io and docs.rs
+//!
+//! crates.io is searched through its JSON API; docs.rs has no API, so its
+//! release search page is scraped. Neither reports a license in its search
+//! results, so unlike npm these results carry none.
+
+use super::traits::*;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use scraper::{Html, Selector};
+use serde::Deserialize;
+
+/// Crate as returned by the crates.io search API
+#[derive(Debug, Deserialize)]
+struct Crate {
+    name: String,
+    description: Option<String>,
+    max_version: Option<String>,
+    newest_version: Option<String>,
+    downloads: Option<u64>,
+    repository: Option<String>,
+    updated_at: Option<String>,
+}
+
+/// crates.io search API response
+#[derive(Debug, Deserialize)]
+struct CratesResponse {
+    #[serde(default)]
+    crates: Vec<Crate>,
+}
+
+/// crates.io package search engine
+pub struct CratesIo {
+    api_url: String,
+}
+
+impl CratesIo {
+    pub fn new() -> Self {
+        Self {
+            api_url: "https://crates.io/api/v1/crates".to_string(),
+        }
+    }
+}
+
+impl Default for CratesIo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for CratesIo {
+    fn name(&self) -> &str {
+        "crates_io"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://crates.io")
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["it", "packages"]
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        // crates.io requires an identifying User-Agent
+        let request = EngineRequest::get(&self.api_url)
+            .param("q", &params.query)
+            .param("page", params.pageno.to_string())
+            .param("per_page", "10")
+            .header("User-Agent", "SearXNG-RS/1.0")
+            .header("Accept", "application/json");
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: CratesResponse = response.json()?;
+        let mut results = Vec::new();
+
+        for (i, krate) in data.crates.into_iter().enumerate() {
+            let url = format!("https://crates.io/crates/{}", krate.name);
+            let mut result =
+                Result::new(url, krate.name, self.name().to_string()).with_position(i as u32 + 1);
+
+            if let Some(description) = krate.description {
+                result = result.with_content(description.trim().to_string());
+            }
+
+            result.result_type = ResultType::Package;
+            result.metadata.template = Some("package.html".to_string());
+            result.metadata.version = krate.max_version.or(krate.newest_version);
+            result.metadata.downloads = krate.downloads;
+            result.metadata.repository = krate.repository;
+            result.metadata.updated_date = krate.updated_at;
+
+            results.push(result);
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+/// docs.rs documentation search engine
+pub struct DocsRs {
+    search_url: String,
+}
+
+impl DocsRs {
+    pub fn new() -> Self {
+        Self {
+            search_url: "https://docs.rs/releases/search".to_string(),
+        }
+    }
+
+    /// Split a docs.rs release name ("serde-1.0.219") into name and version
+    fn split_release(release: &str) -> (&str, Option<&str>) {
+        match release.rsplit_once('-') {
+            Some((name, version)) if version.starts_with(|c: char| c.is_ascii_digit()) => {
+                (name, Some(version))
+            }
+            _ => (release, None),
+        }
+    }
+}
+
+impl Default for DocsRs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for DocsRs {
+    fn name(&self) -> &str {
+        "docs_rs"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://docs.rs")
+            .official_api(false)
+            .results_format("HTML")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["it", "packages"]
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Ok(EngineRequest::get(&self.search_url)
+            .param("query", &params.query)
+            .param("page", params.pageno.to_string()))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let document = Html::parse_document(&response.text);
+        let release_selector = Selector::parse("a.release").unwrap();
+        let name_selector = Selector::parse(".name").unwrap();
+        let description_selector = Selector::parse(".description").unwrap();
+        let date_selector = Selector::parse(".date").unwrap();
+
+        let text = |element: scraper::ElementRef, selector: &Selector| {
+            element
+                .select(selector)
+                .next()
+                .map(|e| e.text().collect::<String>().trim().to_string())
+                .filter(|t| !t.is_empty())
+        };
+
+        let mut results = Vec::new();
+
+        for release in document.select(&release_selector) {
+            let Some(href) = release.value().attr("href") else {
+                continue;
+            };
+            let Some(full_name) = text(release, &name_selector) else {
+                continue;
+            };
+
+            let (name, version) = Self::split_release(&full_name);
+            let url = if href.starts_with('/') {
+                format!("https://docs.rs{}", href)
+            } else {
+                href.to_string()
+            };
+
+            let mut result = Result::new(url, name.to_string(), self.name().to_string())
+                .with_position(results.len() as u32 + 1);
+
+            if let Some(description) = text(release, &description_selector) {
+                result = result.with_content(description);
+            }
+
+            result.result_type = ResultType::Package;
+            result.metadata.template = Some("package.html".to_string());
+            result.metadata.version = version.map(|v| v.to_string());
+            result.metadata.updated_date = release
+                .select(&date_selector)
+                .next()
+                .and_then(|d| d.value().attr("title"))
+                .map(|d| d.to_string());
+
+            results.push(result);
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crates_io_response() {
+        let crates = CratesIo::new();
+        let request = crates.request(&RequestParams::new("serde")).unwrap();
+        assert!(request.headers.contains_key("User-Agent"));
+
+        let response = EngineResponse::new(200, include_str!("fixtures/crates_io_search.json"));
+        let results = crates.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://crates.io/crates/serde");
+        assert_eq!(results[0].result_type, ResultType::Package);
+        assert_eq!(results[0].metadata.version.as_deref(), Some("1.0.219"));
+        assert_eq!(results[0].metadata.downloads, Some(521_403_012));
+        assert_eq!(
+            results[0].metadata.repository.as_deref(),
+            Some("https://github.com/serde-rs/serde")
+        );
+        assert!(results[0].metadata.updated_date.is_some());
+        assert_eq!(results[0].metadata.license, None);
+    }
+
+    #[test]
+    fn test_docs_rs_response() {
+        let docs = DocsRs::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/docs_rs_search.html"));
+        let results = docs.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "serde");
+        assert_eq!(results[0].url, "https://docs.rs/serde/latest/serde/");
+        assert_eq!(results[0].metadata.version.as_deref(), Some("1.0.219"));
+        assert_eq!(
+            results[0].metadata.updated_date.as_deref(),
+            Some("2025-03-09T18:31:59Z")
+        );
+        assert_eq!(results[1].title, "serde_json");
+        assert_eq!(results[0].metadata.license, None);
+    }
+
+    #[test]
+    fn test_split_release() {
+        assert_eq!(
+            DocsRs::split_release("serde-1.0.0"),
+            ("serde", Some("1.0.0"))
+        );
+        assert_eq!(
+            DocsRs::split_release("tokio-util-0.7.1"),
+            ("tokio-util", Some("0.7.1"))
+        );
+        assert_eq!(DocsRs::split_release("no-version"), ("no-version", None));
+    }
+}
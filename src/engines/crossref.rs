@@ -0,0 +1,271 @@
+//! Crossref search engine implementation
+//!
+//! Searches scholarly works registered with Crossref through its REST API.
+//! Setting `mailto` puts requests into Crossref's "polite" pool.
+
+use super::dates;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::Html;
+use serde::Deserialize;
+
+/// Section titles ("Abstract") inside JATS abstracts
+static JATS_TITLE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<jats:title>.*?</jats:title>").unwrap());
+
+/// `GET /works` response
+#[derive(Debug, Deserialize)]
+struct WorksResponse {
+    message: WorksMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorksMessage {
+    #[serde(default)]
+    items: Vec<Work>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Work {
+    #[serde(rename = "DOI")]
+    doi: String,
+    #[serde(rename = "URL")]
+    url: Option<String>,
+    #[serde(default)]
+    title: Vec<String>,
+    #[serde(default)]
+    author: Vec<Author>,
+    #[serde(default)]
+    container_title: Vec<String>,
+    published: Option<DateParts>,
+    issued: Option<DateParts>,
+    is_referenced_by_count: Option<u64>,
+    #[serde(rename = "abstract")]
+    abstract_text: Option<String>,
+    #[serde(default)]
+    link: Vec<Link>,
+    #[serde(default)]
+    license: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    given: Option<String>,
+    family: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct DateParts {
+    #[serde(default)]
+    date_parts: Vec<Vec<Option<u32>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Link {
+    #[serde(rename = "URL")]
+    url: String,
+    content_type: Option<String>,
+}
+
+/// Crossref scholarly works engine
+pub struct Crossref {
+    api_url: String,
+    mailto: Option<String>,
+}
+
+impl Crossref {
+    pub fn new() -> Self {
+        Self {
+            api_url: "https://api.crossref.org/works".to_string(),
+            mailto: None,
+        }
+    }
+
+    /// Date of Crossref date parts, which may lack the month or day
+    fn date(date: &DateParts) -> Option<DateTime<Utc>> {
+        let parts: Vec<u32> = date.date_parts.first()?.iter().map_while(|p| *p).collect();
+        let (year, rest) = parts.split_first()?;
+        dates::from_parts(*year as i32, rest.first().copied(), rest.get(1).copied())
+    }
+
+    /// Strip the JATS markup Crossref abstracts come with
+    fn strip_markup(text: &str) -> String {
+        let text = JATS_TITLE.replace_all(text, " ");
+        let fragment = Html::parse_fragment(&text);
+        let text: String = fragment.root_element().text().collect();
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Default for Crossref {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Crossref {
+    fn name(&self) -> &str {
+        "crossref"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://www.crossref.org")
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["science"]
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.mailto = config.extra_str("mailto");
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut request = EngineRequest::get(&self.api_url)
+            .param("query", &params.query)
+            .param("rows", "10")
+            .param("offset", ((params.pageno.max(1) - 1) * 10).to_string());
+
+        if let Some(mailto) = &self.mailto {
+            request = request.param("mailto", mailto);
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: WorksResponse = response.json()?;
+        let mut results = Vec::new();
+
+        for work in data.message.items {
+            let Some(title) = work.title.first().filter(|t| !t.is_empty()) else {
+                continue;
+            };
+
+            let url = work
+                .url
+                .unwrap_or_else(|| format!("https://doi.org/{}", work.doi));
+            let mut result = Result::new(url, title.clone(), self.name().to_string())
+                .with_position(results.len() as u32 + 1);
+            result.result_type = ResultType::Paper;
+
+            if let Some(abstract_text) = work.abstract_text {
+                result = result.with_content(Self::strip_markup(&abstract_text));
+            }
+
+            let authors: Vec<String> = work
+                .author
+                .into_iter()
+                .filter_map(|a| match (a.given, a.family) {
+                    (Some(given), Some(family)) => Some(format!("{} {}", given, family)),
+                    (None, Some(family)) => Some(family),
+                    (given, None) => a.name.or(given),
+                })
+                .collect();
+
+            result.metadata.template = Some("paper.html".to_string());
+            result.metadata.author = Some(authors.join(", ")).filter(|a| !a.is_empty());
+            result.metadata.authors = authors;
+            result.metadata.journal = work.container_title.into_iter().next();
+            result.metadata.doi = Some(work.doi);
+            result.metadata.published_date = work
+                .published
+                .as_ref()
+                .or(work.issued.as_ref())
+                .and_then(Self::date);
+            result.metadata.citations = work.is_referenced_by_count;
+            result.metadata.pdf_url = work
+                .link
+                .into_iter()
+                .find(|l| l.content_type.as_deref() == Some("application/pdf"))
+                .map(|l| l.url);
+            // Crossref has no open access flag; a license is the best hint
+            result.metadata.open_access = !work.license.is_empty();
+
+            results.push(result);
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossref_request() {
+        let mut crossref = Crossref::new();
+        let config: EngineConfig =
+            serde_yaml::from_str("name: crossref\nengine: crossref\nmailto: ops@example.org")
+                .unwrap();
+        crossref.init(&config).unwrap();
+
+        let mut params = RequestParams::new("protein folding");
+        params.pageno = 2;
+        let request = crossref.request(&params).unwrap();
+        assert_eq!(request.params.get("offset"), Some(&"10".to_string()));
+        assert_eq!(
+            request.params.get("mailto"),
+            Some(&"ops@example.org".to_string())
+        );
+    }
+
+    #[test]
+    fn test_crossref_response() {
+        let crossref = Crossref::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/crossref_works.json"));
+        let results = crossref.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        let paper = &results[0];
+        assert_eq!(paper.result_type, ResultType::Paper);
+        assert_eq!(paper.url, "https://doi.org/10.1038/s41586-021-03819-2");
+        assert_eq!(
+            paper.metadata.authors,
+            vec!["John Jumper", "Richard Evans", "AlphaFold Team"]
+        );
+        assert_eq!(paper.metadata.journal.as_deref(), Some("Nature"));
+        assert_eq!(
+            paper.metadata.doi.as_deref(),
+            Some("10.1038/s41586-021-03819-2")
+        );
+        assert_eq!(
+            paper.metadata.published_date,
+            "2021-07-15T00:00:00Z".parse().ok()
+        );
+        assert_eq!(paper.metadata.citations, Some(21457));
+        assert_eq!(
+            paper.metadata.pdf_url.as_deref(),
+            Some("https://www.nature.com/articles/s41586-021-03819-2.pdf")
+        );
+        assert!(paper.metadata.open_access);
+        assert_eq!(
+            paper.content.as_deref(),
+            Some("Proteins are essential to life, and understanding their structure can facilitate a mechanistic understanding of their function.")
+        );
+
+        assert_eq!(
+            results[1].metadata.published_date,
+            "2019-01-01T00:00:00Z".parse().ok()
+        );
+        assert!(!results[1].metadata.open_access);
+    }
+}
@@ -0,0 +1,200 @@
+//! Publish date parsing shared by the engines
+//!
+//! Engines report dates as RFC 3339 or RFC 2822 timestamps, plain dates or
+//! relative text such as "3 hours ago", "2d" or "vor 2 Tagen". [`parse`]
+//! turns all of them into UTC timestamps.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Timestamps without a time zone, taken as UTC
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y/%m/%d %H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%d %B %Y",
+    "%d %b %Y",
+    "%b %d %Y",
+];
+
+/// Amount and unit of a relative date, once "ago" and the like are removed
+static RELATIVE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d+|an?|one|eine?[mr]?|une?|una)\s*([^\s\d.]+)\.?$").unwrap());
+
+/// Parse a publish date, relative ones counting back from now
+pub fn parse(text: &str) -> Option<DateTime<Utc>> {
+    parse_at(text, Utc::now())
+}
+
+/// Parse a publish date, relative ones counting back from `now`
+pub fn parse_at(text: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(text) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in DATETIME_FORMATS {
+        if let Ok(date) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(date.and_utc());
+        }
+    }
+    for format in DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(text, format) {
+            return Some(start_of(date));
+        }
+    }
+    // Partial dates: "2024-02" and "2024"
+    if let Ok(date) = NaiveDate::parse_from_str(&format!("{}-01", text), "%Y-%m-%d") {
+        return Some(start_of(date));
+    }
+    if text.len() == 4 {
+        if let Some(date) = text
+            .parse()
+            .ok()
+            .and_then(|y| NaiveDate::from_ymd_opt(y, 1, 1))
+        {
+            return Some(start_of(date));
+        }
+    }
+
+    relative(&text.to_lowercase(), now)
+}
+
+/// Date from its parts, as in Crossref's `date-parts`
+pub fn from_parts(year: i32, month: Option<u32>, day: Option<u32>) -> Option<DateTime<Utc>> {
+    NaiveDate::from_ymd_opt(year, month.unwrap_or(1), day.unwrap_or(1)).map(start_of)
+}
+
+fn start_of(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+fn relative(text: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match text {
+        "now" | "just now" | "today" | "jetzt" | "heute" | "maintenant" | "aujourd'hui"
+        | "ahora" | "hoy" => return Some(now),
+        "yesterday" | "gestern" | "hier" | "ayer" => return Some(now - Duration::days(1)),
+        _ => {}
+    }
+
+    let text = text.strip_suffix(" ago").unwrap_or(text);
+    let text = ["vor ", "il y a ", "hace "]
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+        .unwrap_or(text);
+
+    let captures = RELATIVE.captures(text.trim())?;
+    // Words like "an" stand for one; numbers too large for a date are no date
+    let amount: i32 = if captures[1].starts_with(|c: char| c.is_ascii_digit()) {
+        captures[1].parse().ok()?
+    } else {
+        1
+    };
+    let duration = unit(&captures[2])?.checked_mul(amount)?;
+    now.checked_sub_signed(duration)
+}
+
+/// Length of a relative date unit, in English, German, French or Spanish
+fn unit(word: &str) -> Option<Duration> {
+    Some(match word {
+        "s" | "sec" | "secs" | "second" | "seconds" | "sekunde" | "sekunden" | "seconde"
+        | "secondes" | "segundo" | "segundos" => Duration::seconds(1),
+        "m" | "min" | "mins" | "minute" | "minutes" | "minuten" | "minuto" | "minutos" => {
+            Duration::minutes(1)
+        }
+        "h" | "hr" | "hrs" | "hour" | "hours" | "std" | "stunde" | "stunden" | "heure"
+        | "heures" | "hora" | "horas" => Duration::hours(1),
+        "d" | "day" | "days" | "tag" | "tage" | "tagen" | "jour" | "jours" | "día" | "días"
+        | "dia" | "dias" => Duration::days(1),
+        "w" | "wk" | "wks" | "week" | "weeks" | "woche" | "wochen" | "semaine" | "semaines"
+        | "semana" | "semanas" => Duration::weeks(1),
+        "mo" | "mon" | "mos" | "month" | "months" | "monat" | "monate" | "monaten" | "mois"
+        | "mes" | "meses" => Duration::days(30),
+        "y" | "yr" | "yrs" | "year" | "years" | "jahr" | "jahre" | "jahren" | "an" | "ans"
+        | "année" | "années" | "año" | "años" => Duration::days(365),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_absolute() {
+        let expected = at("2024-02-08T15:04:05Z");
+        assert_eq!(parse("2024-02-08T15:04:05Z"), Some(expected));
+        assert_eq!(parse("2024-02-08T16:04:05+01:00"), Some(expected));
+        assert_eq!(parse("Thu, 08 Feb 2024 15:04:05 GMT"), Some(expected));
+        assert_eq!(parse("2024-02-08T15:04:05.000"), Some(expected));
+        assert_eq!(parse("2024-02-08 15:04:05"), Some(expected));
+
+        let day = at("2024-02-08T00:00:00Z");
+        assert_eq!(parse("2024-02-08"), Some(day));
+        assert_eq!(parse("2024/02/08"), Some(day));
+        assert_eq!(parse("February 8, 2024"), Some(day));
+        assert_eq!(parse("Feb 8, 2024"), Some(day));
+        assert_eq!(parse("8 Feb 2024"), Some(day));
+        assert_eq!(parse("2024-02"), Some(at("2024-02-01T00:00:00Z")));
+        assert_eq!(parse("2024"), Some(at("2024-01-01T00:00:00Z")));
+
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("not a date"), None);
+    }
+
+    #[test]
+    fn test_parse_relative() {
+        let now = at("2024-02-08T15:00:00Z");
+        let hours_ago = |h| Some(now - Duration::hours(h));
+
+        assert_eq!(parse_at("3 hours ago", now), hours_ago(3));
+        assert_eq!(parse_at("an hour ago", now), hours_ago(1));
+        assert_eq!(parse_at("3h", now), hours_ago(3));
+        assert_eq!(parse_at("vor 3 Stunden", now), hours_ago(3));
+        assert_eq!(parse_at("il y a 3 heures", now), hours_ago(3));
+        assert_eq!(parse_at("hace 3 horas", now), hours_ago(3));
+        assert_eq!(parse_at("15m", now), Some(now - Duration::minutes(15)));
+        assert_eq!(parse_at("2 days ago", now), Some(now - Duration::days(2)));
+        assert_eq!(
+            parse_at("vor einem Tag", now),
+            Some(now - Duration::days(1))
+        );
+        assert_eq!(parse_at("1 week ago", now), Some(now - Duration::weeks(1)));
+        assert_eq!(parse_at("Yesterday", now), Some(now - Duration::days(1)));
+        assert_eq!(parse_at("just now", now), Some(now));
+        assert_eq!(parse_at("3 fortnights ago", now), None);
+
+        // Amounts out of range are no date rather than a panic
+        assert_eq!(parse_at("1000000 years ago", now), None);
+        assert_eq!(parse_at("9999999999 days ago", now), None);
+        assert_eq!(parse_at("2147483647 weeks ago", now), None);
+    }
+
+    #[test]
+    fn test_from_parts() {
+        assert_eq!(
+            from_parts(2024, Some(2), None),
+            Some(at("2024-02-01T00:00:00Z"))
+        );
+        assert_eq!(from_parts(2024, Some(13), None), None);
+    }
+}
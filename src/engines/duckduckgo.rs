@@ -1,11 +1,21 @@
 //! DuckDuckGo search engine implementation
 
+use super::dates;
+use super::media;
 use super::traits::*;
-use crate::results::{Answer, Result, Suggestion};
+use crate::query::TimeRange;
+use crate::results::{Answer, Result, ResultType, Suggestion};
 use anyhow::Result as AnyhowResult;
+use chrono::DateTime;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use scraper::{Html, Selector};
+use serde::Deserialize;
 use std::collections::HashMap;
 
+/// Token the image and news APIs need, embedded in the search page
+static VQD: Lazy<Regex> = Lazy::new(|| Regex::new(r#"vqd=["']?([\d-]+)"#).unwrap());
+
 /// DuckDuckGo web search engine
 pub struct DuckDuckGo {
     _base_url: String,
@@ -106,6 +116,10 @@ impl Engine for DuckDuckGo {
         "duckduckgo"
     }
 
+    fn languages(&self) -> Vec<&str> {
+        crate::locales::language_codes()
+    }
+
     fn about(&self) -> EngineAbout {
         EngineAbout::new()
             .website("https://duckduckgo.com")
@@ -283,6 +297,306 @@ impl Engine for DuckDuckGoInstant {
     }
 }
 
+/// Search page request for one of the APIs behind a `vqd` token
+///
+/// The APIs need a token from the search page, so the page is requested
+/// first and carries the search parameters in its URL for [`api_request`].
+fn search_page_request(
+    base_url: &str,
+    params: &RequestParams,
+    ia: &str,
+    offset: u32,
+) -> EngineRequest {
+    let kp = if params.safesearch > 0 { "1" } else { "-1" };
+    let mut request = EngineRequest::get(format!("{}/", base_url))
+        .param("q", &params.query)
+        .param("iax", ia)
+        .param("ia", ia)
+        .param("kp", kp)
+        .param("s", offset.to_string());
+    if !params.lang.is_empty() && params.lang != "all" {
+        request = request.param("kl", &params.lang);
+    }
+    if let Some(time_range) = params.time_range {
+        let df = match time_range {
+            TimeRange::Day => "d",
+            TimeRange::Week => "w",
+            TimeRange::Month => "m",
+            TimeRange::Year => "y",
+        };
+        request = request.param("df", df);
+    }
+    request
+}
+
+/// API request following a search page, if the page holds a `vqd` token
+fn api_request(
+    base_url: &str,
+    api: &str,
+    response: &EngineResponse,
+) -> AnyhowResult<Option<EngineRequest>> {
+    // Only the search page leads to the API
+    if !response.is_success() || response.url.contains(".js?") {
+        return Ok(None);
+    }
+    let Some(vqd) = VQD.captures(&response.text).map(|c| c[1].to_string()) else {
+        return Ok(None);
+    };
+
+    let page = url::Url::parse(&response.url)?;
+    let param = |name: &str| {
+        page.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    let mut request = EngineRequest::get(format!("{}/{}", base_url, api))
+        .param("q", param("q").unwrap_or_default())
+        .param("vqd", vqd)
+        .param("o", "json")
+        .param("l", param("kl").unwrap_or_else(|| "wt-wt".to_string()))
+        .param("p", param("kp").unwrap_or_else(|| "-1".to_string()))
+        .param("s", param("s").unwrap_or_else(|| "0".to_string()))
+        .header("Referer", format!("{}/", base_url));
+    if let Some(df) = param("df") {
+        request = request.param("df", df);
+    }
+    Ok(Some(request))
+}
+
+/// Fail on anything but an API response
+fn check_api_response(response: &EngineResponse) -> AnyhowResult<()> {
+    if !response.is_success() {
+        return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+    }
+    // The search page only reaches `response` without a token
+    if !response.text.trim_start().starts_with('{') {
+        return Err(anyhow::anyhow!("DuckDuckGo returned no vqd token"));
+    }
+    Ok(())
+}
+
+/// `i.js` response
+#[derive(Debug, Deserialize)]
+struct ImagesResponse {
+    #[serde(default)]
+    results: Vec<ImageResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageResult {
+    title: String,
+    /// Full size image
+    image: String,
+    thumbnail: Option<String>,
+    /// Page the image is on
+    url: String,
+    width: Option<u64>,
+    height: Option<u64>,
+    source: Option<String>,
+}
+
+/// DuckDuckGo Images search engine
+pub struct DuckDuckGoImages {
+    base_url: String,
+}
+
+impl DuckDuckGoImages {
+    /// Images per API response
+    const PAGE_SIZE: u32 = 100;
+
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://duckduckgo.com".to_string(),
+        }
+    }
+
+    fn image_result(&self, image: ImageResult, position: u32) -> Result {
+        let mut result =
+            Result::new(image.url, image.title, self.name().to_string()).with_position(position);
+        result.result_type = ResultType::Image;
+
+        let meta = &mut result.metadata;
+        meta.template = Some("images.html".to_string());
+        meta.img_src = Some(image.image);
+        meta.thumbnail = image.thumbnail.filter(|t| !t.is_empty());
+        meta.resolution = image
+            .width
+            .zip(image.height)
+            .map(|(w, h)| media::format_resolution(w, h));
+        meta.author = image.source.filter(|s| !s.is_empty());
+        result
+    }
+}
+
+impl Default for DuckDuckGoImages {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for DuckDuckGoImages {
+    fn name(&self) -> &str {
+        "duckduckgo_images"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://duckduckgo.com")
+            .official_api(false)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["images"]
+    }
+
+    fn supports_paging(&self) -> bool {
+        true
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let offset = (params.pageno.max(1) - 1) * Self::PAGE_SIZE;
+        Ok(search_page_request(
+            &self.base_url,
+            params,
+            "images",
+            offset,
+        ))
+    }
+
+    fn next_request(&self, response: &EngineResponse) -> AnyhowResult<Option<EngineRequest>> {
+        Ok(api_request(&self.base_url, "i.js", response)?.map(|r| r.param("f", ",,,,,")))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        check_api_response(&response)?;
+
+        let data: ImagesResponse = response.json()?;
+        let results = data
+            .results
+            .into_iter()
+            .enumerate()
+            .map(|(i, image)| self.image_result(image, i as u32 + 1))
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+/// `news.js` response
+#[derive(Debug, Deserialize)]
+struct NewsResponse {
+    #[serde(default)]
+    results: Vec<NewsResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewsResult {
+    title: String,
+    url: String,
+    excerpt: Option<String>,
+    image: Option<String>,
+    source: Option<String>,
+    /// Unix timestamp
+    date: Option<i64>,
+    /// e.g. "3 hours ago"
+    relative_time: Option<String>,
+}
+
+/// DuckDuckGo News search engine
+pub struct DuckDuckGoNews {
+    base_url: String,
+}
+
+impl DuckDuckGoNews {
+    /// Articles per API response
+    const PAGE_SIZE: u32 = 30;
+
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://duckduckgo.com".to_string(),
+        }
+    }
+
+    fn news_result(&self, article: NewsResult, position: u32) -> Result {
+        let mut result = Result::new(article.url, article.title, self.name().to_string())
+            .with_position(position);
+        result.result_type = ResultType::News;
+        if let Some(excerpt) = article.excerpt.filter(|e| !e.trim().is_empty()) {
+            result = result.with_content(excerpt.trim().to_string());
+        }
+
+        let meta = &mut result.metadata;
+        meta.template = Some("news.html".to_string());
+        meta.author = article.source.filter(|s| !s.is_empty());
+        meta.thumbnail = article.image.filter(|i| !i.is_empty());
+        meta.published_date = article
+            .date
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            .or_else(|| article.relative_time.as_deref().and_then(dates::parse));
+        result
+    }
+}
+
+impl Default for DuckDuckGoNews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for DuckDuckGoNews {
+    fn name(&self) -> &str {
+        "duckduckgo_news"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://duckduckgo.com")
+            .official_api(false)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["news"]
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let offset = (params.pageno.max(1) - 1) * Self::PAGE_SIZE;
+        Ok(search_page_request(&self.base_url, params, "news", offset))
+    }
+
+    fn next_request(&self, response: &EngineResponse) -> AnyhowResult<Option<EngineRequest>> {
+        api_request(&self.base_url, "news.js", response)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        check_api_response(&response)?;
+
+        let data: NewsResponse = response.json()?;
+        let results = data
+            .results
+            .into_iter()
+            .enumerate()
+            .map(|(i, article)| self.news_result(article, i as u32 + 1))
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -295,4 +609,95 @@ mod tests {
 
         assert!(request.url.contains("duckduckgo.com"));
     }
+
+    #[test]
+    fn test_duckduckgo_images_requests() {
+        let ddg = DuckDuckGoImages::new();
+        let mut params = RequestParams::new("ferris");
+        params.pageno = 2;
+        params.safesearch = 1;
+        let request = ddg.request(&params).unwrap();
+        assert_eq!(request.url, "https://duckduckgo.com/");
+        assert_eq!(request.params.get("s"), Some(&"100".to_string()));
+
+        let mut page = EngineResponse::new(
+            200,
+            "<script>DDG.deep.initialize('/d.js?q=ferris&vqd=4-1234567890&p=1');</script>",
+        );
+        page.url =
+            "https://duckduckgo.com/?q=ferris&iax=images&ia=images&kp=1&s=100&kl=en".to_string();
+        let api = ddg.next_request(&page).unwrap().unwrap();
+        assert_eq!(api.url, "https://duckduckgo.com/i.js");
+        assert_eq!(api.params.get("q"), Some(&"ferris".to_string()));
+        assert_eq!(api.params.get("vqd"), Some(&"4-1234567890".to_string()));
+        assert_eq!(api.params.get("p"), Some(&"1".to_string()));
+        assert_eq!(api.params.get("s"), Some(&"100".to_string()));
+        assert_eq!(api.params.get("l"), Some(&"en".to_string()));
+
+        // Without a token the page is handed to `response`, which fails
+        let mut blocked = EngineResponse::new(200, "<html>anomaly</html>");
+        blocked.url = page.url.clone();
+        assert!(ddg.next_request(&blocked).unwrap().is_none());
+        assert!(ddg.response(blocked).is_err());
+    }
+
+    #[test]
+    fn test_duckduckgo_images_response() {
+        let ddg = DuckDuckGoImages::new();
+        let mut response =
+            EngineResponse::new(200, include_str!("fixtures/duckduckgo_images.json"));
+        response.url = "https://duckduckgo.com/i.js?q=ferris".to_string();
+        assert!(ddg.next_request(&response).unwrap().is_none());
+
+        let results = ddg.response(response).unwrap();
+        assert_eq!(results.results.len(), 2);
+        let image = &results.results[0];
+        assert_eq!(image.url, "https://www.rustacean.net/");
+        assert_eq!(image.result_type, ResultType::Image);
+        assert_eq!(
+            image.metadata.img_src.as_deref(),
+            Some("https://www.rustacean.net/assets/rustacean-flat-happy.png")
+        );
+        assert_eq!(image.metadata.resolution.as_deref(), Some("1200 x 800"));
+        assert_eq!(
+            image.metadata.thumbnail.as_deref(),
+            Some("https://tse1.mm.bing.net/th?id=OIP.abc")
+        );
+    }
+
+    #[test]
+    fn test_duckduckgo_news() {
+        let ddg = DuckDuckGoNews::new();
+        let mut params = RequestParams::new("rust");
+        params.time_range = Some(TimeRange::Week);
+        let request = ddg.request(&params).unwrap();
+        assert_eq!(request.params.get("ia"), Some(&"news".to_string()));
+        assert_eq!(request.params.get("df"), Some(&"w".to_string()));
+
+        let mut page = EngineResponse::new(200, "<script>vqd=\"4-987654321\"</script>");
+        page.url = "https://duckduckgo.com/?q=rust&iax=news&ia=news&kp=-1&s=0&df=w".to_string();
+        let api = ddg.next_request(&page).unwrap().unwrap();
+        assert_eq!(api.url, "https://duckduckgo.com/news.js");
+        assert_eq!(api.params.get("vqd"), Some(&"4-987654321".to_string()));
+        assert_eq!(api.params.get("df"), Some(&"w".to_string()));
+
+        let mut response = EngineResponse::new(200, include_str!("fixtures/duckduckgo_news.json"));
+        response.url = "https://duckduckgo.com/news.js?q=rust&vqd=4-987654321".to_string();
+        assert!(ddg.next_request(&response).unwrap().is_none());
+
+        let results = ddg.response(response).unwrap();
+        assert_eq!(results.results.len(), 2);
+        let article = &results.results[0];
+        assert_eq!(article.url, "https://www.example.com/news/rust-2024");
+        assert_eq!(article.result_type, ResultType::News);
+        assert_eq!(article.metadata.author.as_deref(), Some("Example News"));
+        assert_eq!(
+            article.metadata.published_date,
+            "2024-02-08T15:04:05Z".parse().ok()
+        );
+
+        // Without a timestamp the relative time is used
+        let age = chrono::Utc::now() - results.results[1].metadata.published_date.unwrap();
+        assert!(age >= chrono::Duration::hours(2) && age < chrono::Duration::hours(3));
+    }
 }
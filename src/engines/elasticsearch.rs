@@ -0,0 +1,249 @@
+//! Elasticsearch / OpenSearch engine implementation
+//!
+//! Searches an index through the `_search` API, which both servers share.
+//! The `query_template` is the JSON query body; by default a `multi_match`
+//! over the mapped title and content fields.
+
+use super::search_server::{self, FieldMapping, QUERY_PLACEHOLDER};
+use super::traits::*;
+use crate::config::EngineConfig;
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// `POST /{index}/_search` response
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    hits: Hits,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hits {
+    /// `{"value": n}` since Elasticsearch 7, a plain number before
+    total: Option<Value>,
+    #[serde(default)]
+    hits: Vec<Hit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hit {
+    #[serde(rename = "_source", default)]
+    source: Value,
+}
+
+/// Elasticsearch / OpenSearch index search engine
+pub struct Elasticsearch {
+    name: String,
+    base_url: String,
+    index: String,
+    fields: FieldMapping,
+    template: Value,
+    auth: Option<(String, String)>,
+}
+
+impl Elasticsearch {
+    pub fn new() -> Self {
+        let fields = FieldMapping::default();
+        Self {
+            name: "elasticsearch".to_string(),
+            base_url: "http://localhost:9200".to_string(),
+            index: String::new(),
+            template: Self::default_template(&fields),
+            fields,
+            auth: None,
+        }
+    }
+
+    /// `multi_match` query over the title (boosted) and content fields
+    fn default_template(fields: &FieldMapping) -> Value {
+        json!({
+            "query": {
+                "multi_match": {
+                    "query": QUERY_PLACEHOLDER,
+                    "fields": [format!("{}^2", fields.title), fields.content],
+                }
+            }
+        })
+    }
+}
+
+impl Default for Elasticsearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Elasticsearch {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.index = config.extra_str("index").ok_or_else(|| {
+            anyhow::anyhow!("Elasticsearch engine '{}' needs an `index`", config.name)
+        })?;
+        self.fields = FieldMapping::from_config(config);
+        self.template = search_server::json_template(config, Self::default_template(&self.fields))?;
+        self.auth = search_server::auth_header(config, "ApiKey")?;
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut body = search_server::fill_template(&self.template, &params.query);
+        body["from"] = json!((params.pageno.max(1) - 1) * self.results_per_page());
+        body["size"] = json!(self.results_per_page());
+
+        let url = format!("{}/{}/_search", self.base_url, self.index);
+        let mut request = EngineRequest::post(url).json(body);
+        if let Some((name, value)) = &self.auth {
+            request = request.header(name, value);
+        }
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            // Errors come as {"error": {"type": ..., "reason": ...}}
+            let reason = response
+                .json::<Value>()
+                .ok()
+                .and_then(|e| e["error"]["reason"].as_str().map(str::to_string));
+            return Err(match reason {
+                Some(reason) => anyhow::anyhow!("Elasticsearch error: {}", reason),
+                None => anyhow::anyhow!("HTTP error: {}", response.status),
+            });
+        }
+
+        let data: SearchResponse = response.json()?;
+        let mut results = Vec::new();
+        for hit in &data.hits.hits {
+            let position = results.len() as u32 + 1;
+            if let Some(result) = self.fields.result(&hit.source, &self.name, position) {
+                results.push(result);
+            }
+        }
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = data
+            .hits
+            .total
+            .and_then(|total| total.get("value").unwrap_or(&total).as_u64());
+        Ok(engine_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::HttpClient;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn elasticsearch(yaml: &str) -> Elasticsearch {
+        let mut engine = Elasticsearch::new();
+        let config: EngineConfig = serde_yaml::from_str(yaml).unwrap();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_elasticsearch_request() {
+        let engine = elasticsearch(
+            "name: wiki\nengine: elasticsearch\nindex: wiki\nfields:\n  title: page.title\n  content: text",
+        );
+        let request = engine.request(&RequestParams::new("deploy")).unwrap();
+        assert_eq!(request.url, "http://localhost:9200/wiki/_search");
+
+        let Some(RequestBody::Json(body)) = request.data else {
+            panic!("expected a JSON body");
+        };
+        assert_eq!(
+            body["query"]["multi_match"],
+            json!({"query": "deploy", "fields": ["page.title^2", "text"]})
+        );
+        assert_eq!(body["from"], 0);
+        assert_eq!(body["size"], 10);
+    }
+
+    #[test]
+    fn test_elasticsearch_query_template() {
+        let engine = elasticsearch(
+            r#"name: wiki
+engine: opensearch
+index: wiki-*
+query_template: '{"query": {"simple_query_string": {"query": "{{query}}", "default_operator": "and"}}}'"#,
+        );
+        let request = engine.request(&RequestParams::new("a \"b\"")).unwrap();
+        let Some(RequestBody::Json(body)) = request.data else {
+            panic!("expected a JSON body");
+        };
+        assert_eq!(
+            body["query"]["simple_query_string"]["query"],
+            json!("a \"b\"")
+        );
+
+        let config: EngineConfig = serde_yaml::from_str(
+            "name: wiki\nengine: elasticsearch\nindex: wiki\nquery_template: '{not json'",
+        )
+        .unwrap();
+        assert!(Elasticsearch::new().init(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_elasticsearch_mock_server() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/wiki/_search"))
+            .and(header("Authorization", "ApiKey c2VjcmV0"))
+            .and(body_partial_json(json!({"from": 0, "size": 10})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("fixtures/elasticsearch_search.json")),
+            )
+            .mount(&server)
+            .await;
+
+        let engine = elasticsearch(&format!(
+            "name: wiki\nengine: elasticsearch\nbase_url: {}\nindex: wiki\napi_key: c2VjcmV0\n\
+             fields:\n  url: page.url\n  title: page.title\n  content: text\n  date: modified",
+            server.uri()
+        ));
+        let request = engine.request(&RequestParams::new("deploy")).unwrap();
+        let response = HttpClient::new().unwrap().execute(request).await.unwrap();
+        let results = engine.response(response).unwrap();
+
+        assert_eq!(results.number_of_results, Some(57));
+        let titles: Vec<&str> = results.results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["Deploy", "Deploy freeze"]);
+        assert_eq!(
+            results.results[0].content.as_deref(),
+            Some("Deployments run every weekday.")
+        );
+        assert_eq!(
+            results.results[1].metadata.published_date,
+            "2023-12-18T11:46:40Z".parse().ok()
+        );
+    }
+
+    #[test]
+    fn test_elasticsearch_error() {
+        let engine = elasticsearch("name: wiki\nengine: elasticsearch\nindex: wiki");
+        let response = EngineResponse::new(
+            404,
+            r#"{"error": {"type": "index_not_found_exception", "reason": "no such index [wiki]"}, "status": 404}"#,
+        );
+        let error = engine.response(response).unwrap_err();
+        assert!(error.to_string().contains("no such index [wiki]"));
+    }
+}
@@ -0,0 +1,377 @@
+//! RSS/Atom feed search engine implementation
+//!
+//! Downloads a configured list of feeds every `refresh_interval` seconds into
+//! an in-memory index and answers queries by keyword matching over the entry
+//! titles and summaries, ranked by relevance and recency.
+
+use super::syndication::{self, FeedEntry};
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Default refresh interval in seconds
+const DEFAULT_REFRESH_INTERVAL: u64 = 3600;
+
+/// Age in days at which the recency boost of an entry has halved
+const RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+
+/// Indexed feed entry
+#[derive(Debug, Clone)]
+struct FeedItem {
+    url: String,
+    title: String,
+    summary: Option<String>,
+    author: Option<String>,
+    published: Option<DateTime<Utc>>,
+    /// Lowercased title and summary for matching
+    title_lower: String,
+    summary_lower: String,
+}
+
+impl FeedItem {
+    fn from_entry(entry: FeedEntry) -> Option<Self> {
+        let url = entry.link()?.to_string();
+        let title = entry.title.filter(|t| !t.is_empty())?;
+        let summary = entry.summary.or(entry.content).filter(|s| !s.is_empty());
+
+        Some(Self {
+            title_lower: title.to_lowercase(),
+            summary_lower: summary.as_deref().unwrap_or_default().to_lowercase(),
+            url,
+            title,
+            summary,
+            author: Some(entry.authors.join(", ")).filter(|a| !a.is_empty()),
+            published: entry.published.or(entry.updated),
+        })
+    }
+
+    /// Keyword relevance: every term must match, title matches weigh double
+    fn relevance(&self, terms: &[String]) -> f64 {
+        let mut relevance = 0.0;
+        for term in terms {
+            if self.title_lower.contains(term.as_str()) {
+                relevance += 2.0;
+            } else if self.summary_lower.contains(term.as_str()) {
+                relevance += 1.0;
+            } else {
+                return 0.0;
+            }
+        }
+        relevance
+    }
+
+    /// Boost between 1 (undated or old) and 2 (published just now)
+    fn recency(&self, now: DateTime<Utc>) -> f64 {
+        match self.published {
+            Some(published) => {
+                let age_days = (now - published).num_seconds().max(0) as f64 / 86_400.0;
+                1.0 + 0.5f64.powf(age_days / RECENCY_HALF_LIFE_DAYS)
+            }
+            None => 1.0,
+        }
+    }
+}
+
+/// Search engine over a set of RSS/Atom feeds
+pub struct Feeds {
+    name: String,
+    feeds: Vec<String>,
+    refresh_interval: Duration,
+    /// Entries per feed URL
+    index: RwLock<HashMap<String, Vec<FeedItem>>>,
+}
+
+impl Feeds {
+    pub fn new() -> Self {
+        Self {
+            name: "feed".to_string(),
+            feeds: Vec::new(),
+            refresh_interval: Duration::from_secs(DEFAULT_REFRESH_INTERVAL),
+            index: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Search the index as of `now`
+    fn search_at(&self, params: &RequestParams, now: DateTime<Utc>) -> EngineResults {
+        let terms: Vec<String> = params
+            .query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        if terms.is_empty() {
+            return EngineResults::new();
+        }
+
+        let since = params.time_range.map(|range| now - range.duration());
+
+        let index = self.index.read().unwrap();
+        let mut matches: Vec<(f64, &FeedItem)> = index
+            .values()
+            .flatten()
+            .filter(|item| match since {
+                Some(since) => item.published.is_some_and(|p| p >= since),
+                None => true,
+            })
+            .filter_map(|item| {
+                let relevance = item.relevance(&terms);
+                (relevance > 0.0).then(|| (relevance * item.recency(now), item))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let per_page = self.results_per_page() as usize;
+        let offset = (params.pageno.max(1) as usize - 1) * per_page;
+        let total = matches.len();
+
+        let results = matches
+            .into_iter()
+            .skip(offset)
+            .take(per_page)
+            .enumerate()
+            .map(|(i, (_, item))| self.item_result(item, (offset + i) as u32 + 1))
+            .collect();
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = Some(total as u64);
+        engine_results
+    }
+
+    fn item_result(&self, item: &FeedItem, position: u32) -> Result {
+        let mut result = Result::new(
+            item.url.clone(),
+            item.title.clone(),
+            self.name().to_string(),
+        )
+        .with_position(position);
+        result.result_type = ResultType::News;
+
+        if let Some(summary) = &item.summary {
+            result = result.with_content(summary.clone());
+        }
+
+        result.metadata.author = item.author.clone();
+        result.metadata.published_date = item.published;
+        result
+    }
+}
+
+impl Default for Feeds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Feeds {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new().results_format("XML-RSS")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["news"]
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn engine_type(&self) -> EngineType {
+        EngineType::Offline
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        self.feeds = config.extra_value("feeds").unwrap_or_default();
+        if self.feeds.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Feed engine '{}' needs a list of `feeds`",
+                config.name
+            ));
+        }
+        if let Some(secs) = config.extra_value::<u64>("refresh_interval") {
+            self.refresh_interval = Duration::from_secs(secs.max(60));
+        }
+        Ok(())
+    }
+
+    fn search(&self, params: &RequestParams) -> AnyhowResult<EngineResults> {
+        Ok(self.search_at(params, Utc::now()))
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        Some(self.refresh_interval)
+    }
+
+    fn refresh_requests(&self) -> Vec<EngineRequest> {
+        self.feeds.iter().map(EngineRequest::get).collect()
+    }
+
+    fn refresh(&self, responses: Vec<AnyhowResult<EngineResponse>>) -> AnyhowResult<()> {
+        let mut failed = Vec::new();
+
+        for (url, response) in self.feeds.iter().zip(responses) {
+            let entries = response.and_then(|response| {
+                if !response.is_success() {
+                    return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+                }
+                Ok(syndication::parse_feed(&response.text)?.entries)
+            });
+
+            match entries {
+                Ok(entries) => {
+                    let items = entries.into_iter().filter_map(FeedItem::from_entry);
+                    self.index
+                        .write()
+                        .unwrap()
+                        .insert(url.clone(), items.collect());
+                }
+                // Keep the entries from the last successful download
+                Err(e) => failed.push(format!("{} ({})", url, e)),
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Failed to refresh {}", failed.join(", ")))
+        }
+    }
+
+    fn request(&self, _params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Err(anyhow::anyhow!("The feed engine searches its local index"))
+    }
+
+    fn response(&self, _response: EngineResponse) -> AnyhowResult<EngineResults> {
+        Err(anyhow::anyhow!("The feed engine searches its local index"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::TimeRange;
+
+    const BLOG: &str = "https://blog.example.org/feed.xml";
+    const RELEASES: &str = "https://example.org/releases.atom";
+
+    fn feeds() -> Feeds {
+        let mut feeds = Feeds::new();
+        let config: EngineConfig = serde_yaml::from_str(&format!(
+            "name: internal news\nengine: feed\nrefresh_interval: 600\nfeeds:\n  - {}\n  - {}",
+            BLOG, RELEASES
+        ))
+        .unwrap();
+        feeds.init(&config).unwrap();
+        feeds
+            .refresh(vec![
+                Ok(EngineResponse::new(
+                    200,
+                    include_str!("fixtures/feed_blog.xml"),
+                )),
+                Ok(EngineResponse::new(
+                    200,
+                    include_str!("fixtures/feed_releases.xml"),
+                )),
+            ])
+            .unwrap();
+        feeds
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2024-06-01T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_feed_config() {
+        let feeds = feeds();
+        assert_eq!(feeds.name(), "internal news");
+        assert_eq!(feeds.engine_type(), EngineType::Offline);
+        assert_eq!(feeds.refresh_interval(), Some(Duration::from_secs(600)));
+        let requests = feeds.refresh_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].url, BLOG);
+
+        let config: EngineConfig = serde_yaml::from_str("name: news\nengine: feed").unwrap();
+        assert!(Feeds::new().init(&config).is_err());
+    }
+
+    #[test]
+    fn test_feed_search() {
+        let feeds = feeds();
+        let results = feeds
+            .search_at(&RequestParams::new("Search"), now())
+            .results;
+
+        // Title matches rank above summary matches, newer above older
+        let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Search 2.0 released",
+                "Rewriting search in Rust",
+                "Release 1.4"
+            ]
+        );
+
+        let first = &results[0];
+        assert_eq!(first.result_type, ResultType::News);
+        assert_eq!(first.engine, "internal news");
+        assert_eq!(first.url, "https://example.org/releases/2.0");
+        assert_eq!(
+            first.metadata.published_date,
+            "2024-05-28T09:00:00Z".parse().ok()
+        );
+
+        let blog = &results[1];
+        assert_eq!(blog.metadata.author.as_deref(), Some("Ada"));
+        assert_eq!(
+            blog.content.as_deref(),
+            Some("Why we moved our search backend to Rust.")
+        );
+
+        // Every term must match
+        let results = feeds
+            .search_at(&RequestParams::new("rust search"), now())
+            .results;
+        assert_eq!(results.len(), 1);
+        assert!(feeds
+            .search_at(&RequestParams::new("kubernetes"), now())
+            .results
+            .is_empty());
+    }
+
+    #[test]
+    fn test_feed_time_range() {
+        let feeds = feeds();
+        let mut params = RequestParams::new("search");
+        params.time_range = Some(TimeRange::Week);
+        let results = feeds.search_at(&params, now()).results;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Search 2.0 released");
+    }
+
+    #[test]
+    fn test_feed_failed_refresh_keeps_entries() {
+        let feeds = feeds();
+        let error = feeds
+            .refresh(vec![
+                Ok(EngineResponse::new(503, "Service Unavailable")),
+                Err(anyhow::anyhow!("connection refused")),
+            ])
+            .unwrap_err();
+        assert!(error.to_string().contains(BLOG));
+
+        let results = feeds
+            .search_at(&RequestParams::new("search"), now())
+            .results;
+        assert_eq!(results.len(), 3);
+    }
+}
@@ -0,0 +1,303 @@
+//! Gitea and Forgejo search engine implementation
+//!
+//! Both forges share the same REST API (v1). `base_url` points at the
+//! instance (Codeberg by default). Repositories are searched by default;
+//! set `search_type` to `issues` or `pulls` to search those instead.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+
+/// What the engine searches for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchType {
+    Repos,
+    Issues,
+    Pulls,
+}
+
+impl SearchType {
+    fn parse(value: &str) -> AnyhowResult<Self> {
+        match value {
+            "repos" => Ok(Self::Repos),
+            "issues" => Ok(Self::Issues),
+            "pulls" => Ok(Self::Pulls),
+            other => Err(anyhow::anyhow!(
+                "Unknown Gitea search_type '{}' (expected repos, issues or pulls)",
+                other
+            )),
+        }
+    }
+}
+
+/// `GET /repos/search` response
+#[derive(Debug, Deserialize)]
+struct RepoSearch {
+    #[serde(default)]
+    data: Vec<Repo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Repo {
+    html_url: String,
+    full_name: String,
+    description: Option<String>,
+    language: Option<String>,
+    stars_count: Option<u64>,
+    updated_at: Option<String>,
+    avatar_url: Option<String>,
+    owner: Option<User>,
+}
+
+/// Issue or pull request as returned by `GET /repos/issues/search`
+#[derive(Debug, Deserialize)]
+struct Issue {
+    html_url: String,
+    number: u64,
+    title: String,
+    body: Option<String>,
+    state: String,
+    updated_at: Option<String>,
+    user: Option<User>,
+    repository: Option<IssueRepo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueRepo {
+    full_name: String,
+}
+
+/// Gitea/Forgejo search engine
+pub struct Gitea {
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+    search_type: SearchType,
+}
+
+impl Gitea {
+    pub fn new() -> Self {
+        Self {
+            name: "gitea".to_string(),
+            base_url: "https://codeberg.org".to_string(),
+            api_key: None,
+            search_type: SearchType::Repos,
+        }
+    }
+
+    fn repo_result(&self, repo: Repo) -> Result {
+        let mut result = Result::new(repo.html_url, repo.full_name, self.name().to_string());
+        result.result_type = ResultType::Code;
+
+        if let Some(desc) = repo.description.filter(|d| !d.trim().is_empty()) {
+            result = result.with_content(desc.trim().to_string());
+        }
+
+        // Repositories without an avatar fall back to their owner's
+        let owner_avatar = repo.owner.as_ref().and_then(|o| o.avatar_url.clone());
+        result.metadata.template = Some("package.html".to_string());
+        result.metadata.thumbnail = repo.avatar_url.filter(|a| !a.is_empty()).or(owner_avatar);
+        result.metadata.author = repo.owner.map(|o| o.login);
+        result.metadata.stars = repo.stars_count;
+        result.metadata.language = repo.language.filter(|l| !l.is_empty());
+        result.metadata.updated_date = repo.updated_at;
+        result
+    }
+
+    fn issue_result(&self, issue: Issue) -> Result {
+        let mut result = Result::new(issue.html_url, issue.title, self.name().to_string());
+
+        let mut content = issue.state;
+        if let Some(repo) = issue.repository {
+            content = format!("{}#{} ({})", repo.full_name, issue.number, content);
+        }
+        if let Some(body) = issue.body.filter(|b| !b.trim().is_empty()) {
+            let body = body.trim();
+            content = format!(
+                "{} - {}",
+                content,
+                body.split("\n\n").next().unwrap_or(body).trim()
+            );
+        }
+
+        result = result.with_content(content);
+        result.metadata.author = issue.user.map(|u| u.login);
+        result.metadata.updated_date = issue.updated_at;
+        result
+    }
+}
+
+impl Default for Gitea {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Gitea {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["it", "repos"]
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.api_key = config.api_key.clone().filter(|k| !k.is_empty());
+        if let Some(search_type) = config.extra_str("search_type") {
+            self.search_type = SearchType::parse(&search_type)?;
+        }
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut request = match self.search_type {
+            SearchType::Repos => {
+                EngineRequest::get(format!("{}/api/v1/repos/search", self.base_url))
+                    .param("sort", "stars")
+                    .param("order", "desc")
+            }
+            SearchType::Issues | SearchType::Pulls => {
+                let kind = if self.search_type == SearchType::Pulls {
+                    "pulls"
+                } else {
+                    "issues"
+                };
+                EngineRequest::get(format!("{}/api/v1/repos/issues/search", self.base_url))
+                    .param("type", kind)
+                    .param("state", "all")
+            }
+        };
+
+        request = request
+            .param("q", &params.query)
+            .param("limit", "10")
+            .param("page", params.pageno.to_string())
+            .header("Accept", "application/json");
+
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("token {}", api_key));
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            if response.status == 401 {
+                return Err(anyhow::anyhow!("Gitea API requires a valid api_key (401)"));
+            }
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let results: Vec<Result> = match self.search_type {
+            SearchType::Repos => response
+                .json::<RepoSearch>()?
+                .data
+                .into_iter()
+                .map(|r| self.repo_result(r))
+                .collect(),
+            SearchType::Issues | SearchType::Pulls => response
+                .json::<Vec<Issue>>()?
+                .into_iter()
+                .map(|i| self.issue_result(i))
+                .collect(),
+        };
+
+        let results = results
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| r.with_position(i as u32 + 1))
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gitea(yaml: &str) -> Gitea {
+        let mut gitea = Gitea::new();
+        let config: EngineConfig = serde_yaml::from_str(yaml).unwrap();
+        gitea.init(&config).unwrap();
+        gitea
+    }
+
+    #[test]
+    fn test_gitea_request() {
+        let engine = gitea(
+            "name: forgejo\nengine: gitea\nbase_url: https://forge.example.org\napi_key: secret",
+        );
+        assert_eq!(engine.name(), "forgejo");
+
+        let request = engine.request(&RequestParams::new("search")).unwrap();
+        assert_eq!(request.url, "https://forge.example.org/api/v1/repos/search");
+        assert_eq!(request.params.get("q"), Some(&"search".to_string()));
+        assert_eq!(
+            request.headers.get("Authorization"),
+            Some(&"token secret".to_string())
+        );
+
+        let engine = gitea("name: forgejo prs\nengine: gitea\nsearch_type: pulls");
+        let request = engine.request(&RequestParams::new("search")).unwrap();
+        assert_eq!(
+            request.url,
+            "https://codeberg.org/api/v1/repos/issues/search"
+        );
+        assert_eq!(request.params.get("type"), Some(&"pulls".to_string()));
+    }
+
+    #[test]
+    fn test_gitea_repos_response() {
+        let engine = Gitea::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/gitea_repos.json"));
+        let results = engine.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://codeberg.org/forgejo/forgejo");
+        assert_eq!(results[0].title, "forgejo/forgejo");
+        assert_eq!(results[0].metadata.stars, Some(3120));
+        assert_eq!(results[0].metadata.language.as_deref(), Some("Go"));
+        assert_eq!(results[0].metadata.author.as_deref(), Some("forgejo"));
+        assert_eq!(
+            results[0].metadata.thumbnail.as_deref(),
+            Some("https://codeberg.org/avatars/forgejo")
+        );
+        assert_eq!(results[1].metadata.language, None);
+    }
+
+    #[test]
+    fn test_gitea_issues_response() {
+        let engine = gitea("name: gitea issues\nengine: gitea\nsearch_type: issues");
+        let response = EngineResponse::new(200, include_str!("fixtures/gitea_issues.json"));
+        let results = engine.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].content.as_deref(),
+            Some("forgejo/forgejo#5210 (open) - Searching issues across repositories times out.")
+        );
+        assert_eq!(results[0].metadata.author.as_deref(), Some("alice"));
+    }
+}
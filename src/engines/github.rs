@@ -1,23 +1,286 @@
 //! GitHub search engine implementation
 //!
-//! Uses GitHub's official API to search for repositories.
+//! Uses GitHub's official search API. Repositories are searched by default;
+//! `search_type` switches to code, issues or users. An `api_key` (personal
+//! access token) raises the rate limit and is required for code search.
 
 use super::traits::*;
-use crate::results::{Result, ResultType};
+use crate::config::EngineConfig;
+use crate::query::{quote_if_needed, OperatorKind, QueryOperator, TimeRange};
+use crate::results::{EngineError, Result, ResultType};
 use anyhow::Result as AnyhowResult;
-use std::collections::HashMap;
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// What the engine searches for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchType {
+    Repositories,
+    Code,
+    Issues,
+    Users,
+}
+
+impl SearchType {
+    fn parse(value: &str) -> AnyhowResult<Self> {
+        match value {
+            "repositories" => Ok(Self::Repositories),
+            "code" => Ok(Self::Code),
+            "issues" => Ok(Self::Issues),
+            "users" => Ok(Self::Users),
+            other => Err(anyhow::anyhow!(
+                "Unknown GitHub search_type '{}' (expected repositories, code, issues or users)",
+                other
+            )),
+        }
+    }
+
+    /// API path below `/search`
+    fn path(self) -> &'static str {
+        match self {
+            Self::Repositories => "repositories",
+            Self::Code => "code",
+            Self::Issues => "issues",
+            Self::Users => "users",
+        }
+    }
+
+    /// Qualifier restricting a term to the item's name
+    fn in_title(self) -> &'static str {
+        match self {
+            Self::Repositories => "in:name",
+            Self::Code => "in:path",
+            Self::Issues => "in:title",
+            Self::Users => "in:login",
+        }
+    }
 
-/// GitHub repository search engine
+    /// Date qualifier used for time range filtering
+    fn date_qualifier(self) -> Option<&'static str> {
+        match self {
+            Self::Repositories => Some("pushed"),
+            Self::Issues | Self::Users => Some("created"),
+            // Code search has no date qualifiers
+            Self::Code => None,
+        }
+    }
+}
+
+/// Search API response
+#[derive(Debug, Deserialize)]
+struct SearchResponse<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Repository {
+    html_url: String,
+    full_name: String,
+    description: Option<String>,
+    language: Option<String>,
+    stargazers_count: Option<u64>,
+    pushed_at: Option<String>,
+    owner: Option<Owner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Owner {
+    login: String,
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeItem {
+    html_url: String,
+    path: String,
+    repository: CodeRepository,
+    #[serde(default)]
+    text_matches: Vec<TextMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeRepository {
+    full_name: String,
+    owner: Option<Owner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextMatch {
+    fragment: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Issue {
+    html_url: String,
+    number: u64,
+    title: String,
+    body: Option<String>,
+    state: String,
+    updated_at: Option<String>,
+    repository_url: String,
+    user: Option<Owner>,
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    html_url: String,
+    login: String,
+    avatar_url: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+/// GitHub search engine
 pub struct GitHub {
+    name: String,
     api_url: String,
+    api_key: Option<String>,
+    search_type: SearchType,
 }
 
 impl GitHub {
     pub fn new() -> Self {
         Self {
-            api_url: "https://api.github.com/search/repositories".to_string(),
+            name: "github".to_string(),
+            api_url: "https://api.github.com/search".to_string(),
+            api_key: None,
+            search_type: SearchType::Repositories,
+        }
+    }
+
+    /// Map a `site:` value onto a GitHub qualifier
+    ///
+    /// `github.com/owner` becomes `user:owner` and `github.com/owner/repo`
+    /// becomes `repo:owner/repo`. Other sites can't be expressed.
+    fn site_qualifier(site: &str) -> Option<String> {
+        let path = site.strip_prefix("github.com/")?;
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match segments.as_slice() {
+            [] => None,
+            [owner] => Some(format!("user:{}", owner)),
+            [owner, repo, ..] => Some(format!("repo:{}/{}", owner, repo)),
         }
     }
+
+    /// Build the date qualifier for a time range, e.g. `pushed:>=2025-04-01`
+    fn time_qualifier(&self, time_range: TimeRange, today: NaiveDate) -> Option<String> {
+        let since = today - time_range.duration();
+        self.search_type
+            .date_qualifier()
+            .map(|q| format!("{}:>={}", q, since.format("%Y-%m-%d")))
+    }
+
+    /// Turn an error response into an [`EngineFailure`]
+    ///
+    /// Rate limited responses carry either `Retry-After` (secondary limits)
+    /// or `X-RateLimit-Remaining: 0` with the reset time as a Unix timestamp.
+    fn failure(response: &EngineResponse, now: i64) -> EngineFailure {
+        if let Some(secs) = response
+            .header("retry-after")
+            .and_then(|s| s.trim().parse::<u64>().ok())
+        {
+            return EngineFailure::new(EngineError::TooManyRequests)
+                .suspend_for(Duration::from_secs(secs));
+        }
+
+        if response.header("x-ratelimit-remaining") == Some("0") {
+            let failure = EngineFailure::new(EngineError::TooManyRequests);
+            return match response
+                .header("x-ratelimit-reset")
+                .and_then(|s| s.trim().parse::<i64>().ok())
+            {
+                Some(reset) => {
+                    failure.suspend_for(Duration::from_secs((reset - now).max(1) as u64))
+                }
+                None => failure,
+            };
+        }
+
+        match response.status {
+            401 | 403 => EngineFailure::new(EngineError::AccessDenied),
+            429 => EngineFailure::new(EngineError::TooManyRequests),
+            status if status >= 500 => EngineFailure::new(EngineError::ServerError),
+            status => EngineFailure::new(EngineError::HttpError(status)),
+        }
+    }
+
+    fn repository_result(&self, repo: Repository) -> Result {
+        let mut result = Result::new(repo.html_url, repo.full_name, self.name().to_string());
+        result.result_type = ResultType::Code;
+
+        if let Some(desc) = repo.description.filter(|d| !d.is_empty()) {
+            result = result.with_content(desc);
+        }
+
+        result.metadata.template = Some("package.html".to_string());
+        result.metadata.language = repo.language.filter(|l| !l.is_empty());
+        result.metadata.stars = repo.stargazers_count;
+        result.metadata.updated_date = repo.pushed_at;
+        if let Some(owner) = repo.owner {
+            result.metadata.thumbnail = owner.avatar_url;
+            result.metadata.author = Some(owner.login);
+        }
+        result
+    }
+
+    fn code_result(&self, item: CodeItem) -> Result {
+        let title = format!("{}/{}", item.repository.full_name, item.path);
+        let mut result = Result::new(item.html_url, title, self.name().to_string());
+        result.result_type = ResultType::Code;
+
+        if let Some(fragment) = item.text_matches.into_iter().next() {
+            result = result.with_content(fragment.fragment.trim().to_string());
+        }
+
+        if let Some(owner) = item.repository.owner {
+            result.metadata.thumbnail = owner.avatar_url;
+            result.metadata.author = Some(owner.login);
+        }
+        result
+    }
+
+    fn issue_result(&self, issue: Issue) -> Result {
+        let mut result = Result::new(issue.html_url, issue.title, self.name().to_string());
+
+        // repository_url is https://api.github.com/repos/{owner}/{repo}
+        let repo = issue
+            .repository_url
+            .split("/repos/")
+            .nth(1)
+            .unwrap_or_default();
+        let kind = if issue.pull_request.is_some() {
+            "pull request"
+        } else {
+            "issue"
+        };
+        let mut content = format!("{}#{} ({} {})", repo, issue.number, issue.state, kind);
+        if let Some(body) = issue.body.filter(|b| !b.trim().is_empty()) {
+            let body = body.trim();
+            content = format!(
+                "{} - {}",
+                content,
+                body.split("\n\n").next().unwrap_or(body).trim()
+            );
+        }
+
+        result = result.with_content(content);
+        result.metadata.author = issue.user.map(|u| u.login);
+        result.metadata.updated_date = issue.updated_at;
+        result
+    }
+
+    fn user_result(&self, user: User) -> Result {
+        let mut result = Result::new(user.html_url, user.login, self.name().to_string());
+        if let Some(kind) = user.kind {
+            result = result.with_content(kind);
+        }
+        result.metadata.thumbnail = user.avatar_url;
+        result
+    }
 }
 
 impl Default for GitHub {
@@ -28,7 +291,7 @@ impl Default for GitHub {
 
 impl Engine for GitHub {
     fn name(&self) -> &str {
-        "github"
+        &self.name
     }
 
     fn about(&self) -> EngineAbout {
@@ -46,133 +309,144 @@ impl Engine for GitHub {
         true
     }
 
-    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
-        let mut query_params = HashMap::new();
-        query_params.insert("q".to_string(), params.query.clone());
-        query_params.insert("sort".to_string(), "stars".to_string());
-        query_params.insert("order".to_string(), "desc".to_string());
-
-        // Pagination (GitHub API uses per_page and page)
-        query_params.insert("per_page".to_string(), "10".to_string());
-        query_params.insert("page".to_string(), params.pageno.to_string());
-
-        let mut request = EngineRequest::get(&self.api_url);
-        request.params = query_params;
-
-        // Set the Accept header for text match highlights
-        request.headers.insert(
-            "Accept".to_string(),
-            "application/vnd.github.preview.text-match+json".to_string(),
-        );
-
-        // GitHub API requires User-Agent
-        request
-            .headers
-            .insert("User-Agent".to_string(), "SearXNG-RS/1.0".to_string());
-
-        Ok(request)
+    fn supports_time_range(&self) -> bool {
+        self.search_type.date_qualifier().is_some()
     }
 
-    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
-        if !response.is_success() {
-            // Check for rate limiting
-            if response.status == 403 {
-                return Err(anyhow::anyhow!("GitHub API rate limit exceeded"));
-            }
-            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        self.api_key = config.api_key.clone().filter(|k| !k.is_empty());
+        if let Some(search_type) = config.extra_str("search_type") {
+            self.search_type = SearchType::parse(&search_type)?;
         }
+        if self.search_type == SearchType::Code && self.api_key.is_none() {
+            return Err(anyhow::anyhow!("GitHub code search requires an api_key"));
+        }
+        Ok(())
+    }
 
-        let json: serde_json::Value = serde_json::from_str(&response.text)
-            .map_err(|e| anyhow::anyhow!("Failed to parse JSON: {}", e))?;
-
-        let items = json
-            .get("items")
-            .and_then(|i| i.as_array())
-            .cloned()
-            .unwrap_or_default();
-
-        let mut results = Vec::new();
-        let mut position = 1u32;
-
-        for item in items {
-            // Get URL
-            let url = item
-                .get("html_url")
-                .and_then(|u| u.as_str())
-                .unwrap_or_default()
-                .to_string();
+    fn supported_operators(&self) -> Vec<OperatorKind> {
+        vec![
+            OperatorKind::Site,
+            OperatorKind::ExcludeSite,
+            OperatorKind::InTitle,
+            OperatorKind::Phrase,
+            OperatorKind::Exclude,
+        ]
+    }
 
-            if url.is_empty() {
-                continue;
+    fn supports_operator(&self, operator: &QueryOperator) -> bool {
+        match operator {
+            QueryOperator::Site(site) | QueryOperator::ExcludeSite(site) => {
+                Self::site_qualifier(site).is_some()
             }
+            op => self.supported_operators().contains(&op.kind()),
+        }
+    }
 
-            // Get title (full_name = owner/repo)
-            let title = item
-                .get("full_name")
-                .and_then(|t| t.as_str())
-                .unwrap_or_default()
-                .to_string();
-
-            // Build content from language and description
-            let mut content_parts = Vec::new();
-
-            if let Some(lang) = item.get("language").and_then(|l| l.as_str()) {
-                if !lang.is_empty() {
-                    content_parts.push(lang.to_string());
-                }
-            }
+    fn format_query(
+        &self,
+        terms: &str,
+        operators: &[QueryOperator],
+    ) -> (String, Vec<QueryOperator>) {
+        let mut parts = Vec::new();
+        if !terms.is_empty() {
+            parts.push(terms.to_string());
+        }
 
-            if let Some(desc) = item.get("description").and_then(|d| d.as_str()) {
-                if !desc.is_empty() {
-                    content_parts.push(desc.to_string());
+        for op in operators {
+            let part = match op {
+                QueryOperator::Site(site) => Self::site_qualifier(site),
+                QueryOperator::ExcludeSite(site) => {
+                    Self::site_qualifier(site).map(|q| format!("-{}", q))
                 }
-            }
-
-            let content = if content_parts.is_empty() {
-                None
-            } else {
-                Some(content_parts.join(" - "))
+                QueryOperator::InTitle(text) => Some(format!(
+                    "{} {}",
+                    quote_if_needed(text),
+                    self.search_type.in_title()
+                )),
+                QueryOperator::Phrase(phrase) => Some(format!("\"{}\"", phrase)),
+                QueryOperator::Exclude(term) => Some(format!("NOT {}", quote_if_needed(term))),
+                QueryOperator::FileType(_) => None,
             };
+            parts.extend(part);
+        }
 
-            // Get thumbnail (owner's avatar)
-            let thumbnail = item
-                .get("owner")
-                .and_then(|o| o.get("avatar_url"))
-                .and_then(|a| a.as_str())
-                .map(|s| s.to_string());
-
-            // Get author (owner's login)
-            let author = item
-                .get("owner")
-                .and_then(|o| o.get("login"))
-                .and_then(|l| l.as_str())
-                .map(|s| s.to_string());
-
-            // Get stars
-            let stars = item.get("stargazers_count").and_then(|s| s.as_u64());
-
-            // Build result
-            let mut result = Result::new(url, title, self.name().to_string());
-            result.result_type = ResultType::Code;
-            result = result.with_position(position);
-
-            if let Some(c) = content {
-                result = result.with_content(c);
-            }
+        (parts.join(" "), Vec::new())
+    }
 
-            result.metadata.thumbnail = thumbnail;
-            result.metadata.author = author;
-            result.metadata.template = Some("packages.html".to_string());
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut query = params.query.clone();
+        if let Some(qualifier) = params
+            .time_range
+            .and_then(|range| self.time_qualifier(range, Utc::now().date_naive()))
+        {
+            query = format!("{} {}", query, qualifier);
+        }
 
-            // Store stars in views field (repurposed)
-            if let Some(s) = stars {
-                result.metadata.views = Some(s);
-            }
+        // Pagination (GitHub API uses per_page and page)
+        let url = format!("{}/{}", self.api_url, self.search_type.path());
+        let mut request = EngineRequest::get(url)
+            .param("q", query)
+            .param("per_page", "10")
+            .param("page", params.pageno.to_string())
+            // Set the Accept header for text match highlights
+            .header("Accept", "application/vnd.github.text-match+json")
+            // GitHub API requires User-Agent
+            .header("User-Agent", "SearXNG-RS/1.0")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+
+        request = match self.search_type {
+            SearchType::Repositories => request.param("sort", "stars").param("order", "desc"),
+            SearchType::Users => request.param("sort", "followers").param("order", "desc"),
+            SearchType::Code | SearchType::Issues => request,
+        };
+
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
 
-            results.push(result);
-            position += 1;
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(Self::failure(&response, Utc::now().timestamp()).into());
         }
 
+        let results: Vec<Result> = match self.search_type {
+            SearchType::Repositories => response
+                .json::<SearchResponse<Repository>>()?
+                .items
+                .into_iter()
+                .map(|r| self.repository_result(r))
+                .collect(),
+            SearchType::Code => response
+                .json::<SearchResponse<CodeItem>>()?
+                .items
+                .into_iter()
+                .map(|c| self.code_result(c))
+                .collect(),
+            SearchType::Issues => response
+                .json::<SearchResponse<Issue>>()?
+                .items
+                .into_iter()
+                .map(|i| self.issue_result(i))
+                .collect(),
+            SearchType::Users => response
+                .json::<SearchResponse<User>>()?
+                .items
+                .into_iter()
+                .map(|u| self.user_result(u))
+                .collect(),
+        };
+
+        let results = results
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| r.with_position(i as u32 + 1))
+            .collect();
+
         Ok(EngineResults::with_results(results))
     }
 }
@@ -181,15 +455,153 @@ impl Engine for GitHub {
 mod tests {
     use super::*;
 
+    fn github(yaml: &str) -> GitHub {
+        let mut github = GitHub::new();
+        let config: EngineConfig = serde_yaml::from_str(yaml).unwrap();
+        github.init(&config).unwrap();
+        github
+    }
+
     #[test]
     fn test_github_request() {
         let github = GitHub::new();
         let params = RequestParams::new("rust");
         let request = github.request(&params).unwrap();
 
-        assert!(request.url.contains("api.github.com"));
+        assert_eq!(request.url, "https://api.github.com/search/repositories");
         assert!(request.params.contains_key("q"));
         assert!(request.headers.contains_key("Accept"));
         assert!(request.headers.contains_key("User-Agent"));
+        assert!(!request.headers.contains_key("Authorization"));
+    }
+
+    #[test]
+    fn test_github_authenticated_modes() {
+        let github = github("name: github code\nengine: github\nsearch_type: code\napi_key: ghp_x");
+        let mut params = RequestParams::new("tokio::spawn");
+        params.pageno = 3;
+        let request = github.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://api.github.com/search/code");
+        assert_eq!(request.params.get("page"), Some(&"3".to_string()));
+        assert_eq!(
+            request.headers.get("Authorization"),
+            Some(&"Bearer ghp_x".to_string())
+        );
+        assert!(!github.supports_time_range());
+
+        // Code search is not available anonymously
+        let mut engine = GitHub::new();
+        let config: EngineConfig =
+            serde_yaml::from_str("name: github\nengine: github\nsearch_type: code").unwrap();
+        assert!(engine.init(&config).is_err());
+    }
+
+    #[test]
+    fn test_github_time_range() {
+        let today = NaiveDate::from_ymd_opt(2025, 5, 8).unwrap();
+        let repos = GitHub::new();
+        assert_eq!(
+            repos.time_qualifier(TimeRange::Week, today).as_deref(),
+            Some("pushed:>=2025-05-01")
+        );
+
+        let issues = github("name: github issues\nengine: github\nsearch_type: issues");
+        assert_eq!(
+            issues.time_qualifier(TimeRange::Month, today).as_deref(),
+            Some("created:>=2025-04-08")
+        );
+
+        let mut params = RequestParams::new("rust");
+        params.time_range = Some(TimeRange::Year);
+        let request = repos.request(&params).unwrap();
+        assert!(request.params["q"].starts_with("rust pushed:>="));
+    }
+
+    #[test]
+    fn test_github_rate_limit() {
+        let mut response = EngineResponse::new(403, r#"{"message": "API rate limit exceeded"}"#);
+        response
+            .headers
+            .insert("x-ratelimit-remaining".to_string(), "0".to_string());
+        response
+            .headers
+            .insert("x-ratelimit-reset".to_string(), "1746700000".to_string());
+
+        let failure = GitHub::failure(&response, 1746699100);
+        assert_eq!(failure.error, EngineError::TooManyRequests);
+        assert_eq!(failure.suspend_for, Some(Duration::from_secs(900)));
+
+        let error = GitHub::new().response(response).unwrap_err();
+        let failure = error.downcast_ref::<EngineFailure>().unwrap();
+        assert_eq!(failure.error, EngineError::TooManyRequests);
+
+        let mut response = EngineResponse::new(403, "");
+        response
+            .headers
+            .insert("Retry-After".to_string(), "60".to_string());
+        assert_eq!(
+            GitHub::failure(&response, 0).suspend_for,
+            Some(Duration::from_secs(60))
+        );
+
+        let response = EngineResponse::new(401, r#"{"message": "Bad credentials"}"#);
+        assert_eq!(
+            GitHub::failure(&response, 0).error,
+            EngineError::AccessDenied
+        );
+    }
+
+    #[test]
+    fn test_github_responses() {
+        let repos = GitHub::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/github_repositories.json"));
+        let results = repos.response(response).unwrap().results;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "tokio-rs/tokio");
+        assert_eq!(results[0].metadata.stars, Some(29012));
+        assert_eq!(results[0].metadata.language.as_deref(), Some("Rust"));
+        assert_eq!(results[0].metadata.author.as_deref(), Some("tokio-rs"));
+
+        let issues = github("name: github issues\nengine: github\nsearch_type: issues");
+        let response = EngineResponse::new(200, include_str!("fixtures/github_issues.json"));
+        let results = issues.response(response).unwrap().results;
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].content.as_deref(),
+            Some("tokio-rs/tokio#7012 (open issue) - spawn_blocking panics after shutdown.")
+        );
+        assert!(results[1]
+            .content
+            .as_deref()
+            .unwrap()
+            .contains("(closed pull request)"));
+
+        let users = github("name: github users\nengine: github\nsearch_type: users");
+        let response = EngineResponse::new(
+            200,
+            r#"{"items": [{"login": "octocat", "html_url": "https://github.com/octocat", "avatar_url": "https://avatars.githubusercontent.com/u/583231", "type": "User"}]}"#,
+        );
+        let results = users.response(response).unwrap().results;
+        assert_eq!(results[0].title, "octocat");
+        assert_eq!(results[0].content.as_deref(), Some("User"));
+    }
+
+    #[test]
+    fn test_github_operators() {
+        let github = GitHub::new();
+        let ops = vec![
+            QueryOperator::Site("github.com/rust-lang/rust".to_string()),
+            QueryOperator::ExcludeSite("github.com/someone".to_string()),
+            QueryOperator::InTitle("async".to_string()),
+            QueryOperator::Exclude("deprecated".to_string()),
+        ];
+
+        assert!(github.supports_operator(&ops[0]));
+        assert!(!github.supports_operator(&QueryOperator::Site("docs.rs".to_string())));
+        assert_eq!(
+            github.format_query("runtime", &ops).0,
+            "runtime repo:rust-lang/rust -user:someone async in:name NOT deprecated"
+        );
     }
 }
@@ -0,0 +1,316 @@
+//! GitLab search engine implementation
+//!
+//! Uses the GitLab REST API (v4) of gitlab.com or a self-managed instance
+//! configured through `base_url`. Projects are searched by default; set
+//! `search_type` to `issues` or `merge_requests` to search those instead.
+//!
+//! Project results carry no language: the project list has no language
+//! field, and `/projects/:id/languages` would take one more request per
+//! project on every search.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+
+/// What the engine searches for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchType {
+    Projects,
+    Issues,
+    MergeRequests,
+}
+
+impl SearchType {
+    fn parse(value: &str) -> AnyhowResult<Self> {
+        match value {
+            "projects" => Ok(Self::Projects),
+            "issues" => Ok(Self::Issues),
+            "merge_requests" => Ok(Self::MergeRequests),
+            other => Err(anyhow::anyhow!(
+                "Unknown GitLab search_type '{}' (expected projects, issues or merge_requests)",
+                other
+            )),
+        }
+    }
+
+    /// API path below `/api/v4`
+    fn path(self) -> &'static str {
+        match self {
+            Self::Projects => "projects",
+            Self::Issues => "issues",
+            Self::MergeRequests => "merge_requests",
+        }
+    }
+}
+
+/// Project as returned by `GET /projects`
+#[derive(Debug, Deserialize)]
+struct Project {
+    web_url: String,
+    path_with_namespace: String,
+    description: Option<String>,
+    star_count: Option<u64>,
+    last_activity_at: Option<String>,
+    avatar_url: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+}
+
+/// Issue or merge request as returned by `GET /issues` and `GET /merge_requests`
+#[derive(Debug, Deserialize)]
+struct Issue {
+    web_url: String,
+    title: String,
+    description: Option<String>,
+    state: String,
+    updated_at: Option<String>,
+    author: Option<User>,
+    references: Option<References>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct References {
+    full: String,
+}
+
+/// GitLab search engine
+pub struct GitLab {
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+    search_type: SearchType,
+}
+
+impl GitLab {
+    pub fn new() -> Self {
+        Self {
+            name: "gitlab".to_string(),
+            base_url: "https://gitlab.com".to_string(),
+            api_key: None,
+            search_type: SearchType::Projects,
+        }
+    }
+
+    fn project_result(&self, project: Project) -> Result {
+        let mut result = Result::new(
+            project.web_url,
+            project.path_with_namespace,
+            self.name().to_string(),
+        );
+        result.result_type = ResultType::Code;
+
+        let mut content_parts = Vec::new();
+        if !project.topics.is_empty() {
+            content_parts.push(project.topics.join(", "));
+        }
+        if let Some(desc) = project.description.filter(|d| !d.trim().is_empty()) {
+            content_parts.push(desc.trim().to_string());
+        }
+        if !content_parts.is_empty() {
+            result = result.with_content(content_parts.join(" - "));
+        }
+
+        // No `language`: it is only available per project (see module docs)
+        result.metadata.template = Some("package.html".to_string());
+        result.metadata.thumbnail = project.avatar_url;
+        result.metadata.stars = project.star_count;
+        result.metadata.updated_date = project.last_activity_at;
+        result
+    }
+
+    fn issue_result(&self, issue: Issue) -> Result {
+        let mut result = Result::new(issue.web_url, issue.title, self.name().to_string());
+
+        let mut content = issue.state;
+        if let Some(reference) = issue.references {
+            content = format!("{} ({})", reference.full, content);
+        }
+        if let Some(desc) = issue.description.filter(|d| !d.trim().is_empty()) {
+            content = format!("{} - {}", content, first_paragraph(&desc));
+        }
+
+        result = result.with_content(content);
+        result.metadata.author = issue.author.map(|a| a.username);
+        result.metadata.updated_date = issue.updated_at;
+        result
+    }
+}
+
+/// First paragraph of a Markdown description
+fn first_paragraph(text: &str) -> &str {
+    let text = text.trim();
+    text.split("\n\n").next().unwrap_or(text).trim()
+}
+
+impl Default for GitLab {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for GitLab {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["it", "repos"]
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.api_key = config.api_key.clone().filter(|k| !k.is_empty());
+        if let Some(search_type) = config.extra_str("search_type") {
+            self.search_type = SearchType::parse(&search_type)?;
+        }
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let url = format!("{}/api/v4/{}", self.base_url, self.search_type.path());
+        let mut request = EngineRequest::get(url)
+            .param("search", &params.query)
+            .param("per_page", "10")
+            .param("page", params.pageno.to_string());
+
+        request = match self.search_type {
+            SearchType::Projects => request.param("order_by", "star_count"),
+            // Without a scope only the user's own issues are returned
+            SearchType::Issues | SearchType::MergeRequests => request.param("scope", "all"),
+        };
+
+        if let Some(api_key) = &self.api_key {
+            request = request.header("PRIVATE-TOKEN", api_key);
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            if response.status == 401 {
+                return Err(anyhow::anyhow!("GitLab API requires a valid api_key (401)"));
+            }
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let results: Vec<Result> = match self.search_type {
+            SearchType::Projects => response
+                .json::<Vec<Project>>()?
+                .into_iter()
+                .map(|p| self.project_result(p))
+                .collect(),
+            SearchType::Issues | SearchType::MergeRequests => response
+                .json::<Vec<Issue>>()?
+                .into_iter()
+                .map(|i| self.issue_result(i))
+                .collect(),
+        };
+
+        let results = results
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| r.with_position(i as u32 + 1))
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gitlab(yaml: &str) -> GitLab {
+        let mut gitlab = GitLab::new();
+        let config: EngineConfig = serde_yaml::from_str(yaml).unwrap();
+        gitlab.init(&config).unwrap();
+        gitlab
+    }
+
+    #[test]
+    fn test_gitlab_request() {
+        let engine = gitlab(
+            "name: work gitlab\nengine: gitlab\nbase_url: https://git.example.org/\napi_key: secret",
+        );
+        assert_eq!(engine.name(), "work gitlab");
+
+        let request = engine.request(&RequestParams::new("runner")).unwrap();
+        assert_eq!(request.url, "https://git.example.org/api/v4/projects");
+        assert_eq!(request.params.get("search"), Some(&"runner".to_string()));
+        assert_eq!(
+            request.headers.get("PRIVATE-TOKEN"),
+            Some(&"secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_gitlab_search_type() {
+        let engine = gitlab("name: gitlab mrs\nengine: gitlab\nsearch_type: merge_requests");
+        let request = engine.request(&RequestParams::new("fix")).unwrap();
+        assert_eq!(request.url, "https://gitlab.com/api/v4/merge_requests");
+        assert_eq!(request.params.get("scope"), Some(&"all".to_string()));
+        assert!(!request.headers.contains_key("PRIVATE-TOKEN"));
+
+        let mut engine = GitLab::new();
+        let config: EngineConfig =
+            serde_yaml::from_str("name: gitlab\nengine: gitlab\nsearch_type: wikis").unwrap();
+        assert!(engine.init(&config).is_err());
+    }
+
+    #[test]
+    fn test_gitlab_projects_response() {
+        let engine = GitLab::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/gitlab_projects.json"));
+        let results = engine.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].url,
+            "https://gitlab.com/gitlab-org/gitlab-runner"
+        );
+        assert_eq!(results[0].title, "gitlab-org/gitlab-runner");
+        assert_eq!(results[0].result_type, ResultType::Code);
+        assert_eq!(results[0].metadata.stars, Some(2431));
+        assert_eq!(
+            results[0].metadata.updated_date.as_deref(),
+            Some("2025-05-02T09:14:11.402Z")
+        );
+        assert!(results[0].content.as_deref().unwrap().contains("CI/CD"));
+        // Not part of the project list
+        assert_eq!(results[0].metadata.language, None);
+    }
+
+    #[test]
+    fn test_gitlab_issues_response() {
+        let engine = gitlab("name: gitlab issues\nengine: gitlab\nsearch_type: issues");
+        let response = EngineResponse::new(200, include_str!("fixtures/gitlab_issues.json"));
+        let results = engine.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].engine, "gitlab issues");
+        assert_eq!(results[0].title, "Runner fails to pull image behind proxy");
+        assert_eq!(
+            results[0].content.as_deref(),
+            Some("gitlab-org/gitlab-runner#4123 (opened) - The runner ignores HTTP_PROXY.")
+        );
+        assert_eq!(results[0].metadata.author.as_deref(), Some("jdoe"));
+    }
+}
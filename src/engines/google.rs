@@ -1,6 +1,8 @@
 //! Google search engine implementation
 
+use super::dates;
 use super::traits::*;
+use crate::query::OperatorKind;
 use crate::results::Result;
 use anyhow::Result as AnyhowResult;
 use scraper::{Html, Selector};
@@ -124,6 +126,10 @@ impl Engine for Google {
         "google"
     }
 
+    fn languages(&self) -> Vec<&str> {
+        crate::locales::language_codes()
+    }
+
     fn about(&self) -> EngineAbout {
         EngineAbout::new()
             .website("https://www.google.com")
@@ -147,6 +153,11 @@ impl Engine for Google {
         true
     }
 
+    fn supported_operators(&self) -> Vec<OperatorKind> {
+        // Standard web search syntax is passed through unchanged
+        OperatorKind::all()
+    }
+
     fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
         let mut query_params = HashMap::new();
         query_params.insert("q".to_string(), params.query.clone());
@@ -238,6 +249,10 @@ impl Engine for GoogleImages {
         "google_images"
     }
 
+    fn languages(&self) -> Vec<&str> {
+        crate::locales::language_codes()
+    }
+
     fn about(&self) -> EngineAbout {
         EngineAbout::new()
             .website("https://images.google.com")
@@ -407,10 +422,7 @@ impl Engine for GoogleImages {
                 result.metadata.thumbnail = thumbnail;
                 result.metadata.template = Some("images.html".to_string());
 
-                // Add resolution and source to content if available
-                if let Some(res) = resolution {
-                    result.metadata.file_size = Some(res);
-                }
+                result.metadata.resolution = resolution;
                 if let Some(src) = source {
                     result.metadata.author = Some(src);
                 }
@@ -448,6 +460,10 @@ impl Engine for GoogleNews {
         "google_news"
     }
 
+    fn languages(&self) -> Vec<&str> {
+        crate::locales::language_codes()
+    }
+
     fn about(&self) -> EngineAbout {
         EngineAbout::new()
             .website("https://news.google.com")
@@ -498,6 +514,9 @@ impl Engine for GoogleNews {
         let title_selector = Selector::parse("div.mCBkyc, div.n0jPhd").unwrap();
         let link_selector = Selector::parse("a").unwrap();
         let snippet_selector = Selector::parse("div.GI74Re, div.Y3v8qd").unwrap();
+        let source_selector = Selector::parse("div.MgUUmf span, div.CEMjEf span").unwrap();
+        // Relative dates such as "3 hours ago", in the interface language
+        let date_selector = Selector::parse("div.OSrXXb span, span.WG9SHc span").unwrap();
 
         let mut position = 1u32;
 
@@ -528,12 +547,23 @@ impl Engine for GoogleNews {
                 .next()
                 .map(|s| s.text().collect::<String>());
 
+            let text_of = |selector: &Selector| {
+                element
+                    .select(selector)
+                    .next()
+                    .map(|e| e.text().collect::<String>().trim().to_string())
+                    .filter(|t| !t.is_empty())
+            };
+
             let mut result = Result::new(url, title, self.name().to_string());
             if let Some(content) = snippet {
                 result = result.with_content(content);
             }
             result = result.with_position(position);
             result.result_type = crate::results::ResultType::News;
+            result.metadata.template = Some("news.html".to_string());
+            result.metadata.author = text_of(&source_selector);
+            result.metadata.published_date = text_of(&date_selector).and_then(|d| dates::parse(&d));
             position += 1;
 
             results.push(result);
@@ -0,0 +1,245 @@
+//! Hacker News search engine implementation
+//!
+//! Uses the Algolia Hacker News search API. Stories link to their URL, or to
+//! the discussion for Ask/Show HN posts without one; the discussion URL,
+//! points and comment count are kept in the metadata.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+/// Algolia search response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResponse {
+    #[serde(default)]
+    hits: Vec<Hit>,
+    nb_hits: Option<u64>,
+}
+
+/// Story hit
+#[derive(Debug, Deserialize)]
+struct Hit {
+    #[serde(rename = "objectID")]
+    object_id: String,
+    title: Option<String>,
+    url: Option<String>,
+    author: Option<String>,
+    points: Option<i64>,
+    num_comments: Option<u64>,
+    created_at_i: Option<i64>,
+    story_text: Option<String>,
+}
+
+/// Hacker News search engine
+pub struct HackerNews {
+    name: String,
+    api_url: String,
+    /// Sort by date instead of relevance
+    by_date: bool,
+}
+
+impl HackerNews {
+    pub fn new() -> Self {
+        Self {
+            name: "hackernews".to_string(),
+            api_url: "https://hn.algolia.com/api/v1".to_string(),
+            by_date: false,
+        }
+    }
+
+    fn item_url(id: &str) -> String {
+        format!("https://news.ycombinator.com/item?id={}", id)
+    }
+
+    /// Plain text of a story's HTML text
+    fn plain_text(html: &str) -> String {
+        let fragment = scraper::Html::parse_fragment(html);
+        fragment
+            .root_element()
+            .text()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Default for HackerNews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for HackerNews {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://news.ycombinator.com")
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["news", "it"]
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        self.by_date = config.extra_str("sort").as_deref() == Some("date");
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let endpoint = if self.by_date {
+            "search_by_date"
+        } else {
+            "search"
+        };
+        let mut request = EngineRequest::get(format!("{}/{}", self.api_url, endpoint))
+            .param("query", &params.query)
+            .param("tags", "story")
+            // Algolia pages start at 0
+            .param("page", (params.pageno.max(1) - 1).to_string())
+            .param("hitsPerPage", self.results_per_page().to_string());
+
+        if let Some(time_range) = params.time_range {
+            let since = Utc::now() - time_range.duration();
+            request = request.param(
+                "numericFilters",
+                format!("created_at_i>{}", since.timestamp()),
+            );
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: SearchResponse = response.json()?;
+        let mut results = Vec::new();
+
+        for hit in data.hits {
+            let Some(title) = hit.title.filter(|t| !t.is_empty()) else {
+                continue;
+            };
+            let comments_url = Self::item_url(&hit.object_id);
+            let url = hit
+                .url
+                .filter(|u| !u.is_empty())
+                .unwrap_or_else(|| comments_url.clone());
+
+            let mut result =
+                Result::new(url, title, self.name.clone()).with_position(results.len() as u32 + 1);
+            result.result_type = ResultType::News;
+            if let Some(text) = hit.story_text.as_deref().map(Self::plain_text) {
+                if !text.is_empty() {
+                    result = result.with_content(text);
+                }
+            }
+
+            let meta = &mut result.metadata;
+            meta.template = Some("discussion.html".to_string());
+            meta.author = hit.author;
+            meta.votes = hit.points;
+            meta.comment_count = hit.num_comments;
+            meta.comments_url = Some(comments_url);
+            meta.published_date = hit
+                .created_at_i
+                .and_then(|ts| DateTime::from_timestamp(ts, 0));
+
+            results.push(result);
+        }
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = data.nb_hits;
+        Ok(engine_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::TimeRange;
+
+    #[test]
+    fn test_hackernews_request() {
+        let engine = HackerNews::new();
+        let mut params = RequestParams::new("rust");
+        params.pageno = 2;
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://hn.algolia.com/api/v1/search");
+        assert_eq!(request.params.get("tags"), Some(&"story".to_string()));
+        assert_eq!(request.params.get("page"), Some(&"1".to_string()));
+        assert!(!request.params.contains_key("numericFilters"));
+
+        params.time_range = Some(TimeRange::Week);
+        let request = engine.request(&params).unwrap();
+        let since = request.params["numericFilters"]
+            .strip_prefix("created_at_i>")
+            .unwrap()
+            .parse::<i64>()
+            .unwrap();
+        let expected = Utc::now().timestamp() - 7 * 86_400;
+        assert!((since - expected).abs() < 60);
+
+        let mut engine = HackerNews::new();
+        let config: EngineConfig =
+            serde_yaml::from_str("name: hn\nengine: hackernews\nsort: date").unwrap();
+        engine.init(&config).unwrap();
+        let request = engine.request(&params).unwrap();
+        assert!(request.url.ends_with("/search_by_date"));
+    }
+
+    #[test]
+    fn test_hackernews_response() {
+        let engine = HackerNews::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/hackernews_search.json"));
+        let results = engine.response(response).unwrap();
+
+        assert_eq!(results.number_of_results, Some(52318));
+        assert_eq!(results.results.len(), 2);
+
+        let story = &results.results[0];
+        assert_eq!(
+            story.url,
+            "https://blog.rust-lang.org/2024/02/08/Rust-1.76.0.html"
+        );
+        assert_eq!(story.title, "Announcing Rust 1.76.0");
+        assert_eq!(story.result_type, ResultType::News);
+        assert_eq!(story.metadata.votes, Some(312));
+        assert_eq!(story.metadata.comment_count, Some(148));
+        assert_eq!(story.metadata.author.as_deref(), Some("steveklabnik"));
+        assert_eq!(
+            story.metadata.comments_url.as_deref(),
+            Some("https://news.ycombinator.com/item?id=39300000")
+        );
+        assert_eq!(
+            story.metadata.published_date,
+            "2024-02-08T15:04:05Z".parse().ok()
+        );
+
+        // Ask HN posts link to the discussion and carry their text
+        let ask = &results.results[1];
+        assert_eq!(ask.url, "https://news.ycombinator.com/item?id=39100000");
+        assert_eq!(
+            ask.content.as_deref(),
+            Some("Is it worth learning Rust in 2024? Asking for a friend.")
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Invidious search engine implementation
+//!
+//! Searches YouTube through the API of an Invidious instance at `base_url`.
+//! Results link to and embed from the instance instead of YouTube.
+
+use super::media;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::query::TimeRange;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use chrono::DateTime;
+use serde::Deserialize;
+
+/// Search result; channels and playlists share the list with videos
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    #[serde(rename = "type")]
+    kind: String,
+    title: Option<String>,
+    video_id: Option<String>,
+    author: Option<String>,
+    #[serde(default)]
+    video_thumbnails: Vec<Thumbnail>,
+    description: Option<String>,
+    view_count: Option<u64>,
+    /// Unix timestamp
+    published: Option<i64>,
+    length_seconds: Option<u64>,
+    #[serde(default)]
+    live_now: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Thumbnail {
+    quality: String,
+    url: String,
+}
+
+/// Invidious search engine
+pub struct Invidious {
+    name: String,
+    base_url: String,
+}
+
+impl Invidious {
+    pub fn new() -> Self {
+        Self {
+            name: "invidious".to_string(),
+            base_url: "https://yewtu.be".to_string(),
+        }
+    }
+
+    fn video_result(&self, item: Item) -> Option<Result> {
+        let video_id = item.video_id.filter(|id| !id.is_empty())?;
+        let title = item.title.filter(|t| !t.is_empty())?;
+        let url = format!("{}/watch?v={}", self.base_url, video_id);
+
+        let mut result = Result::new(url, title, self.name.clone());
+        result.result_type = ResultType::Video;
+        if let Some(description) = item.description.filter(|d| !d.trim().is_empty()) {
+            result = result.with_content(description.trim().to_string());
+        }
+
+        let thumbnail = item
+            .video_thumbnails
+            .iter()
+            .find(|t| t.quality == "medium")
+            .or(item.video_thumbnails.first())
+            .map(|t| media::absolute_url(&self.base_url, &t.url));
+
+        let meta = &mut result.metadata;
+        meta.template = Some("videos.html".to_string());
+        meta.thumbnail = thumbnail;
+        meta.author = item.author.filter(|a| !a.is_empty());
+        meta.views = item.view_count;
+        // Live streams report a length of 0
+        meta.duration = item
+            .length_seconds
+            .filter(|s| *s > 0 && !item.live_now)
+            .map(media::format_duration);
+        meta.published_date = item
+            .published
+            .and_then(|ts| DateTime::from_timestamp(ts, 0));
+        meta.iframe_src = Some(format!("{}/embed/{}", self.base_url, video_id));
+        Some(result)
+    }
+}
+
+impl Default for Invidious {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Invidious {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(false)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["videos", "music"]
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut request = EngineRequest::get(format!("{}/api/v1/search", self.base_url))
+            .param("q", &params.query)
+            .param("type", "video")
+            .param("page", params.pageno.max(1).to_string());
+
+        if let Some(time_range) = params.time_range {
+            let date = match time_range {
+                TimeRange::Day => "today",
+                TimeRange::Week => "week",
+                TimeRange::Month => "month",
+                TimeRange::Year => "year",
+            };
+            request = request.param("date", date);
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let items: Vec<Item> = response.json()?;
+        let results = items
+            .into_iter()
+            .filter(|item| item.kind == "video")
+            .filter_map(|item| self.video_result(item))
+            .enumerate()
+            .map(|(i, r)| r.with_position(i as u32 + 1))
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invidious_request() {
+        let engine = Invidious::new();
+        let mut params = RequestParams::new("rust");
+        params.pageno = 2;
+        params.time_range = Some(TimeRange::Week);
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://yewtu.be/api/v1/search");
+        assert_eq!(request.params.get("type"), Some(&"video".to_string()));
+        assert_eq!(request.params.get("page"), Some(&"2".to_string()));
+        assert_eq!(request.params.get("date"), Some(&"week".to_string()));
+    }
+
+    #[test]
+    fn test_invidious_response() {
+        let mut engine = Invidious::new();
+        let config: EngineConfig = serde_yaml::from_str(
+            "name: invidious\nengine: invidious\nbase_url: https://inv.example.org/",
+        )
+        .unwrap();
+        engine.init(&config).unwrap();
+
+        let response = EngineResponse::new(200, include_str!("fixtures/invidious_search.json"));
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.results.len(), 2);
+
+        let video = &results.results[0];
+        assert_eq!(video.url, "https://inv.example.org/watch?v=dQw4w9WgXcQ");
+        assert_eq!(video.result_type, ResultType::Video);
+        assert_eq!(video.metadata.author.as_deref(), Some("Rust Videos"));
+        assert_eq!(video.metadata.duration.as_deref(), Some("1:02:05"));
+        assert_eq!(video.metadata.views, Some(15321));
+        assert_eq!(
+            video.metadata.thumbnail.as_deref(),
+            Some("https://inv.example.org/vi/dQw4w9WgXcQ/mqdefault.jpg")
+        );
+        assert_eq!(
+            video.metadata.iframe_src.as_deref(),
+            Some("https://inv.example.org/embed/dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            video.metadata.published_date,
+            "2024-02-08T15:04:05Z".parse().ok()
+        );
+
+        // Live streams have no duration
+        assert_eq!(results.results[1].metadata.duration, None);
+    }
+}
@@ -0,0 +1,385 @@
+//! Lemmy search engine implementation
+//!
+//! Uses `/api/v3/search` of the instance at `base_url`, which also finds
+//! posts and communities federated from other instances. Posts are searched
+//! by default; set `search_type` to `communities` or `all`.
+
+use super::dates;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::query::TimeRange;
+use crate::results::Result;
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+
+/// What the engine searches for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchType {
+    Posts,
+    Communities,
+    All,
+}
+
+impl SearchType {
+    fn parse(value: &str) -> AnyhowResult<Self> {
+        match value {
+            "posts" => Ok(Self::Posts),
+            "communities" => Ok(Self::Communities),
+            "all" => Ok(Self::All),
+            other => Err(anyhow::anyhow!(
+                "Unknown Lemmy search_type '{}' (expected posts, communities or all)",
+                other
+            )),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Posts => "Posts",
+            Self::Communities => "Communities",
+            Self::All => "All",
+        }
+    }
+}
+
+/// `GET /api/v3/search` response
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    posts: Vec<PostView>,
+    #[serde(default)]
+    communities: Vec<CommunityView>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostView {
+    post: Post,
+    creator: Person,
+    community: Community,
+    counts: PostCounts,
+}
+
+#[derive(Debug, Deserialize)]
+struct Post {
+    name: String,
+    url: Option<String>,
+    body: Option<String>,
+    published: Option<String>,
+    /// Canonical URL of the post on its home instance
+    ap_id: String,
+    thumbnail_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Person {
+    name: String,
+    actor_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Community {
+    name: String,
+    title: Option<String>,
+    description: Option<String>,
+    actor_id: String,
+    icon: Option<String>,
+    published: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostCounts {
+    score: Option<i64>,
+    comments: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommunityView {
+    community: Community,
+    counts: CommunityCounts,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommunityCounts {
+    subscribers: Option<u64>,
+}
+
+/// Lemmy search engine
+pub struct Lemmy {
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+    search_type: SearchType,
+    /// Sort by date instead of score
+    by_date: bool,
+}
+
+impl Lemmy {
+    pub fn new() -> Self {
+        Self {
+            name: "lemmy".to_string(),
+            base_url: "https://lemmy.world".to_string(),
+            api_key: None,
+            search_type: SearchType::Posts,
+            by_date: false,
+        }
+    }
+
+    /// Host of a URL, i.e. the instance it belongs to
+    fn instance(url: &str) -> Option<String> {
+        url::Url::parse(url).ok()?.host_str().map(str::to_string)
+    }
+
+    /// `name@instance` of a person or community
+    fn qualified(name: &str, actor_id: &str) -> String {
+        match Self::instance(actor_id) {
+            Some(instance) => format!("{}@{}", name, instance),
+            None => name.to_string(),
+        }
+    }
+
+    fn sort(&self, time_range: Option<TimeRange>) -> &'static str {
+        match time_range {
+            Some(TimeRange::Day) => "TopDay",
+            Some(TimeRange::Week) => "TopWeek",
+            Some(TimeRange::Month) => "TopMonth",
+            Some(TimeRange::Year) => "TopYear",
+            None if self.by_date => "New",
+            None => "TopAll",
+        }
+    }
+
+    fn post_result(&self, view: PostView) -> Result {
+        let PostView {
+            post,
+            creator,
+            community,
+            counts,
+        } = view;
+        let url = post
+            .url
+            .filter(|u| !u.is_empty())
+            .unwrap_or_else(|| post.ap_id.clone());
+
+        let mut result = Result::new(url, post.name, self.name.clone());
+        if let Some(body) = post.body.filter(|b| !b.trim().is_empty()) {
+            result = result.with_content(body.trim().to_string());
+        }
+
+        let meta = &mut result.metadata;
+        meta.template = Some("discussion.html".to_string());
+        meta.instance = Self::instance(&post.ap_id);
+        meta.author = Some(Self::qualified(&creator.name, &creator.actor_id));
+        meta.published_date = post.published.as_deref().and_then(dates::parse);
+        meta.votes = counts.score;
+        meta.comment_count = counts.comments;
+        meta.comments_url = Some(post.ap_id);
+        meta.thumbnail = post.thumbnail_url;
+        meta.tags = vec![format!(
+            "!{}",
+            Self::qualified(&community.name, &community.actor_id)
+        )];
+        result
+    }
+
+    fn community_result(&self, view: CommunityView) -> Result {
+        let community = view.community;
+        let handle = format!("!{}", Self::qualified(&community.name, &community.actor_id));
+        let title = match community.title.as_deref().map(str::trim) {
+            Some(title) if !title.is_empty() => format!("{} ({})", title, handle),
+            _ => handle,
+        };
+
+        let mut result = Result::new(community.actor_id.clone(), title, self.name.clone());
+        let description = community
+            .description
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or_default();
+        let content = match view.counts.subscribers {
+            Some(subscribers) if description.is_empty() => format!("{} subscribers", subscribers),
+            Some(subscribers) => format!("{} subscribers · {}", subscribers, description),
+            None => description.to_string(),
+        };
+        if !content.is_empty() {
+            result = result.with_content(content);
+        }
+
+        result.metadata.instance = Self::instance(&community.actor_id);
+        result.metadata.thumbnail = community.icon;
+        result.metadata.published_date = community.published.as_deref().and_then(dates::parse);
+        result
+    }
+}
+
+impl Default for Lemmy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Lemmy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["social"]
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.api_key = config.api_key.clone().filter(|k| !k.is_empty());
+        if let Some(search_type) = config.extra_str("search_type") {
+            self.search_type = SearchType::parse(&search_type)?;
+        }
+        self.by_date = config.extra_str("sort").as_deref() == Some("date");
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut request = EngineRequest::get(format!("{}/api/v3/search", self.base_url))
+            .param("q", &params.query)
+            .param("type_", self.search_type.as_str())
+            .param("sort", self.sort(params.time_range))
+            .param("listing_type", "All")
+            .param("page", params.pageno.max(1).to_string())
+            .param("limit", self.results_per_page().to_string());
+
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            if response.status == 401 {
+                return Err(anyhow::anyhow!("Lemmy API requires a valid api_key (401)"));
+            }
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: SearchResponse = response.json()?;
+        let posts = data.posts.into_iter().map(|p| self.post_result(p));
+        let communities = data
+            .communities
+            .into_iter()
+            .map(|c| self.community_result(c));
+
+        let results = communities
+            .chain(posts)
+            .enumerate()
+            .map(|(i, r)| r.with_position(i as u32 + 1))
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lemmy(yaml: &str) -> Lemmy {
+        let mut engine = Lemmy::new();
+        let config: EngineConfig = serde_yaml::from_str(yaml).unwrap();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    /// Whether `init` rejects the extra configuration
+    fn lemmy_init_error(extra: &str) -> bool {
+        let config: EngineConfig =
+            serde_yaml::from_str(&format!("name: lemmy\nengine: lemmy\n{}", extra)).unwrap();
+        Lemmy::new().init(&config).is_err()
+    }
+
+    #[test]
+    fn test_lemmy_request() {
+        let engine = lemmy("name: lemmy\nengine: lemmy");
+        let mut params = RequestParams::new("rust");
+        params.pageno = 2;
+        let request = engine.request(&params).unwrap();
+        assert_eq!(request.url, "https://lemmy.world/api/v3/search");
+        assert_eq!(request.params.get("type_"), Some(&"Posts".to_string()));
+        assert_eq!(request.params.get("sort"), Some(&"TopAll".to_string()));
+        assert_eq!(request.params.get("page"), Some(&"2".to_string()));
+        assert!(!request.headers.contains_key("Authorization"));
+
+        params.time_range = Some(TimeRange::Week);
+        let request = engine.request(&params).unwrap();
+        assert_eq!(request.params.get("sort"), Some(&"TopWeek".to_string()));
+
+        let engine = lemmy(
+            "name: lemmy ml\nengine: lemmy\nbase_url: https://lemmy.ml\n\
+             search_type: all\nsort: date\napi_key: secret",
+        );
+        let request = engine.request(&RequestParams::new("rust")).unwrap();
+        assert_eq!(request.params.get("type_"), Some(&"All".to_string()));
+        assert_eq!(request.params.get("sort"), Some(&"New".to_string()));
+        assert_eq!(
+            request.headers.get("Authorization"),
+            Some(&"Bearer secret".to_string())
+        );
+        assert!(lemmy_init_error("search_type: users"));
+    }
+
+    #[test]
+    fn test_lemmy_response() {
+        let engine = lemmy("name: lemmy\nengine: lemmy");
+        let response = EngineResponse::new(200, include_str!("fixtures/lemmy_search.json"));
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.results.len(), 3);
+
+        let community = &results.results[0];
+        assert_eq!(community.url, "https://programming.dev/c/rust");
+        assert_eq!(community.title, "Rust (!rust@programming.dev)");
+        assert_eq!(
+            community.content.as_deref(),
+            Some("9120 subscribers · Discuss the Rust programming language.")
+        );
+
+        let link = &results.results[1];
+        assert_eq!(
+            link.url,
+            "https://blog.rust-lang.org/2024/02/08/Rust-1.76.0.html"
+        );
+        assert_eq!(link.metadata.author.as_deref(), Some("alice@lemmy.ml"));
+        assert_eq!(link.metadata.instance.as_deref(), Some("lemmy.ml"));
+        assert_eq!(link.metadata.votes, Some(87));
+        assert_eq!(link.metadata.comment_count, Some(14));
+        assert_eq!(
+            link.metadata.comments_url.as_deref(),
+            Some("https://lemmy.ml/post/1234")
+        );
+        assert_eq!(link.metadata.tags, vec!["!rust@programming.dev"]);
+        assert_eq!(
+            link.metadata.published_date,
+            "2024-02-08T16:20:00.123456Z".parse().ok()
+        );
+
+        // Text posts link to the post; dates without offset are UTC
+        let text = &results.results[2];
+        assert_eq!(text.url, "https://programming.dev/post/5678");
+        assert_eq!(text.content.as_deref(), Some("Which one do you use?"));
+        assert_eq!(
+            text.metadata.published_date,
+            "2023-06-20T10:00:00.654321Z".parse().ok()
+        );
+    }
+}
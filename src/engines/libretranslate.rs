@@ -0,0 +1,257 @@
+//! LibreTranslate translation engine implementation
+//!
+//! Answers `en-de <text>` and `translate <text> to de` queries with a
+//! translation from a LibreTranslate server, public or self-hosted at
+//! `base_url`. Other searches don't reach the engine.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::Answer;
+use anyhow::Result as AnyhowResult;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+
+/// Languages offered by LibreTranslate
+const LANGUAGES: &[&str] = &[
+    "ar", "az", "bg", "bn", "ca", "cs", "da", "de", "el", "en", "eo", "es", "et", "fa", "fi", "fr",
+    "ga", "he", "hi", "hu", "id", "it", "ja", "ko", "lt", "lv", "ms", "nb", "nl", "pl", "pt", "ro",
+    "ru", "sk", "sl", "sq", "sv", "th", "tl", "tr", "uk", "ur", "zh",
+];
+
+/// `en-de <text>`
+static LANGUAGE_PAIR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)^([a-z]{2})-([a-z]{2})\s+(.+)$").unwrap());
+
+/// `translate <text> [from en] to de`
+static TRANSLATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)^translate\s+(.+?)(?:\s+from\s+([a-z]{2}))?\s+(?:to|into)\s+([a-z]{2})$")
+        .unwrap()
+});
+
+/// Text to translate, parsed from a query
+#[derive(Debug, PartialEq)]
+struct Translation {
+    /// Source language, or `auto` to detect it
+    source: String,
+    target: String,
+    text: String,
+}
+
+impl Translation {
+    fn parse(query: &str) -> Option<Self> {
+        let query = query.trim();
+        let translation = if let Some(captures) = LANGUAGE_PAIR.captures(query) {
+            Self {
+                source: captures[1].to_lowercase(),
+                target: captures[2].to_lowercase(),
+                text: captures[3].to_string(),
+            }
+        } else {
+            let captures = TRANSLATE.captures(query)?;
+            Self {
+                source: captures
+                    .get(2)
+                    .map_or("auto".to_string(), |s| s.as_str().to_lowercase()),
+                target: captures[3].to_lowercase(),
+                text: captures[1].to_string(),
+            }
+        };
+
+        // Quotes only keep the text together
+        let translation = Self {
+            text: translation.text.trim_matches('"').trim().to_string(),
+            ..translation
+        };
+
+        let known = |code: &str| LANGUAGES.contains(&code);
+        let valid = (translation.source == "auto" || known(&translation.source))
+            && known(&translation.target)
+            && translation.source != translation.target;
+        valid.then_some(translation)
+    }
+}
+
+/// `POST /translate` response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslateResponse {
+    translated_text: Option<String>,
+    error: Option<String>,
+}
+
+/// LibreTranslate translation engine
+pub struct LibreTranslate {
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl LibreTranslate {
+    pub fn new() -> Self {
+        Self {
+            name: "libretranslate".to_string(),
+            base_url: "https://libretranslate.com".to_string(),
+            api_key: None,
+        }
+    }
+}
+
+impl Default for LibreTranslate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for LibreTranslate {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["general"]
+    }
+
+    fn supports_paging(&self) -> bool {
+        false
+    }
+
+    fn matches_query(&self, query: &str) -> bool {
+        Translation::parse(query).is_some()
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.api_key = config.api_key.clone().filter(|k| !k.is_empty());
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let translation = Translation::parse(&params.full_query)
+            .ok_or_else(|| anyhow::anyhow!("Not a translation query: {}", params.full_query))?;
+
+        let mut body = json!({
+            "q": translation.text,
+            "source": translation.source,
+            "target": translation.target,
+            "format": "text",
+        });
+        if let Some(api_key) = &self.api_key {
+            body["api_key"] = json!(api_key);
+        }
+        Ok(EngineRequest::post(format!("{}/translate", self.base_url)).json(body))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        // Errors come as {"error": ...}, e.g. for a missing API key
+        let data = response.json::<TranslateResponse>().ok();
+        if !response.is_success() {
+            return Err(match data.and_then(|d| d.error) {
+                Some(error) => anyhow::anyhow!("LibreTranslate error: {}", error),
+                None => anyhow::anyhow!("HTTP error: {}", response.status),
+            });
+        }
+
+        let data = data.ok_or_else(|| anyhow::anyhow!("Invalid LibreTranslate response"))?;
+        let mut engine_results = EngineResults::new();
+        if let Some(text) = data.translated_text.filter(|t| !t.trim().is_empty()) {
+            engine_results.add_answer(Answer::new(text, self.name.clone()));
+        }
+        Ok(engine_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_libretranslate_query() {
+        assert_eq!(
+            Translation::parse("en-de good morning"),
+            Some(Translation {
+                source: "en".to_string(),
+                target: "de".to_string(),
+                text: "good morning".to_string(),
+            })
+        );
+        assert_eq!(
+            Translation::parse("Translate good morning to FR"),
+            Some(Translation {
+                source: "auto".to_string(),
+                target: "fr".to_string(),
+                text: "good morning".to_string(),
+            })
+        );
+        assert_eq!(
+            Translation::parse("translate guten Morgen from de into es")
+                .map(|t| (t.source, t.text)),
+            Some(("de".to_string(), "guten Morgen".to_string()))
+        );
+
+        assert_eq!(
+            Translation::parse(r#"translate "good morning" to de"#).map(|t| t.text),
+            Some("good morning".to_string())
+        );
+        assert_eq!(
+            Translation::parse(r#"en-de "good morning""#).map(|t| t.text),
+            Some("good morning".to_string())
+        );
+
+        // Unknown or identical languages and ordinary searches
+        assert_eq!(Translation::parse("xx-de hello"), None);
+        assert_eq!(Translation::parse("en-en hello"), None);
+        assert_eq!(Translation::parse("how to translate a book"), None);
+        assert!(!LibreTranslate::new().matches_query("rust programming"));
+    }
+
+    #[test]
+    fn test_libretranslate_request() {
+        let mut engine = LibreTranslate::new();
+        let config: EngineConfig = serde_yaml::from_str(
+            "name: libretranslate\nengine: libretranslate\nbase_url: http://localhost:5000/\napi_key: secret",
+        )
+        .unwrap();
+        engine.init(&config).unwrap();
+
+        let request = engine
+            .request(&RequestParams::new("en-de good morning"))
+            .unwrap();
+        assert_eq!(request.url, "http://localhost:5000/translate");
+        let Some(RequestBody::Json(body)) = request.data else {
+            panic!("expected a JSON body");
+        };
+        assert_eq!(body["q"], "good morning");
+        assert_eq!(body["source"], "en");
+        assert_eq!(body["target"], "de");
+        assert_eq!(body["api_key"], "secret");
+    }
+
+    #[test]
+    fn test_libretranslate_response() {
+        let engine = LibreTranslate::new();
+        let response = EngineResponse::new(200, r#"{"translatedText": "Guten Morgen"}"#);
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.answers.len(), 1);
+        assert_eq!(results.answers[0].answer, "Guten Morgen");
+        assert_eq!(results.answers[0].engine, "libretranslate");
+
+        let response = EngineResponse::new(
+            400,
+            r#"{"error": "Visit https://portal.libretranslate.com to get an API key"}"#,
+        );
+        let error = engine.response(response).unwrap_err();
+        assert!(error.to_string().contains("API key"));
+    }
+}
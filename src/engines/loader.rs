@@ -2,7 +2,13 @@
 
 use super::registry::EngineRegistry;
 use super::traits::Engine;
-use super::{arxiv, bing, brave, duckduckgo, github, google, stackoverflow, wikipedia, youtube};
+use super::{
+    arxiv, bandcamp, bing, bookmarks, brave, crates, crossref, duckduckgo, elasticsearch, feed,
+    gitea, github, gitlab, google, hackernews, invidious, lemmy, libretranslate, lobsters,
+    local_index, mastodon, meilisearch, nominatim, npm, peertube, piped, pubmed, pypi, searx,
+    semantic_scholar, solr, soundcloud, sqlite, stackexchange, wikicommons, wikidata, wikipedia,
+    wiktionary, youtube,
+};
 use crate::config::{EngineConfig, Settings};
 use anyhow::Result;
 use std::sync::Arc;
@@ -44,14 +50,51 @@ impl EngineLoader {
             "google_images" => Box::new(google::GoogleImages::new()),
             "google_news" => Box::new(google::GoogleNews::new()),
             "duckduckgo" => Box::new(duckduckgo::DuckDuckGo::new()),
+            "duckduckgo_images" => Box::new(duckduckgo::DuckDuckGoImages::new()),
+            "duckduckgo_news" => Box::new(duckduckgo::DuckDuckGoNews::new()),
             "bing" => Box::new(bing::Bing::new()),
             "bing_images" => Box::new(bing::BingImages::new()),
+            "bing_news" => Box::new(bing::BingNews::new()),
             "brave" => Box::new(brave::Brave::new()),
+            "brave_images" => Box::new(brave::BraveImages::new()),
+            "brave_news" => Box::new(brave::BraveNews::new()),
             "wikipedia" => Box::new(wikipedia::Wikipedia::new()),
+            "wikidata" => Box::new(wikidata::Wikidata::new()),
+            "nominatim" => Box::new(nominatim::Nominatim::new()),
+            "crates_io" => Box::new(crates::CratesIo::new()),
+            "docs_rs" => Box::new(crates::DocsRs::new()),
+            "pypi" => Box::new(pypi::PyPI::new()),
+            "npm" => Box::new(npm::Npm::new()),
             "youtube" => Box::new(youtube::YouTube::new()),
             "github" => Box::new(github::GitHub::new()),
-            "stackoverflow" => Box::new(stackoverflow::StackOverflow::new()),
+            "gitlab" => Box::new(gitlab::GitLab::new()),
+            "gitea" => Box::new(gitea::Gitea::new()),
+            // "stackoverflow" is kept for configs predating the StackExchange engine
+            "stackexchange" | "stackoverflow" => Box::new(stackexchange::StackExchange::new()),
             "arxiv" => Box::new(arxiv::ArXiv::new()),
+            "crossref" => Box::new(crossref::Crossref::new()),
+            "semantic_scholar" => Box::new(semantic_scholar::SemanticScholar::new()),
+            "pubmed" => Box::new(pubmed::PubMed::new()),
+            "feed" => Box::new(feed::Feeds::new()),
+            "local_index" => Box::new(local_index::LocalIndex::new()),
+            "meilisearch" => Box::new(meilisearch::Meilisearch::new()),
+            "elasticsearch" | "opensearch" => Box::new(elasticsearch::Elasticsearch::new()),
+            "solr" => Box::new(solr::Solr::new()),
+            "sqlite" => Box::new(sqlite::Sqlite::new()),
+            "searx" => Box::new(searx::Searx::new()),
+            "bookmarks" => Box::new(bookmarks::Bookmarks::new()),
+            "hackernews" => Box::new(hackernews::HackerNews::new()),
+            "lobsters" => Box::new(lobsters::Lobsters::new()),
+            "mastodon" => Box::new(mastodon::Mastodon::new()),
+            "lemmy" => Box::new(lemmy::Lemmy::new()),
+            "invidious" => Box::new(invidious::Invidious::new()),
+            "piped" => Box::new(piped::Piped::new()),
+            "peertube" => Box::new(peertube::PeerTube::new()),
+            "soundcloud" => Box::new(soundcloud::SoundCloud::new()),
+            "bandcamp" => Box::new(bandcamp::Bandcamp::new()),
+            "wikicommons" => Box::new(wikicommons::WikiCommons::new()),
+            "wiktionary" => Box::new(wiktionary::Wiktionary::new()),
+            "libretranslate" => Box::new(libretranslate::LibreTranslate::new()),
             _ => {
                 return Err(anyhow::anyhow!("Unknown engine type: {}", engine_type));
             }
@@ -73,14 +116,51 @@ impl EngineLoader {
             "google_images",
             "google_news",
             "duckduckgo",
+            "duckduckgo_images",
+            "duckduckgo_news",
             "bing",
             "bing_images",
+            "bing_news",
             "brave",
+            "brave_images",
+            "brave_news",
             "wikipedia",
+            "wikidata",
+            "nominatim",
+            "crates_io",
+            "docs_rs",
+            "pypi",
+            "npm",
             "youtube",
             "github",
-            "stackoverflow",
+            "gitlab",
+            "gitea",
+            "stackexchange",
             "arxiv",
+            "crossref",
+            "semantic_scholar",
+            "pubmed",
+            "feed",
+            "local_index",
+            "meilisearch",
+            "elasticsearch",
+            "opensearch",
+            "solr",
+            "sqlite",
+            "searx",
+            "bookmarks",
+            "hackernews",
+            "lobsters",
+            "mastodon",
+            "lemmy",
+            "invidious",
+            "piped",
+            "peertube",
+            "soundcloud",
+            "bandcamp",
+            "wikicommons",
+            "wiktionary",
+            "libretranslate",
         ]
     }
 }
@@ -0,0 +1,219 @@
+//! Lobsters search engine implementation
+//!
+//! Uses the JSON version of the Lobsters story search. The search has no
+//! date filter, so time ranges are enforced on the stories' dates.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+/// Story as returned by `/search.json`
+#[derive(Debug, Deserialize)]
+struct Story {
+    title: Option<String>,
+    url: Option<String>,
+    score: Option<i64>,
+    comment_count: Option<u64>,
+    comments_url: Option<String>,
+    short_id_url: Option<String>,
+    created_at: Option<String>,
+    description_plain: Option<String>,
+    submitter_user: Option<Submitter>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Submitters are a name, or a user object in older versions
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Submitter {
+    Name(String),
+    User { username: String },
+}
+
+/// Lobsters search engine
+pub struct Lobsters {
+    name: String,
+    base_url: String,
+    /// Sort by date instead of relevance
+    by_date: bool,
+}
+
+impl Lobsters {
+    pub fn new() -> Self {
+        Self {
+            name: "lobsters".to_string(),
+            base_url: "https://lobste.rs".to_string(),
+            by_date: false,
+        }
+    }
+}
+
+impl Default for Lobsters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Lobsters {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(false)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["news", "it"]
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn filters_time_range_locally(&self) -> bool {
+        true
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.by_date = config.extra_str("sort").as_deref() == Some("date");
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        // Recent stories first, so a time range keeps a full page
+        let order = if self.by_date || params.time_range.is_some() {
+            "newest"
+        } else {
+            "relevance"
+        };
+        Ok(EngineRequest::get(format!("{}/search.json", self.base_url))
+            .param("q", &params.query)
+            .param("what", "stories")
+            .param("order", order)
+            .param("page", params.pageno.max(1).to_string()))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let stories: Vec<Story> = response.json()?;
+        let mut results = Vec::new();
+
+        for story in stories {
+            let Some(title) = story.title.filter(|t| !t.is_empty()) else {
+                continue;
+            };
+            let created = story
+                .created_at
+                .as_deref()
+                .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                .map(|d| d.with_timezone(&Utc));
+
+            let comments_url = story.comments_url.or(story.short_id_url);
+            let Some(url) = story
+                .url
+                .filter(|u| !u.is_empty())
+                .or_else(|| comments_url.clone())
+            else {
+                continue;
+            };
+
+            let mut result =
+                Result::new(url, title, self.name.clone()).with_position(results.len() as u32 + 1);
+            result.result_type = ResultType::News;
+            if let Some(description) = story.description_plain.filter(|d| !d.trim().is_empty()) {
+                result = result.with_content(description.trim().to_string());
+            }
+
+            let meta = &mut result.metadata;
+            meta.template = Some("discussion.html".to_string());
+            meta.author = story.submitter_user.map(|s| match s {
+                Submitter::Name(name) => name,
+                Submitter::User { username } => username,
+            });
+            meta.votes = story.score;
+            meta.comment_count = story.comment_count;
+            meta.comments_url = comments_url;
+            meta.tags = story.tags;
+            meta.published_date = created;
+
+            results.push(result);
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::TimeRange;
+
+    #[test]
+    fn test_lobsters_request() {
+        let engine = Lobsters::new();
+        let mut params = RequestParams::new("rust");
+        params.pageno = 2;
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://lobste.rs/search.json");
+        assert_eq!(request.params.get("what"), Some(&"stories".to_string()));
+        assert_eq!(request.params.get("order"), Some(&"relevance".to_string()));
+        assert_eq!(request.params.get("page"), Some(&"2".to_string()));
+
+        params.time_range = Some(TimeRange::Month);
+        let request = engine.request(&params).unwrap();
+        assert_eq!(request.params.get("order"), Some(&"newest".to_string()));
+    }
+
+    #[test]
+    fn test_lobsters_response() {
+        let engine = Lobsters::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/lobsters_search.json"));
+        let results = engine.response(response).unwrap();
+
+        assert_eq!(results.results.len(), 2);
+        let story = &results.results[0];
+        assert_eq!(story.url, "https://without.boats/blog/pinned-places/");
+        assert_eq!(story.result_type, ResultType::News);
+        assert_eq!(story.metadata.votes, Some(41));
+        assert_eq!(story.metadata.comment_count, Some(17));
+        assert_eq!(story.metadata.author.as_deref(), Some("alice"));
+        assert_eq!(story.metadata.tags, vec!["rust", "plt"]);
+        assert_eq!(
+            story.metadata.comments_url.as_deref(),
+            Some("https://lobste.rs/s/xq0ygz/pinned_places")
+        );
+        // Dates are normalized to UTC
+        assert_eq!(
+            story.metadata.published_date,
+            "2024-07-12T15:02:11Z".parse().ok()
+        );
+
+        // Text posts link to the discussion
+        let ask = &results.results[1];
+        assert_eq!(
+            ask.url,
+            "https://lobste.rs/s/b3kzfn/what_are_you_doing_this_week"
+        );
+        assert_eq!(ask.metadata.author.as_deref(), Some("bob"));
+        assert_eq!(
+            ask.content.as_deref(),
+            Some("Feel free to tell what you plan on doing.")
+        );
+    }
+}
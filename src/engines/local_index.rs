@@ -0,0 +1,721 @@
+//! Local full-text index engine implementation
+//!
+//! Indexes the Markdown, plain text and HTML files below a directory into an
+//! inverted index persisted on disk. The directory is rescanned every
+//! `refresh_interval` seconds and only new, changed or deleted files are
+//! reindexed. Matches are ranked with BM25.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::Result;
+use anyhow::Result as AnyhowResult;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// File extensions that are indexed
+const EXTENSIONS: [&str; 5] = ["md", "markdown", "txt", "html", "htm"];
+
+/// Bump when the index layout changes to force a rebuild
+const INDEX_VERSION: u32 = 1;
+
+/// BM25 term frequency saturation
+const K1: f64 = 1.2;
+/// BM25 document length normalization
+const B: f64 = 0.75;
+
+/// Number of words in a snippet
+const SNIPPET_WORDS: usize = 30;
+
+/// Default rescan interval in seconds
+const DEFAULT_REFRESH_INTERVAL: u64 = 60;
+
+/// Markdown links and images: keep the link text
+static MARKDOWN_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"!?\[([^\]]*)\]\([^)]*\)").unwrap());
+
+/// Markdown markup characters
+static MARKDOWN_MARKUP: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*(#{1,6}|>|[-*+]|\d+\.)\s+|[*_`~]").unwrap());
+
+/// Indexed document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Document {
+    /// Path relative to the indexed directory, `/`-separated
+    path: String,
+    title: String,
+    /// Plain text, kept for snippets
+    text: String,
+    /// Number of tokens
+    length: u32,
+    /// Modification time (seconds since the epoch) and size when indexed
+    modified: u64,
+    size: u64,
+}
+
+/// Inverted index persisted as JSON
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+    version: u32,
+    /// Indexed directory, a different one invalidates the index
+    root: PathBuf,
+    next_id: u32,
+    documents: HashMap<u32, Document>,
+    /// Documents and term frequencies per term
+    postings: HashMap<String, Vec<(u32, u32)>>,
+}
+
+impl Index {
+    fn new(root: &Path) -> Self {
+        Self {
+            version: INDEX_VERSION,
+            root: root.to_path_buf(),
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, document: Document) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for token in document_tokens(&document) {
+            *frequencies.entry(token).or_default() += 1;
+        }
+        for (term, frequency) in frequencies {
+            self.postings.entry(term).or_default().push((id, frequency));
+        }
+
+        self.documents.insert(id, document);
+    }
+
+    fn remove(&mut self, id: u32) {
+        let Some(document) = self.documents.remove(&id) else {
+            return;
+        };
+        for token in document_tokens(&document) {
+            if let Some(postings) = self.postings.get_mut(&token) {
+                postings.retain(|(doc, _)| *doc != id);
+                if postings.is_empty() {
+                    self.postings.remove(&token);
+                }
+            }
+        }
+    }
+
+    /// Documents matching any of the terms with their BM25 scores, best first
+    fn search(&self, terms: &[String]) -> Vec<(u32, f64)> {
+        let total = self.documents.len() as f64;
+        if total == 0.0 {
+            return Vec::new();
+        }
+        let avg_length = self
+            .documents
+            .values()
+            .map(|d| d.length as f64)
+            .sum::<f64>()
+            / total;
+
+        let mut scores: HashMap<u32, f64> = HashMap::new();
+        for term in terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let df = postings.len() as f64;
+            let idf = (1.0 + (total - df + 0.5) / (df + 0.5)).ln();
+
+            for &(id, tf) in postings {
+                let length = self.documents[&id].length as f64;
+                let tf = tf as f64;
+                let norm = K1 * (1.0 - B + B * length / avg_length.max(1.0));
+                *scores.entry(id).or_default() += idf * tf * (K1 + 1.0) / (tf + norm);
+            }
+        }
+
+        let mut scores: Vec<(u32, f64)> = scores.into_iter().collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        scores
+    }
+}
+
+/// Lowercased alphanumeric tokens of a text
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Tokens of a document; title terms count twice
+fn document_tokens(document: &Document) -> impl Iterator<Item = String> + '_ {
+    tokenize(&document.title)
+        .chain(tokenize(&document.title))
+        .chain(tokenize(&document.text))
+}
+
+/// A file found while scanning the directory
+struct FileInfo {
+    path: PathBuf,
+    relative: String,
+    modified: u64,
+    size: u64,
+}
+
+/// Find indexable files below `dir`, skipping hidden files and directories
+///
+/// Only a failure to read `dir` itself is an error; entries below it that
+/// can't be read are logged and skipped.
+fn scan(root: &Path, dir: &Path, files: &mut Vec<FileInfo>) -> AnyhowResult<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("Failed to scan {}: {}", dir.display(), e);
+                continue;
+            }
+        };
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                warn!("Failed to scan {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if metadata.is_dir() {
+            if let Err(e) = scan(root, &path, files) {
+                warn!("Failed to scan {}: {}", path.display(), e);
+            }
+            continue;
+        }
+
+        let indexable = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| EXTENSIONS.contains(&e.to_lowercase().as_str()));
+        if !indexable {
+            continue;
+        }
+
+        let relative = path
+            .strip_prefix(root)?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        files.push(FileInfo {
+            path,
+            relative,
+            modified,
+            size: metadata.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Read a file into a document
+fn load_document(file: &FileInfo) -> AnyhowResult<Document> {
+    let raw = std::fs::read_to_string(&file.path)?;
+    let extension = file
+        .path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let (title, text) = match extension.as_str() {
+        "html" | "htm" => parse_html(&raw),
+        "md" | "markdown" => parse_markdown(&raw),
+        _ => (None, raw),
+    };
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let title = title.filter(|t| !t.is_empty()).unwrap_or_else(|| {
+        file.path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.relative.clone())
+    });
+
+    let mut document = Document {
+        path: file.relative.clone(),
+        title,
+        text,
+        length: 0,
+        modified: file.modified,
+        size: file.size,
+    };
+    document.length = document_tokens(&document).count() as u32;
+    Ok(document)
+}
+
+/// Title (first `#` heading) and plain text of a Markdown file
+fn parse_markdown(raw: &str) -> (Option<String>, String) {
+    let title = raw
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|t| t.trim().to_string());
+    let text = MARKDOWN_LINK.replace_all(raw, "$1");
+    let text = MARKDOWN_MARKUP.replace_all(&text, "");
+    (title, text.into_owned())
+}
+
+/// Title and body text of an HTML file
+fn parse_html(raw: &str) -> (Option<String>, String) {
+    static TITLE: Lazy<Selector> = Lazy::new(|| Selector::parse("title, h1").unwrap());
+
+    let document = Html::parse_document(raw);
+    let title = document
+        .select(&TITLE)
+        .map(|e| e.text().collect::<String>().trim().to_string())
+        .find(|t| !t.is_empty());
+
+    let text = document
+        .root_element()
+        .descendants()
+        .filter(|node| {
+            !node.ancestors().any(|a| {
+                a.value()
+                    .as_element()
+                    .is_some_and(|e| matches!(e.name(), "head" | "script" | "style"))
+            })
+        })
+        .filter_map(|node| node.value().as_text().map(|t| t.to_string()))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (title, text)
+}
+
+/// Escape text for inclusion in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Snippet around the densest cluster of query terms
+///
+/// Returns the plain snippet and an HTML version with the terms in `<mark>`.
+fn snippet(text: &str, terms: &[String]) -> (String, String) {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let is_match = |word: &str| tokenize(word).any(|t| terms.contains(&t));
+    let hits: Vec<bool> = words.iter().map(|w| is_match(w)).collect();
+
+    // Slide a window over the words and keep the one with most hits
+    let window = SNIPPET_WORDS.min(words.len());
+    let mut best = (0, 0);
+    let mut count = hits[..window].iter().filter(|h| **h).count();
+    best.1 = count;
+    for start in 1..=words.len() - window {
+        count = count + hits[start + window - 1] as usize - hits[start - 1] as usize;
+        if count > best.1 {
+            best = (start, count);
+        }
+    }
+
+    let range = best.0..best.0 + window;
+    let prefix = if range.start > 0 { "… " } else { "" };
+    let suffix = if range.end < words.len() { " …" } else { "" };
+
+    let plain = words[range.clone()].join(" ");
+    let html = range
+        .map(|i| match hits[i] {
+            true => format!("<mark>{}</mark>", escape_html(words[i])),
+            false => escape_html(words[i]),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (
+        format!("{}{}{}", prefix, plain, suffix),
+        format!("{}{}{}", prefix, html, suffix),
+    )
+}
+
+/// Full-text search engine over a local directory
+pub struct LocalIndex {
+    name: String,
+    root: PathBuf,
+    index_path: PathBuf,
+    /// URL prefix documents are served under; `file://` URLs otherwise
+    base_url: Option<String>,
+    refresh_interval: Duration,
+    index: RwLock<Index>,
+}
+
+impl LocalIndex {
+    pub fn new() -> Self {
+        Self {
+            name: "local_index".to_string(),
+            root: PathBuf::new(),
+            index_path: PathBuf::new(),
+            base_url: None,
+            refresh_interval: Duration::from_secs(DEFAULT_REFRESH_INTERVAL),
+            index: RwLock::new(Index::default()),
+        }
+    }
+
+    /// Load the index saved on disk, unless it is outdated or for another directory
+    fn load_index(&self) -> Index {
+        let saved = std::fs::read_to_string(&self.index_path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Index>(&json).ok())
+            .filter(|index| index.version == INDEX_VERSION && index.root == self.root);
+        saved.unwrap_or_else(|| Index::new(&self.root))
+    }
+
+    fn save_index(&self) -> AnyhowResult<()> {
+        if let Some(dir) = self.index_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string(&*self.index.read().unwrap())?;
+        // Write to a temporary file first so a crash never leaves half an index
+        let tmp = self.index_path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &self.index_path)?;
+        Ok(())
+    }
+
+    /// Reindex new, changed and deleted files, and save the index if it changed
+    fn update(&self) -> AnyhowResult<()> {
+        let mut files = Vec::new();
+        scan(&self.root, &self.root, &mut files)?;
+
+        // Work out the changes without blocking searches
+        let (removed, changed) = {
+            let index = self.index.read().unwrap();
+            let known: HashMap<&str, (u32, &Document)> = index
+                .documents
+                .iter()
+                .map(|(id, doc)| (doc.path.as_str(), (*id, doc)))
+                .collect();
+
+            let mut removed: Vec<u32> = known
+                .iter()
+                .filter(|(path, _)| !files.iter().any(|f| f.relative == **path))
+                .map(|(_, (id, _))| *id)
+                .collect();
+
+            let mut changed = Vec::new();
+            for file in &files {
+                if let Some((id, doc)) = known.get(file.relative.as_str()) {
+                    if doc.modified == file.modified && doc.size == file.size {
+                        continue;
+                    }
+                    removed.push(*id);
+                }
+                match load_document(file) {
+                    Ok(document) => changed.push(document),
+                    Err(e) => warn!("Failed to index {}: {}", file.path.display(), e),
+                }
+            }
+            (removed, changed)
+        };
+
+        if removed.is_empty() && changed.is_empty() {
+            return Ok(());
+        }
+
+        {
+            let mut index = self.index.write().unwrap();
+            for id in &removed {
+                index.remove(*id);
+            }
+            for document in changed {
+                index.add(document);
+            }
+            debug!(
+                "Engine {} indexed {} documents",
+                self.name,
+                index.documents.len()
+            );
+        }
+
+        self.save_index()
+    }
+
+    fn document_url(&self, document: &Document) -> String {
+        match &self.base_url {
+            Some(base_url) => {
+                let path = document
+                    .path
+                    .split('/')
+                    .map(|segment| urlencoding::encode(segment).into_owned())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                format!("{}/{}", base_url, path)
+            }
+            None => url::Url::from_file_path(self.root.join(&document.path))
+                .map(String::from)
+                .unwrap_or_else(|_| format!("file://{}", document.path)),
+        }
+    }
+}
+
+impl Default for LocalIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for LocalIndex {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new().results_format("local")
+    }
+
+    fn engine_type(&self) -> EngineType {
+        EngineType::Offline
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+
+        let path = config.extra_str("path").ok_or_else(|| {
+            anyhow::anyhow!("Local index engine '{}' needs a `path`", config.name)
+        })?;
+        self.root = std::fs::canonicalize(&path)
+            .map_err(|e| anyhow::anyhow!("Cannot index '{}': {}", path, e))?;
+
+        self.index_path = match config.extra_str("index_path") {
+            Some(index_path) => PathBuf::from(index_path),
+            None => dirs::cache_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("searxng-rs")
+                .join(format!("{}.json", config.name.replace(['/', ' '], "_"))),
+        };
+        self.base_url = config
+            .extra_str("base_url")
+            .map(|url| url.trim_end_matches('/').to_string());
+        if let Some(secs) = config.extra_value::<u64>("refresh_interval") {
+            self.refresh_interval = Duration::from_secs(secs.max(1));
+        }
+
+        *self.index.get_mut().unwrap() = self.load_index();
+        self.update()
+    }
+
+    fn search(&self, params: &RequestParams) -> AnyhowResult<EngineResults> {
+        let terms: Vec<String> = tokenize(&params.query).collect();
+        let index = self.index.read().unwrap();
+        let matches = index.search(&terms);
+
+        let per_page = self.results_per_page() as usize;
+        let offset = (params.pageno.max(1) as usize - 1) * per_page;
+
+        let results = matches
+            .iter()
+            .skip(offset)
+            .take(per_page)
+            .enumerate()
+            .map(|(i, (id, _))| {
+                let document = &index.documents[id];
+                let (content, html) = snippet(&document.text, &terms);
+                let mut result = Result::new(
+                    self.document_url(document),
+                    document.title.clone(),
+                    self.name().to_string(),
+                )
+                .with_position((offset + i) as u32 + 1)
+                .with_content(content);
+                result.metadata.snippet = Some(html);
+                result
+            })
+            .collect();
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = Some(matches.len() as u64);
+        Ok(engine_results)
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        Some(self.refresh_interval)
+    }
+
+    fn refresh(&self, _responses: Vec<AnyhowResult<EngineResponse>>) -> AnyhowResult<()> {
+        self.update()
+    }
+
+    fn request(&self, _params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Err(anyhow::anyhow!(
+            "The local index engine searches local files"
+        ))
+    }
+
+    fn response(&self, _response: EngineResponse) -> AnyhowResult<EngineResults> {
+        Err(anyhow::anyhow!(
+            "The local index engine searches local files"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_docs(dir: &Path) {
+        fs::create_dir_all(dir.join("guides")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::write(
+            dir.join("guides/deploy.md"),
+            "# Deploying the service\n\nRun `make deploy` to ship the **service** to \
+             [staging](https://staging.example.org). Deploy again for production.",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("oncall.html"),
+            "<html><head><title>On-call handbook</title><style>.deploy{}</style></head>\
+             <body><h1>On-call</h1><p>Page the owner before any deploy &amp; rollback.</p>\
+             <script>deploy()</script></body></html>",
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "Lunch menu for the offsite.").unwrap();
+        fs::write(dir.join("image.png"), "not text").unwrap();
+        fs::write(dir.join(".git/HEAD"), "deploy").unwrap();
+    }
+
+    fn local_index(dir: &Path, index_path: &Path, extra: &str) -> LocalIndex {
+        let mut engine = LocalIndex::new();
+        let config: EngineConfig = serde_yaml::from_str(&format!(
+            "name: docs\nengine: local_index\npath: {}\nindex_path: {}\n{}",
+            dir.display(),
+            index_path.display(),
+            extra
+        ))
+        .unwrap();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_local_index_search() {
+        let docs = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        write_docs(docs.path());
+        let engine = local_index(
+            docs.path(),
+            &cache.path().join("docs.json"),
+            "base_url: https://docs.example.org/",
+        );
+        assert_eq!(engine.index.read().unwrap().documents.len(), 3);
+
+        let results = engine.search(&RequestParams::new("deploy")).unwrap();
+        assert_eq!(results.number_of_results, Some(2));
+
+        // The guide mentions deploy more often
+        let guide = &results.results[0];
+        assert_eq!(guide.title, "Deploying the service");
+        assert_eq!(guide.url, "https://docs.example.org/guides/deploy.md");
+        assert!(guide
+            .content
+            .as_deref()
+            .unwrap()
+            .contains("Run make deploy to ship the service to staging."));
+
+        let oncall = &results.results[1];
+        assert_eq!(oncall.title, "On-call handbook");
+        assert_eq!(
+            oncall.metadata.snippet.as_deref(),
+            Some("On-call Page the owner before any <mark>deploy</mark> &amp; rollback.")
+        );
+
+        assert!(engine
+            .search(&RequestParams::new("kubernetes"))
+            .unwrap()
+            .results
+            .is_empty());
+    }
+
+    #[test]
+    fn test_local_index_file_urls() {
+        let docs = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        write_docs(docs.path());
+        let engine = local_index(docs.path(), &cache.path().join("docs.json"), "");
+
+        let results = engine
+            .search(&RequestParams::new("offsite"))
+            .unwrap()
+            .results;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].url.starts_with("file:///"));
+        assert!(results[0].url.ends_with("/notes.txt"));
+        assert_eq!(results[0].title, "notes");
+    }
+
+    #[test]
+    fn test_local_index_incremental_update() {
+        let docs = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let index_path = cache.path().join("docs.json");
+        write_docs(docs.path());
+        let engine = local_index(docs.path(), &index_path, "");
+        assert!(index_path.exists());
+
+        fs::remove_file(docs.path().join("oncall.html")).unwrap();
+        fs::write(
+            docs.path().join("notes.txt"),
+            "Deploy freeze over the holidays.",
+        )
+        .unwrap();
+        engine.refresh(vec![]).unwrap();
+
+        let results = engine
+            .search(&RequestParams::new("deploy"))
+            .unwrap()
+            .results;
+        let mut titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+        titles.sort_unstable();
+        assert_eq!(titles, vec!["Deploying the service", "notes"]);
+        assert!(!engine
+            .index
+            .read()
+            .unwrap()
+            .postings
+            .contains_key("rollback"));
+
+        // A new engine picks up the saved index
+        let reloaded = local_index(docs.path(), &index_path, "");
+        assert_eq!(reloaded.index.read().unwrap().documents.len(), 2);
+    }
+
+    #[test]
+    fn test_snippet_window() {
+        let text = (0..100)
+            .map(|i| {
+                if i == 70 {
+                    "needle".to_string()
+                } else {
+                    format!("w{}", i)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (plain, html) = snippet(&text, &["needle".to_string()]);
+        assert!(plain.starts_with("… w41 "));
+        assert!(plain.ends_with(" …"));
+        assert!(html.contains("<mark>needle</mark>"));
+    }
+}
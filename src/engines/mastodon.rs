@@ -0,0 +1,431 @@
+//! Mastodon search engine implementation
+//!
+//! Uses `/api/v2/search` of the instance at `base_url`. Accounts and hashtags
+//! can be searched anonymously; most instances only search statuses for
+//! authenticated users, so `statuses` needs an `api_key` (access token).
+
+use super::dates;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::Result;
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+
+/// What the engine searches for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchType {
+    All,
+    Statuses,
+    Accounts,
+    Hashtags,
+}
+
+impl SearchType {
+    fn parse(value: &str) -> AnyhowResult<Self> {
+        match value {
+            "all" => Ok(Self::All),
+            "statuses" => Ok(Self::Statuses),
+            "accounts" => Ok(Self::Accounts),
+            "hashtags" => Ok(Self::Hashtags),
+            other => Err(anyhow::anyhow!(
+                "Unknown Mastodon search_type '{}' (expected all, statuses, accounts or hashtags)",
+                other
+            )),
+        }
+    }
+
+    fn as_str(self) -> Option<&'static str> {
+        match self {
+            Self::All => None,
+            Self::Statuses => Some("statuses"),
+            Self::Accounts => Some("accounts"),
+            Self::Hashtags => Some("hashtags"),
+        }
+    }
+}
+
+/// `GET /api/v2/search` response
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    accounts: Vec<Account>,
+    #[serde(default)]
+    statuses: Vec<Status>,
+    #[serde(default)]
+    hashtags: Vec<Tag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Account {
+    /// `user` for local accounts, `user@domain` for remote ones
+    acct: String,
+    display_name: Option<String>,
+    url: Option<String>,
+    note: Option<String>,
+    avatar: Option<String>,
+    created_at: Option<String>,
+    followers_count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Status {
+    uri: String,
+    url: Option<String>,
+    created_at: Option<String>,
+    spoiler_text: Option<String>,
+    content: Option<String>,
+    account: Account,
+    reblogs_count: Option<u64>,
+    replies_count: Option<u64>,
+    #[serde(default)]
+    media_attachments: Vec<Attachment>,
+    #[serde(default)]
+    tags: Vec<Tag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Attachment {
+    preview_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tag {
+    name: String,
+    url: Option<String>,
+    #[serde(default)]
+    history: Vec<TagHistory>,
+}
+
+/// Daily usage of a hashtag; the API returns counts as strings
+#[derive(Debug, Deserialize)]
+struct TagHistory {
+    uses: String,
+    accounts: String,
+}
+
+/// Mastodon search engine
+pub struct Mastodon {
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+    search_type: SearchType,
+}
+
+impl Mastodon {
+    pub fn new() -> Self {
+        Self {
+            name: "mastodon".to_string(),
+            base_url: "https://mastodon.social".to_string(),
+            api_key: None,
+            search_type: SearchType::All,
+        }
+    }
+
+    /// Host of a URL, i.e. the instance it belongs to
+    fn instance(url: &str) -> Option<String> {
+        url::Url::parse(url).ok()?.host_str().map(str::to_string)
+    }
+
+    /// Plain text of a status or profile note
+    ///
+    /// Hashtags and mentions are split across inline elements, so only
+    /// paragraphs and line breaks separate words.
+    fn plain_text(html: &str) -> String {
+        let fragment = scraper::Html::parse_fragment(html);
+        let mut text = String::new();
+        for node in fragment.root_element().descendants() {
+            match node.value() {
+                scraper::Node::Text(t) => text.push_str(t),
+                scraper::Node::Element(e) if matches!(e.name(), "p" | "br") => text.push(' '),
+                _ => {}
+            }
+        }
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Full `@user@instance` handle of an account
+    fn handle(&self, account: &Account) -> String {
+        if account.acct.contains('@') {
+            return format!("@{}", account.acct);
+        }
+        let instance = account
+            .url
+            .as_deref()
+            .and_then(Self::instance)
+            .or_else(|| Self::instance(&self.base_url))
+            .unwrap_or_default();
+        format!("@{}@{}", account.acct, instance)
+    }
+
+    fn status_result(&self, status: Status) -> Result {
+        let author = self.handle(&status.account);
+        let url = status.url.filter(|u| !u.is_empty()).unwrap_or(status.uri);
+        let text = status
+            .content
+            .as_deref()
+            .map(Self::plain_text)
+            .unwrap_or_default();
+
+        // Content warnings stand in for the text they hide
+        let title = match status.spoiler_text.filter(|s| !s.trim().is_empty()) {
+            Some(warning) => warning.trim().to_string(),
+            None if text.chars().count() > 80 => {
+                format!("{}…", text.chars().take(80).collect::<String>())
+            }
+            None if !text.is_empty() => text.clone(),
+            None => format!("Post by {}", author),
+        };
+
+        let mut result = Result::new(url, title, self.name.clone());
+        if !text.is_empty() {
+            result = result.with_content(text);
+        }
+
+        let meta = &mut result.metadata;
+        meta.template = Some("discussion.html".to_string());
+        meta.instance = Self::instance(&result.url);
+        meta.author = Some(author);
+        meta.published_date = status.created_at.as_deref().and_then(dates::parse);
+        meta.boosts = status.reblogs_count;
+        meta.comment_count = status.replies_count;
+        meta.thumbnail = status
+            .media_attachments
+            .into_iter()
+            .find_map(|a| a.preview_url)
+            .or(status.account.avatar);
+        meta.tags = status.tags.into_iter().map(|t| t.name).collect();
+        result
+    }
+
+    fn account_result(&self, account: Account) -> Option<Result> {
+        let url = account.url.clone().filter(|u| !u.is_empty())?;
+        let handle = self.handle(&account);
+        let title = match account.display_name.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => format!("{} ({})", name, handle),
+            _ => handle.clone(),
+        };
+
+        let mut result = Result::new(url, title, self.name.clone());
+        let note = account
+            .note
+            .as_deref()
+            .map(Self::plain_text)
+            .unwrap_or_default();
+        let content = match account.followers_count {
+            Some(followers) if note.is_empty() => format!("{} followers", followers),
+            Some(followers) => format!("{} followers · {}", followers, note),
+            None => note,
+        };
+        if !content.is_empty() {
+            result = result.with_content(content);
+        }
+
+        result.metadata.instance = Self::instance(&result.url);
+        result.metadata.author = Some(handle);
+        result.metadata.thumbnail = account.avatar;
+        result.metadata.published_date = account.created_at.as_deref().and_then(dates::parse);
+        Some(result)
+    }
+
+    fn hashtag_result(&self, tag: Tag) -> Result {
+        let url = tag
+            .url
+            .filter(|u| !u.is_empty())
+            .unwrap_or_else(|| format!("{}/tags/{}", self.base_url, tag.name));
+        let mut result = Result::new(url, format!("#{}", tag.name), self.name.clone());
+
+        // History covers the last days, most recent first
+        let sum = |field: fn(&TagHistory) -> &str| -> u64 {
+            tag.history
+                .iter()
+                .filter_map(|h| field(h).parse::<u64>().ok())
+                .sum()
+        };
+        if !tag.history.is_empty() {
+            result = result.with_content(format!(
+                "{} posts by {} people in the past {} days",
+                sum(|h| &h.uses),
+                sum(|h| &h.accounts),
+                tag.history.len()
+            ));
+        }
+        result.metadata.instance = Self::instance(&self.base_url);
+        result
+    }
+}
+
+impl Default for Mastodon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Mastodon {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .api_key_required(self.search_type == SearchType::Statuses)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["social"]
+    }
+
+    /// Offsets only apply when searching a single type
+    fn supports_paging(&self) -> bool {
+        self.search_type != SearchType::All
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.api_key = config.api_key.clone().filter(|k| !k.is_empty());
+        if let Some(search_type) = config.extra_str("search_type") {
+            self.search_type = SearchType::parse(&search_type)?;
+        }
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let per_page = self.results_per_page();
+        let mut request = EngineRequest::get(format!("{}/api/v2/search", self.base_url))
+            .param("q", &params.query)
+            .param("limit", per_page.to_string())
+            .param("resolve", "false");
+
+        if let Some(kind) = self.search_type.as_str() {
+            request = request.param("type", kind).param(
+                "offset",
+                ((params.pageno.max(1) - 1) * per_page).to_string(),
+            );
+        }
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            if response.status == 401 {
+                return Err(anyhow::anyhow!(
+                    "Mastodon API requires a valid api_key (401)"
+                ));
+            }
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: SearchResponse = response.json()?;
+        let statuses = data.statuses.into_iter().map(|s| self.status_result(s));
+        let accounts = data
+            .accounts
+            .into_iter()
+            .filter_map(|a| self.account_result(a));
+        let hashtags = data.hashtags.into_iter().map(|t| self.hashtag_result(t));
+
+        let results = statuses
+            .chain(accounts)
+            .chain(hashtags)
+            .enumerate()
+            .map(|(i, r)| r.with_position(i as u32 + 1))
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mastodon(yaml: &str) -> Mastodon {
+        let mut engine = Mastodon::new();
+        let config: EngineConfig = serde_yaml::from_str(yaml).unwrap();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_mastodon_request() {
+        let engine = mastodon("name: mastodon\nengine: mastodon");
+        let request = engine.request(&RequestParams::new("rust")).unwrap();
+        assert_eq!(request.url, "https://mastodon.social/api/v2/search");
+        assert!(!request.params.contains_key("type"));
+        assert!(!request.headers.contains_key("Authorization"));
+        assert!(!engine.supports_paging());
+
+        let engine = mastodon(
+            "name: fosstodon\nengine: mastodon\nbase_url: https://fosstodon.org/\n\
+             search_type: statuses\napi_key: secret",
+        );
+        let mut params = RequestParams::new("rust");
+        params.pageno = 3;
+        let request = engine.request(&params).unwrap();
+        assert_eq!(request.url, "https://fosstodon.org/api/v2/search");
+        assert_eq!(request.params.get("type"), Some(&"statuses".to_string()));
+        assert_eq!(request.params.get("offset"), Some(&"20".to_string()));
+        assert_eq!(
+            request.headers.get("Authorization"),
+            Some(&"Bearer secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_mastodon_response() {
+        let engine = mastodon("name: mastodon\nengine: mastodon");
+        let response = EngineResponse::new(200, include_str!("fixtures/mastodon_search.json"));
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.results.len(), 4);
+
+        let status = &results.results[0];
+        assert_eq!(status.url, "https://fosstodon.org/@alice/1121");
+        assert_eq!(status.title, "Rust 1.76 is out! Better #rust ABI docs.");
+        assert_eq!(
+            status.metadata.author.as_deref(),
+            Some("@alice@fosstodon.org")
+        );
+        assert_eq!(status.metadata.instance.as_deref(), Some("fosstodon.org"));
+        assert_eq!(status.metadata.boosts, Some(12));
+        assert_eq!(status.metadata.comment_count, Some(3));
+        assert_eq!(
+            status.metadata.published_date,
+            "2024-02-08T16:20:00Z".parse().ok()
+        );
+        assert_eq!(
+            status.metadata.thumbnail.as_deref(),
+            Some("https://files.example.org/media/small.png")
+        );
+
+        // Content warnings are used as the title
+        let warned = &results.results[1];
+        assert_eq!(warned.title, "Rust rant");
+        assert_eq!(
+            warned.url,
+            "https://mastodon.social/users/bob/statuses/1122"
+        );
+
+        let account = &results.results[2];
+        assert_eq!(account.title, "Rust Language (@rust@hachyderm.io)");
+        assert_eq!(
+            account.content.as_deref(),
+            Some("48211 followers · Official account of the Rust project.")
+        );
+        assert_eq!(account.metadata.instance.as_deref(), Some("hachyderm.io"));
+
+        let hashtag = &results.results[3];
+        assert_eq!(hashtag.title, "#rust");
+        assert_eq!(
+            hashtag.content.as_deref(),
+            Some("150 posts by 105 people in the past 2 days")
+        );
+    }
+}
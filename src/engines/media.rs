@@ -0,0 +1,91 @@
+//! Helpers shared by the image, video and music engines
+
+/// Duration as `M:SS`, or `H:MM:SS` from an hour on
+pub fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// File size in bytes as e.g. `1.4 MB`
+pub fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1000 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1000.0;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Image dimensions as shown with image results
+pub fn format_resolution(width: u64, height: u64) -> String {
+    format!("{} x {}", width, height)
+}
+
+/// Absolute URL of a path returned by an instance
+pub fn absolute_url(base_url: &str, url: &str) -> String {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        url.to_string()
+    } else if let Some(rest) = url.strip_prefix("//") {
+        format!("https://{}", rest)
+    } else {
+        format!("{}/{}", base_url, url.trim_start_matches('/'))
+    }
+}
+
+/// Whether a URL taken from a third party may be embedded in a player
+pub fn is_embeddable(url: &str) -> bool {
+    url::Url::parse(url).is_ok_and(|u| u.scheme() == "https" && u.host().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0:00");
+        assert_eq!(format_duration(75), "1:15");
+        assert_eq!(format_duration(3600 + 62), "1:01:02");
+    }
+
+    #[test]
+    fn test_format_file_size() {
+        assert_eq!(format_file_size(512), "512 B");
+        assert_eq!(format_file_size(1_430_000), "1.4 MB");
+        assert_eq!(format_file_size(2_000_000_000), "2.0 GB");
+    }
+
+    #[test]
+    fn test_absolute_url() {
+        let base = "https://yewtu.be";
+        assert_eq!(
+            absolute_url(base, "/vi/a/mq.jpg"),
+            "https://yewtu.be/vi/a/mq.jpg"
+        );
+        assert_eq!(
+            absolute_url(base, "//i.ytimg.com/a.jpg"),
+            "https://i.ytimg.com/a.jpg"
+        );
+        assert_eq!(
+            absolute_url(base, "https://x.org/a.jpg"),
+            "https://x.org/a.jpg"
+        );
+    }
+
+    #[test]
+    fn test_is_embeddable() {
+        assert!(is_embeddable("https://tube.example.org/videos/embed/abc"));
+        assert!(!is_embeddable("http://tube.example.org/videos/embed/abc"));
+        assert!(!is_embeddable("javascript:alert(1)"));
+        assert!(!is_embeddable("data:text/html,<script>alert(1)</script>"));
+    }
+}
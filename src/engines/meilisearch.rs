@@ -0,0 +1,205 @@
+//! Meilisearch engine implementation
+//!
+//! Searches an index of a Meilisearch server through its search API. The
+//! `query_template` is the JSON search body; documents are mapped to results
+//! through `fields`.
+
+use super::search_server::{self, FieldMapping, QUERY_PLACEHOLDER};
+use super::traits::*;
+use crate::config::EngineConfig;
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// `POST /indexes/{index}/search` response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResponse {
+    #[serde(default)]
+    hits: Vec<Value>,
+    estimated_total_hits: Option<u64>,
+    total_hits: Option<u64>,
+}
+
+/// Meilisearch index search engine
+pub struct Meilisearch {
+    name: String,
+    base_url: String,
+    index: String,
+    fields: FieldMapping,
+    template: Value,
+    auth: Option<(String, String)>,
+}
+
+impl Meilisearch {
+    pub fn new() -> Self {
+        Self {
+            name: "meilisearch".to_string(),
+            base_url: "http://localhost:7700".to_string(),
+            index: String::new(),
+            fields: FieldMapping::default(),
+            template: json!({ "q": QUERY_PLACEHOLDER }),
+            auth: None,
+        }
+    }
+}
+
+impl Default for Meilisearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Meilisearch {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.index = config.extra_str("index").ok_or_else(|| {
+            anyhow::anyhow!("Meilisearch engine '{}' needs an `index`", config.name)
+        })?;
+        self.fields = FieldMapping::from_config(config);
+        self.template = search_server::json_template(config, self.template.clone())?;
+        self.auth = search_server::auth_header(config, "Bearer")?;
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut body = search_server::fill_template(&self.template, &params.query);
+        body["offset"] = json!((params.pageno.max(1) - 1) * self.results_per_page());
+        body["limit"] = json!(self.results_per_page());
+
+        let url = format!("{}/indexes/{}/search", self.base_url, self.index);
+        let mut request = EngineRequest::post(url).json(body);
+        if let Some((name, value)) = &self.auth {
+            request = request.header(name, value);
+        }
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            // Errors come as {"message": ..., "code": ...}
+            let message = response
+                .json::<Value>()
+                .ok()
+                .and_then(|e| e["message"].as_str().map(str::to_string));
+            return Err(match message {
+                Some(message) => anyhow::anyhow!("Meilisearch error: {}", message),
+                None => anyhow::anyhow!("HTTP error: {}", response.status),
+            });
+        }
+
+        let data: SearchResponse = response.json()?;
+        let mut results = Vec::new();
+        for hit in &data.hits {
+            if let Some(result) = self
+                .fields
+                .result(hit, &self.name, results.len() as u32 + 1)
+            {
+                results.push(result);
+            }
+        }
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = data.estimated_total_hits.or(data.total_hits);
+        Ok(engine_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::HttpClient;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn meilisearch(yaml: &str) -> Meilisearch {
+        let mut engine = Meilisearch::new();
+        let config: EngineConfig = serde_yaml::from_str(yaml).unwrap();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_meilisearch_request() {
+        let engine = meilisearch(
+            "name: docs\nengine: meilisearch\nbase_url: http://meili:7700/\nindex: docs\n\
+             query_template:\n  q: '{{query}}'\n  filter: 'public = true'",
+        );
+        let mut params = RequestParams::new("deploy");
+        params.pageno = 3;
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "http://meili:7700/indexes/docs/search");
+        let Some(RequestBody::Json(body)) = request.data else {
+            panic!("expected a JSON body");
+        };
+        assert_eq!(
+            body,
+            json!({"q": "deploy", "filter": "public = true", "offset": 20, "limit": 10})
+        );
+
+        let config: EngineConfig = serde_yaml::from_str("name: docs\nengine: meilisearch").unwrap();
+        assert!(Meilisearch::new().init(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_meilisearch_mock_server() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/indexes/handbook/search"))
+            .and(header("Authorization", "Bearer search-key"))
+            .and(body_partial_json(json!({"q": "deploy", "offset": 0})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("fixtures/meilisearch_search.json")),
+            )
+            .mount(&server)
+            .await;
+
+        let engine = meilisearch(&format!(
+            "name: handbook\nengine: meilisearch\nbase_url: {}\nindex: handbook\n\
+             api_key: search-key\nfields:\n  url: link\n  content: body\n  date: updated",
+            server.uri()
+        ));
+        let request = engine.request(&RequestParams::new("deploy")).unwrap();
+        let response = HttpClient::new().unwrap().execute(request).await.unwrap();
+        let results = engine.response(response).unwrap();
+
+        assert_eq!(results.number_of_results, Some(2));
+        assert_eq!(results.results.len(), 2);
+        let first = &results.results[0];
+        assert_eq!(first.url, "https://handbook.example.org/deploy");
+        assert_eq!(first.title, "Deploying services");
+        assert_eq!(first.engine, "handbook");
+        assert_eq!(first.content.as_deref(), Some("Use the deploy pipeline."));
+        assert_eq!(
+            first.metadata.published_date,
+            "2024-04-02T10:00:00Z".parse().ok()
+        );
+    }
+
+    #[test]
+    fn test_meilisearch_error() {
+        let engine = meilisearch("name: docs\nengine: meilisearch\nindex: docs");
+        let response = EngineResponse::new(
+            404,
+            r#"{"message": "Index `docs` not found.", "code": "index_not_found"}"#,
+        );
+        let error = engine.response(response).unwrap_err();
+        assert!(error.to_string().contains("Index `docs` not found."));
+    }
+}
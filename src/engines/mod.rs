@@ -2,21 +2,67 @@
 //!
 //! Defines the Engine trait and provides a registry for all search engines.
 
+mod dates;
 mod loader;
+mod media;
 mod registry;
+mod search_server;
+mod syndication;
 mod traits;
 
 // Engine implementations
 pub mod arxiv;
+pub mod bandcamp;
 pub mod bing;
+pub mod bookmarks;
 pub mod brave;
+pub mod crates;
+pub mod crossref;
 pub mod duckduckgo;
+pub mod elasticsearch;
+pub mod feed;
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
 pub mod google;
-pub mod stackoverflow;
+pub mod hackernews;
+pub mod invidious;
+pub mod lemmy;
+pub mod libretranslate;
+pub mod lobsters;
+pub mod local_index;
+pub mod mastodon;
+pub mod meilisearch;
+pub mod nominatim;
+pub mod npm;
+pub mod peertube;
+pub mod piped;
+pub mod pubmed;
+pub mod pypi;
+pub mod searx;
+pub mod semantic_scholar;
+pub mod solr;
+pub mod soundcloud;
+pub mod sqlite;
+pub mod stackexchange;
+pub mod wikicommons;
+pub mod wikidata;
 pub mod wikipedia;
+pub mod wiktionary;
 pub mod youtube;
 
 pub use loader::EngineLoader;
 pub use registry::EngineRegistry;
 pub use traits::*;
+
+use once_cell::sync::Lazy;
+
+/// Registry with the engines of the default settings
+static DEFAULT_REGISTRY: Lazy<EngineRegistry> = Lazy::new(|| {
+    EngineLoader::load(&crate::config::Settings::default()).expect("Default engines are invalid")
+});
+
+/// Get the registry of the engines enabled by default
+pub fn default_registry() -> &'static EngineRegistry {
+    &DEFAULT_REGISTRY
+}
@@ -0,0 +1,218 @@
+//! Nominatim (OpenStreetMap) maps engine implementation
+//!
+//! Works with the public OpenStreetMap instance or any self-hosted
+//! Nominatim server configured through `base_url`.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Address, Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Place returned by the Nominatim `jsonv2` format
+#[derive(Debug, Deserialize)]
+struct Place {
+    osm_type: Option<String>,
+    osm_id: Option<u64>,
+    lat: String,
+    lon: String,
+    #[serde(default)]
+    name: String,
+    display_name: String,
+    #[serde(default)]
+    address: HashMap<String, String>,
+    #[serde(default)]
+    boundingbox: Vec<String>,
+}
+
+/// Nominatim maps engine
+pub struct Nominatim {
+    name: String,
+    base_url: String,
+}
+
+impl Nominatim {
+    pub fn new() -> Self {
+        Self {
+            name: "nominatim".to_string(),
+            base_url: "https://nominatim.openstreetmap.org".to_string(),
+        }
+    }
+
+    /// Map Nominatim address details onto an [`Address`]
+    fn address(place: &Place) -> Address {
+        let first = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|k| place.address.get(*k))
+                .map(|v| v.to_string())
+        };
+
+        Address {
+            name: Some(place.name.clone()).filter(|n| !n.is_empty()),
+            road: first(&["road", "pedestrian", "footway", "path"]),
+            house_number: first(&["house_number"]),
+            postcode: first(&["postcode"]),
+            locality: first(&["city", "town", "village", "hamlet", "municipality"]),
+            country: first(&["country"]),
+            country_code: first(&["country_code"]),
+        }
+    }
+
+    /// Parse a bounding box given as [south, north, west, east] strings
+    fn boundingbox(values: &[String]) -> Option<[f64; 4]> {
+        let parsed: Vec<f64> = values.iter().filter_map(|v| v.parse().ok()).collect();
+        parsed.try_into().ok()
+    }
+}
+
+impl Default for Nominatim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Nominatim {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn languages(&self) -> Vec<&str> {
+        crate::locales::language_codes()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["maps"]
+    }
+
+    fn supports_paging(&self) -> bool {
+        false
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut request = EngineRequest::get(format!("{}/search", self.base_url))
+            .param("q", &params.query)
+            .param("format", "jsonv2")
+            .param("addressdetails", "1")
+            .param("limit", "10");
+
+        if params.lang != "all" && !params.lang.is_empty() {
+            request = request.param("accept-language", &params.lang);
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let places: Vec<Place> = response.json()?;
+        let mut results = Vec::new();
+
+        for (i, place) in places.into_iter().enumerate() {
+            let url = match (&place.osm_type, place.osm_id) {
+                (Some(osm_type), Some(osm_id)) => {
+                    format!("https://www.openstreetmap.org/{}/{}", osm_type, osm_id)
+                }
+                _ => format!(
+                    "https://www.openstreetmap.org/?mlat={}&mlon={}",
+                    place.lat, place.lon
+                ),
+            };
+
+            let title = if place.name.is_empty() {
+                place
+                    .display_name
+                    .split(',')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            } else {
+                place.name.clone()
+            };
+
+            let mut result = Result::new(url, title, self.name.clone())
+                .with_content(place.display_name.clone())
+                .with_position(i as u32 + 1);
+
+            result.result_type = ResultType::Map;
+            result.metadata.template = Some("map.html".to_string());
+            result.metadata.latitude = place.lat.parse().ok();
+            result.metadata.longitude = place.lon.parse().ok();
+            result.metadata.boundingbox = Self::boundingbox(&place.boundingbox);
+            result.metadata.address = Some(Self::address(&place));
+            result.metadata.osm_type = place.osm_type.clone();
+            result.metadata.osm_id = place.osm_id;
+
+            results.push(result);
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nominatim_request() {
+        let mut nominatim = Nominatim::new();
+        let config: EngineConfig = serde_yaml::from_str(
+            "name: maps\nengine: nominatim\nbase_url: https://osm.example.org/",
+        )
+        .unwrap();
+        nominatim.init(&config).unwrap();
+        assert_eq!(nominatim.name(), "maps");
+
+        let request = nominatim.request(&RequestParams::new("berlin")).unwrap();
+        assert_eq!(request.url, "https://osm.example.org/search");
+        assert_eq!(request.params.get("format"), Some(&"jsonv2".to_string()));
+        assert_eq!(
+            request.params.get("accept-language"),
+            Some(&"en".to_string())
+        );
+    }
+
+    #[test]
+    fn test_nominatim_response() {
+        let nominatim = Nominatim::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/nominatim_search.json"));
+        let results = nominatim.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        let berlin = &results[0];
+        assert_eq!(berlin.url, "https://www.openstreetmap.org/relation/62422");
+        assert_eq!(berlin.title, "Berlin");
+        assert_eq!(berlin.result_type, ResultType::Map);
+        assert_eq!(berlin.metadata.latitude, Some(52.5173885));
+        assert_eq!(berlin.metadata.osm_type.as_deref(), Some("relation"));
+        assert_eq!(
+            berlin.metadata.boundingbox,
+            Some([52.3382448, 52.6755087, 13.088345, 13.7611609])
+        );
+
+        let address = results[1].metadata.address.as_ref().unwrap();
+        assert_eq!(address.road.as_deref(), Some("Pariser Platz"));
+        assert_eq!(address.house_number.as_deref(), Some("1"));
+        assert_eq!(address.locality.as_deref(), Some("Berlin"));
+        assert_eq!(address.country_code.as_deref(), Some("de"));
+    }
+}
@@ -0,0 +1,160 @@
+//! npm registry search engine implementation
+
+use super::traits::*;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Package entry in the npm registry search response
+#[derive(Debug, Deserialize)]
+struct SearchObject {
+    package: Package,
+    #[serde(default)]
+    downloads: HashMap<String, u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Package {
+    name: String,
+    version: Option<String>,
+    description: Option<String>,
+    date: Option<String>,
+    license: Option<String>,
+    #[serde(default)]
+    links: HashMap<String, String>,
+    publisher: Option<Publisher>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Publisher {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    objects: Vec<SearchObject>,
+}
+
+/// npm registry search engine
+pub struct Npm {
+    api_url: String,
+}
+
+impl Npm {
+    pub fn new() -> Self {
+        Self {
+            api_url: "https://registry.npmjs.org/-/v1/search".to_string(),
+        }
+    }
+}
+
+impl Default for Npm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Npm {
+    fn name(&self) -> &str {
+        "npm"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://www.npmjs.com")
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["it", "packages"]
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let from = (params.pageno.saturating_sub(1)) * 10;
+
+        Ok(EngineRequest::get(&self.api_url)
+            .param("text", &params.query)
+            .param("size", "10")
+            .param("from", from.to_string()))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: SearchResponse = response.json()?;
+        let mut results = Vec::new();
+
+        for (i, object) in data.objects.into_iter().enumerate() {
+            let package = object.package;
+            let url = package
+                .links
+                .get("npm")
+                .cloned()
+                .unwrap_or_else(|| format!("https://www.npmjs.com/package/{}", package.name));
+
+            let mut result =
+                Result::new(url, package.name, self.name().to_string()).with_position(i as u32 + 1);
+
+            if let Some(description) = package.description {
+                result = result.with_content(description);
+            }
+
+            result.result_type = ResultType::Package;
+            result.metadata.template = Some("package.html".to_string());
+            result.metadata.version = package.version;
+            result.metadata.license = package.license;
+            result.metadata.repository = package.links.get("repository").cloned();
+            result.metadata.updated_date = package.date;
+            result.metadata.author = package.publisher.map(|p| p.username);
+            result.metadata.downloads = object
+                .downloads
+                .get("monthly")
+                .or_else(|| object.downloads.get("weekly"))
+                .copied();
+
+            results.push(result);
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npm_request() {
+        let npm = Npm::new();
+        let mut params = RequestParams::new("react");
+        params.pageno = 3;
+        let request = npm.request(&params).unwrap();
+
+        assert_eq!(request.params.get("text"), Some(&"react".to_string()));
+        assert_eq!(request.params.get("from"), Some(&"20".to_string()));
+    }
+
+    #[test]
+    fn test_npm_response() {
+        let npm = Npm::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/npm_search.json"));
+        let results = npm.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        let react = &results[0];
+        assert_eq!(react.url, "https://www.npmjs.com/package/react");
+        assert_eq!(react.metadata.version.as_deref(), Some("19.1.0"));
+        assert_eq!(react.metadata.license.as_deref(), Some("MIT"));
+        assert_eq!(react.metadata.downloads, Some(163_000_000));
+        assert_eq!(
+            react.metadata.repository.as_deref(),
+            Some("https://github.com/facebook/react")
+        );
+        assert_eq!(results[1].metadata.downloads, None);
+    }
+}
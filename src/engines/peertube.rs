@@ -0,0 +1,304 @@
+//! PeerTube search engine implementation
+//!
+//! Searches the videos of the PeerTube instance at `base_url`, or the
+//! Sepia Search index of the whole federation by default. Both answer the
+//! same `/api/v1/search/videos` API.
+
+use super::dates;
+use super::media;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use chrono::{SecondsFormat, Utc};
+use serde::Deserialize;
+
+/// `GET /api/v1/search/videos` response
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    total: Option<u64>,
+    #[serde(default)]
+    data: Vec<Video>,
+}
+
+/// Video; the search index adds absolute URLs to the instance fields
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Video {
+    uuid: String,
+    #[serde(rename = "shortUUID")]
+    short_uuid: Option<String>,
+    name: String,
+    description: Option<String>,
+    duration: Option<u64>,
+    views: Option<u64>,
+    published_at: Option<String>,
+    url: Option<String>,
+    embed_path: Option<String>,
+    embed_url: Option<String>,
+    thumbnail_path: Option<String>,
+    thumbnail_url: Option<String>,
+    account: Option<Actor>,
+    channel: Option<Actor>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Actor {
+    display_name: Option<String>,
+    name: String,
+    host: Option<String>,
+}
+
+/// PeerTube search engine
+pub struct PeerTube {
+    name: String,
+    base_url: String,
+    /// Sort by date instead of relevance
+    by_date: bool,
+}
+
+impl PeerTube {
+    pub fn new() -> Self {
+        Self {
+            name: "peertube".to_string(),
+            base_url: "https://sepiasearch.org".to_string(),
+            by_date: false,
+        }
+    }
+
+    fn video_result(&self, video: Video) -> Result {
+        let id = video.short_uuid.as_deref().unwrap_or(&video.uuid);
+        let url = video
+            .url
+            .clone()
+            .unwrap_or_else(|| format!("{}/w/{}", self.base_url, id));
+
+        let mut result = Result::new(url, video.name, self.name.clone());
+        result.result_type = ResultType::Video;
+        if let Some(description) = video.description.filter(|d| !d.trim().is_empty()) {
+            result = result.with_content(description.trim().to_string());
+        }
+
+        let instance_url =
+            |path: Option<String>| path.map(|p| media::absolute_url(&self.base_url, &p));
+        let author = video.channel.as_ref().or(video.account.as_ref()).map(|a| {
+            match a.display_name.as_deref() {
+                Some(name) if !name.is_empty() => name.to_string(),
+                _ => a.name.clone(),
+            }
+        });
+
+        let meta = &mut result.metadata;
+        meta.template = Some("videos.html".to_string());
+        meta.author = author;
+        meta.instance = video.account.and_then(|a| a.host);
+        meta.views = video.views;
+        // Live streams have a duration of 0
+        meta.duration = video
+            .duration
+            .filter(|d| *d > 0)
+            .map(media::format_duration);
+        meta.published_date = video.published_at.as_deref().and_then(dates::parse);
+        meta.thumbnail = video.thumbnail_url.or(instance_url(video.thumbnail_path));
+        // Index results point anywhere; only embed their players over
+        // HTTPS, built from the video's instance if the URL isn't usable
+        meta.iframe_src = match video.embed_url {
+            Some(url) if media::is_embeddable(&url) => Some(url),
+            Some(_) => meta
+                .instance
+                .as_ref()
+                .map(|host| format!("https://{}/videos/embed/{}", host, video.uuid))
+                .filter(|url| media::is_embeddable(url)),
+            None => instance_url(video.embed_path),
+        };
+        result
+    }
+}
+
+impl Default for PeerTube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for PeerTube {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["videos"]
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.by_date = config.extra_str("sort").as_deref() == Some("date");
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let per_page = self.results_per_page();
+        let mut request = EngineRequest::get(format!("{}/api/v1/search/videos", self.base_url))
+            .param("search", &params.query)
+            .param("start", ((params.pageno.max(1) - 1) * per_page).to_string())
+            .param("count", per_page.to_string())
+            .param(
+                "sort",
+                if self.by_date {
+                    "-publishedAt"
+                } else {
+                    "-match"
+                },
+            )
+            .param(
+                "nsfw",
+                if params.safesearch > 0 {
+                    "false"
+                } else {
+                    "both"
+                },
+            );
+
+        if let Some(time_range) = params.time_range {
+            let since = Utc::now() - time_range.duration();
+            request = request.param(
+                "startDate",
+                since.to_rfc3339_opts(SecondsFormat::Secs, true),
+            );
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: SearchResponse = response.json()?;
+        let results = data
+            .data
+            .into_iter()
+            .map(|video| self.video_result(video))
+            .enumerate()
+            .map(|(i, r)| r.with_position(i as u32 + 1))
+            .collect();
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = data.total;
+        Ok(engine_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::TimeRange;
+
+    #[test]
+    fn test_peertube_request() {
+        let engine = PeerTube::new();
+        let mut params = RequestParams::new("rust");
+        params.pageno = 2;
+        params.safesearch = 1;
+        params.time_range = Some(TimeRange::Month);
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://sepiasearch.org/api/v1/search/videos");
+        assert_eq!(request.params.get("start"), Some(&"10".to_string()));
+        assert_eq!(request.params.get("nsfw"), Some(&"false".to_string()));
+        assert_eq!(request.params.get("sort"), Some(&"-match".to_string()));
+        assert!(request.params["startDate"].ends_with('Z'));
+    }
+
+    #[test]
+    fn test_peertube_response() {
+        let mut engine = PeerTube::new();
+        let config: EngineConfig = serde_yaml::from_str(
+            "name: framatube\nengine: peertube\nbase_url: https://framatube.org",
+        )
+        .unwrap();
+        engine.init(&config).unwrap();
+
+        let response = EngineResponse::new(200, include_str!("fixtures/peertube_search.json"));
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.number_of_results, Some(42));
+        assert_eq!(results.results.len(), 2);
+
+        // Instance results only have paths
+        let local = &results.results[0];
+        assert_eq!(local.url, "https://framatube.org/w/kkGMgK9ZtnKfYAgnEtQxbv");
+        assert_eq!(local.result_type, ResultType::Video);
+        assert_eq!(local.metadata.author.as_deref(), Some("Framasoft"));
+        assert_eq!(local.metadata.instance.as_deref(), Some("framatube.org"));
+        assert_eq!(local.metadata.duration.as_deref(), Some("4:10"));
+        assert_eq!(local.metadata.views, Some(1830));
+        assert_eq!(
+            local.metadata.iframe_src.as_deref(),
+            Some("https://framatube.org/videos/embed/9c9de5e8-0a1e-484a-b099-e80766180a6d")
+        );
+        assert_eq!(
+            local.metadata.thumbnail.as_deref(),
+            Some("https://framatube.org/lazy-static/thumbnails/9c9de5e8.jpg")
+        );
+        assert_eq!(
+            local.metadata.published_date,
+            "2024-02-08T15:04:05Z".parse().ok()
+        );
+
+        // Federated results carry absolute URLs
+        let remote = &results.results[1];
+        assert_eq!(remote.url, "https://tube.example.org/w/abc123");
+        assert_eq!(remote.metadata.author.as_deref(), Some("alice"));
+        assert_eq!(
+            remote.metadata.instance.as_deref(),
+            Some("tube.example.org")
+        );
+        assert_eq!(
+            remote.metadata.iframe_src.as_deref(),
+            Some("https://tube.example.org/videos/embed/abc123")
+        );
+    }
+
+    #[test]
+    fn test_peertube_unsafe_embed_url() {
+        let engine = PeerTube::new();
+        let response = EngineResponse::new(
+            200,
+            r#"{"total": 2, "data": [
+                {"uuid": "abc123", "name": "Pwned", "url": "https://tube.example.org/w/abc123",
+                 "embedUrl": "javascript:alert(document.cookie)",
+                 "account": {"name": "mallory", "host": "tube.example.org"}},
+                {"uuid": "def456", "name": "Pwned again", "url": "https://tube.example.org/w/def456",
+                 "embedUrl": "http://tube.example.org/videos/embed/def456"}
+            ]}"#,
+        );
+        let results = engine.response(response).unwrap();
+
+        assert_eq!(
+            results.results[0].metadata.iframe_src.as_deref(),
+            Some("https://tube.example.org/videos/embed/abc123")
+        );
+        // Without an instance there is nothing to build a player from
+        assert_eq!(results.results[1].metadata.iframe_src, None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Piped search engine implementation
+//!
+//! Searches YouTube through a Piped API instance at `api_url`. Results link
+//! to and embed from the Piped frontend at `base_url`. Piped pages with
+//! opaque tokens, so only the first page is searched.
+
+use super::media;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use chrono::DateTime;
+use serde::Deserialize;
+
+/// `GET /search` response
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    /// `stream` for videos
+    #[serde(rename = "type")]
+    kind: String,
+    /// Path of the watch page, e.g. `/watch?v=ID`
+    url: String,
+    title: Option<String>,
+    thumbnail: Option<String>,
+    uploader_name: Option<String>,
+    short_description: Option<String>,
+    /// Seconds, -1 for live streams
+    duration: Option<i64>,
+    views: Option<i64>,
+    /// Milliseconds since the epoch, -1 if unknown
+    uploaded: Option<i64>,
+}
+
+/// Piped search engine
+pub struct Piped {
+    name: String,
+    api_url: String,
+    base_url: String,
+}
+
+impl Piped {
+    pub fn new() -> Self {
+        Self {
+            name: "piped".to_string(),
+            api_url: "https://pipedapi.kavin.rocks".to_string(),
+            base_url: "https://piped.video".to_string(),
+        }
+    }
+
+    fn video_result(&self, item: Item) -> Option<Result> {
+        let title = item.title.filter(|t| !t.is_empty())?;
+        let video_id = item.url.split_once("v=")?.1.split('&').next()?.to_string();
+        let url = media::absolute_url(&self.base_url, &item.url);
+
+        let mut result = Result::new(url, title, self.name.clone());
+        result.result_type = ResultType::Video;
+        if let Some(description) = item.short_description.filter(|d| !d.trim().is_empty()) {
+            result = result.with_content(description.trim().to_string());
+        }
+
+        let meta = &mut result.metadata;
+        meta.template = Some("videos.html".to_string());
+        meta.thumbnail = item.thumbnail.filter(|t| !t.is_empty());
+        meta.author = item.uploader_name.filter(|a| !a.is_empty());
+        meta.views = item.views.and_then(|v| u64::try_from(v).ok());
+        meta.duration = item
+            .duration
+            .filter(|d| *d > 0)
+            .map(|d| media::format_duration(d as u64));
+        meta.published_date = item
+            .uploaded
+            .filter(|ms| *ms > 0)
+            .and_then(DateTime::from_timestamp_millis);
+        meta.iframe_src = Some(format!("{}/embed/{}", self.base_url, video_id));
+        Some(result)
+    }
+}
+
+impl Default for Piped {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Piped {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(false)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["videos", "music"]
+    }
+
+    fn supports_paging(&self) -> bool {
+        false
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(api_url) = config.extra_str("api_url") {
+            self.api_url = api_url.trim_end_matches('/').to_string();
+        }
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Ok(EngineRequest::get(format!("{}/search", self.api_url))
+            .param("q", &params.query)
+            .param("filter", "videos"))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: SearchResponse = response.json()?;
+        let results = data
+            .items
+            .into_iter()
+            .filter(|item| item.kind == "stream")
+            .filter_map(|item| self.video_result(item))
+            .enumerate()
+            .map(|(i, r)| r.with_position(i as u32 + 1))
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_piped_request() {
+        let mut engine = Piped::new();
+        let config: EngineConfig = serde_yaml::from_str(
+            "name: piped\nengine: piped\napi_url: https://api.piped.example.org/",
+        )
+        .unwrap();
+        engine.init(&config).unwrap();
+        let request = engine.request(&RequestParams::new("rust")).unwrap();
+
+        assert_eq!(request.url, "https://api.piped.example.org/search");
+        assert_eq!(request.params.get("filter"), Some(&"videos".to_string()));
+        assert!(!engine.supports_paging());
+    }
+
+    #[test]
+    fn test_piped_response() {
+        let engine = Piped::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/piped_search.json"));
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.results.len(), 2);
+
+        let video = &results.results[0];
+        assert_eq!(video.url, "https://piped.video/watch?v=dQw4w9WgXcQ");
+        assert_eq!(video.result_type, ResultType::Video);
+        assert_eq!(video.metadata.author.as_deref(), Some("Rust Videos"));
+        assert_eq!(video.metadata.duration.as_deref(), Some("12:05"));
+        assert_eq!(video.metadata.views, Some(15321));
+        assert_eq!(
+            video.metadata.iframe_src.as_deref(),
+            Some("https://piped.video/embed/dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            video.metadata.published_date,
+            "2024-02-08T15:04:05Z".parse().ok()
+        );
+
+        // Live streams have neither duration nor upload date
+        let live = &results.results[1];
+        assert_eq!(live.metadata.duration, None);
+        assert_eq!(live.metadata.published_date, None);
+    }
+}
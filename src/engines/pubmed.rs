@@ -0,0 +1,262 @@
+//! PubMed search engine implementation
+//!
+//! Uses the NCBI E-utilities in two steps: `esearch` returns the PubMed ids
+//! matching the query and `esummary` returns their records. An `api_key`
+//! raises NCBI's request rate limit.
+
+use super::dates;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// `esearch` response
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    esearchresult: SearchResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    #[serde(default)]
+    idlist: Vec<String>,
+}
+
+/// `esummary` response: record ids in order plus one record per id
+#[derive(Debug, Deserialize)]
+struct SummaryResponse {
+    result: SummaryResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct SummaryResult {
+    #[serde(default)]
+    uids: Vec<String>,
+    #[serde(flatten)]
+    records: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Summary {
+    uid: String,
+    title: String,
+    #[serde(default)]
+    authors: Vec<Author>,
+    fulljournalname: Option<String>,
+    source: Option<String>,
+    sortpubdate: Option<String>,
+    #[serde(default)]
+    articleids: Vec<ArticleId>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArticleId {
+    idtype: String,
+    value: String,
+}
+
+/// PubMed biomedical literature engine
+pub struct PubMed {
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl PubMed {
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://eutils.ncbi.nlm.nih.gov/entrez/eutils".to_string(),
+            api_key: None,
+        }
+    }
+
+    /// Build an E-utilities request with the common parameters
+    fn eutils_request(&self, utility: &str) -> EngineRequest {
+        let mut request = EngineRequest::get(format!("{}/{}.fcgi", self.base_url, utility))
+            .param("db", "pubmed")
+            .param("retmode", "json");
+
+        if let Some(api_key) = &self.api_key {
+            request = request.param("api_key", api_key);
+        }
+
+        request
+    }
+
+    /// Build a paper result from an esummary record
+    fn summary_result(&self, summary: Summary, position: u32) -> Result {
+        let url = format!("https://pubmed.ncbi.nlm.nih.gov/{}/", summary.uid);
+        let title = summary.title.trim_end_matches('.').to_string();
+        let mut result = Result::new(url, title, self.name().to_string()).with_position(position);
+        result.result_type = ResultType::Paper;
+
+        let article_id = |idtype: &str| {
+            summary
+                .articleids
+                .iter()
+                .find(|id| id.idtype == idtype)
+                .map(|id| id.value.clone())
+        };
+        let pmc = article_id("pmc");
+
+        let authors: Vec<String> = summary.authors.iter().map(|a| a.name.clone()).collect();
+
+        result.metadata.template = Some("paper.html".to_string());
+        result.metadata.author = Some(authors.join(", ")).filter(|a| !a.is_empty());
+        result.metadata.authors = authors;
+        result.metadata.journal = summary.fulljournalname.or(summary.source);
+        result.metadata.doi = article_id("doi");
+        result.metadata.published_date = summary.sortpubdate.as_deref().and_then(dates::parse);
+        // Articles in PubMed Central are freely available
+        result.metadata.open_access = pmc.is_some();
+        result.metadata.pdf_url =
+            pmc.map(|pmc| format!("https://www.ncbi.nlm.nih.gov/pmc/articles/{}/pdf/", pmc));
+
+        result
+    }
+}
+
+impl Default for PubMed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for PubMed {
+    fn name(&self) -> &str {
+        "pubmed"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://pubmed.ncbi.nlm.nih.gov")
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["science"]
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.api_key = config.api_key.clone().filter(|k| !k.is_empty());
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Ok(self
+            .eutils_request("esearch")
+            .param("term", &params.query)
+            .param("retmax", "10")
+            .param("retstart", ((params.pageno.max(1) - 1) * 10).to_string())
+            .param("sort", "relevance"))
+    }
+
+    fn next_request(&self, response: &EngineResponse) -> AnyhowResult<Option<EngineRequest>> {
+        // Only the esearch response leads to a follow-up
+        if !response.is_success() || !response.text.contains("\"esearchresult\"") {
+            return Ok(None);
+        }
+
+        let ids = response.json::<SearchResponse>()?.esearchresult.idlist;
+        if ids.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            self.eutils_request("esummary").param("id", ids.join(",")),
+        ))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        // An esearch response reaching here had no matches
+        if response.text.contains("\"esearchresult\"") {
+            return Ok(EngineResults::new());
+        }
+
+        let mut summary = response.json::<SummaryResponse>()?.result;
+        let mut results = Vec::new();
+
+        for uid in &summary.uids {
+            let Some(record) = summary.records.remove(uid) else {
+                continue;
+            };
+            // Records for unknown ids only carry an error message
+            let Ok(record) = serde_json::from_value::<Summary>(record) else {
+                continue;
+            };
+            results.push(self.summary_result(record, results.len() as u32 + 1));
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pubmed_search_step() {
+        let pubmed = PubMed::new();
+        let request = pubmed.request(&RequestParams::new("crispr")).unwrap();
+        assert!(request.url.ends_with("/esearch.fcgi"));
+        assert_eq!(request.params.get("term"), Some(&"crispr".to_string()));
+
+        let response = EngineResponse::new(200, include_str!("fixtures/pubmed_esearch.json"));
+        let next = pubmed.next_request(&response).unwrap().unwrap();
+        assert!(next.url.ends_with("/esummary.fcgi"));
+        assert_eq!(
+            next.params.get("id"),
+            Some(&"34265844,30951800".to_string())
+        );
+
+        let empty = EngineResponse::new(200, r#"{"esearchresult": {"count": "0", "idlist": []}}"#);
+        assert!(pubmed.next_request(&empty).unwrap().is_none());
+        assert!(pubmed.response(empty).unwrap().results.is_empty());
+    }
+
+    #[test]
+    fn test_pubmed_summary_step() {
+        let pubmed = PubMed::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/pubmed_esummary.json"));
+        assert!(pubmed.next_request(&response).unwrap().is_none());
+
+        let results = pubmed.response(response).unwrap().results;
+        assert_eq!(results.len(), 2);
+
+        let paper = &results[0];
+        assert_eq!(paper.url, "https://pubmed.ncbi.nlm.nih.gov/34265844/");
+        assert_eq!(
+            paper.title,
+            "Highly accurate protein structure prediction with AlphaFold"
+        );
+        assert_eq!(paper.metadata.authors, vec!["Jumper J", "Evans R"]);
+        assert_eq!(paper.metadata.journal.as_deref(), Some("Nature"));
+        assert_eq!(
+            paper.metadata.doi.as_deref(),
+            Some("10.1038/s41586-021-03819-2")
+        );
+        assert_eq!(
+            paper.metadata.published_date,
+            "2021-07-15T00:00:00Z".parse().ok()
+        );
+        assert!(paper.metadata.open_access);
+        assert_eq!(
+            paper.metadata.pdf_url.as_deref(),
+            Some("https://www.ncbi.nlm.nih.gov/pmc/articles/PMC8371605/pdf/")
+        );
+
+        assert!(!results[1].metadata.open_access);
+    }
+}
@@ -0,0 +1,138 @@
+//! PyPI package search engine implementation
+//!
+//! PyPI has no JSON search API, so the search results page is scraped. The
+//! page does not show a license, so unlike npm these results carry none.
+
+use super::traits::*;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use scraper::{Html, Selector};
+
+/// PyPI package search engine
+pub struct PyPI {
+    search_url: String,
+}
+
+impl PyPI {
+    pub fn new() -> Self {
+        Self {
+            search_url: "https://pypi.org/search/".to_string(),
+        }
+    }
+}
+
+impl Default for PyPI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for PyPI {
+    fn name(&self) -> &str {
+        "pypi"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://pypi.org")
+            .official_api(false)
+            .results_format("HTML")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["it", "packages"]
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Ok(EngineRequest::get(&self.search_url)
+            .param("q", &params.query)
+            .param("page", params.pageno.to_string()))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let document = Html::parse_document(&response.text);
+        let snippet_selector = Selector::parse("a.package-snippet").unwrap();
+        let name_selector = Selector::parse(".package-snippet__name").unwrap();
+        let version_selector = Selector::parse(".package-snippet__version").unwrap();
+        let description_selector = Selector::parse(".package-snippet__description").unwrap();
+        let time_selector = Selector::parse(".package-snippet__created time").unwrap();
+
+        let text = |element: scraper::ElementRef, selector: &Selector| {
+            element
+                .select(selector)
+                .next()
+                .map(|e| e.text().collect::<String>().trim().to_string())
+                .filter(|t| !t.is_empty())
+        };
+
+        let mut results = Vec::new();
+
+        for snippet in document.select(&snippet_selector) {
+            let Some(href) = snippet.value().attr("href") else {
+                continue;
+            };
+            let Some(name) = text(snippet, &name_selector) else {
+                continue;
+            };
+
+            let url = if href.starts_with('/') {
+                format!("https://pypi.org{}", href)
+            } else {
+                href.to_string()
+            };
+
+            let mut result = Result::new(url, name, self.name().to_string())
+                .with_position(results.len() as u32 + 1);
+
+            if let Some(description) = text(snippet, &description_selector) {
+                result = result.with_content(description);
+            }
+
+            result.result_type = ResultType::Package;
+            result.metadata.template = Some("package.html".to_string());
+            result.metadata.version = text(snippet, &version_selector);
+            result.metadata.updated_date = snippet
+                .select(&time_selector)
+                .next()
+                .and_then(|t| t.value().attr("datetime"))
+                .map(|d| d.to_string());
+
+            results.push(result);
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pypi_response() {
+        let pypi = PyPI::new();
+        let request = pypi.request(&RequestParams::new("requests")).unwrap();
+        assert_eq!(request.params.get("q"), Some(&"requests".to_string()));
+
+        let response = EngineResponse::new(200, include_str!("fixtures/pypi_search.html"));
+        let results = pypi.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "requests");
+        assert_eq!(results[0].url, "https://pypi.org/project/requests/");
+        assert_eq!(results[0].metadata.version.as_deref(), Some("2.32.3"));
+        assert_eq!(
+            results[0].content.as_deref(),
+            Some("Python HTTP for Humans.")
+        );
+        assert_eq!(
+            results[0].metadata.updated_date.as_deref(),
+            Some("2024-05-29T15:37:47+0000")
+        );
+        assert_eq!(results[0].metadata.license, None);
+    }
+}
@@ -1,16 +1,21 @@
 //! Engine registry for managing available search engines
 
 use super::traits::Engine;
+use crate::bangs::BangRegistry;
 use crate::config::EngineConfig;
 use std::collections::HashMap;
 use std::sync::Arc;
+use tracing::warn;
 
 /// Registry of all available search engines
+#[derive(Clone)]
 pub struct EngineRegistry {
     /// Engines by name
     engines: HashMap<String, Arc<dyn Engine>>,
     /// Engine shortcuts (e.g., "g" -> "google")
     shortcuts: HashMap<String, String>,
+    /// Alternative names usable as bangs (config name, display name)
+    aliases: HashMap<String, String>,
     /// Engines by category
     categories: HashMap<String, Vec<String>>,
     /// Engine configurations
@@ -23,26 +28,52 @@ impl EngineRegistry {
         Self {
             engines: HashMap::new(),
             shortcuts: HashMap::new(),
+            aliases: HashMap::new(),
             categories: HashMap::new(),
             configs: HashMap::new(),
         }
     }
 
     /// Register an engine
+    ///
+    /// Categories from the engine config take precedence over the engine's
+    /// built-in categories, so settings.yml can define new ones.
     pub fn register(&mut self, engine: Arc<dyn Engine>, config: EngineConfig) {
         let name = engine.name().to_string();
 
         // Register shortcut
         if !config.shortcut.is_empty() {
-            self.shortcuts.insert(config.shortcut.clone(), name.clone());
+            let shortcut = config.shortcut.to_lowercase();
+            if let Some(existing) = self.shortcuts.get(&shortcut).filter(|e| **e != name) {
+                warn!(
+                    "Shortcut !{} of engine {} is already used by {}",
+                    shortcut, name, existing
+                );
+            } else {
+                self.shortcuts.insert(shortcut, name.clone());
+            }
+        }
+
+        // Register config name and display name as aliases
+        let aliases = std::iter::once(config.name.as_str()).chain(config.display_name.as_deref());
+        for alias in aliases {
+            let alias = Self::normalize_bang(alias);
+            if !alias.is_empty() && alias != name {
+                self.aliases.entry(alias).or_insert_with(|| name.clone());
+            }
         }
 
         // Register in categories
-        for category in engine.categories() {
-            self.categories
-                .entry(category.to_string())
-                .or_default()
-                .push(name.clone());
+        let categories: Vec<String> = if config.categories.is_empty() {
+            engine.categories().iter().map(|c| c.to_string()).collect()
+        } else {
+            config.categories.clone()
+        };
+        for category in categories {
+            let names = self.categories.entry(category).or_default();
+            if !names.contains(&name) {
+                names.push(name.clone());
+            }
         }
 
         // Store engine and config
@@ -80,6 +111,18 @@ impl EngineRegistry {
             .unwrap_or_default()
     }
 
+    /// Get the categories an engine is registered in
+    pub fn categories_of(&self, name: &str) -> Vec<&str> {
+        let mut categories: Vec<&str> = self
+            .categories
+            .iter()
+            .filter(|(_, names)| names.iter().any(|n| n == name))
+            .map(|(category, _)| category.as_str())
+            .collect();
+        categories.sort_unstable();
+        categories
+    }
+
     /// Get all enabled engines
     pub fn enabled(&self) -> Vec<&Arc<dyn Engine>> {
         self.configs
@@ -114,13 +157,110 @@ impl EngineRegistry {
         self.engines.is_empty()
     }
 
-    /// Resolve a name or shortcut to an engine name
-    pub fn resolve_name<'a>(&'a self, name_or_shortcut: &'a str) -> Option<&'a str> {
-        if self.engines.contains_key(name_or_shortcut) {
-            Some(name_or_shortcut)
-        } else {
-            self.shortcuts.get(name_or_shortcut).map(|s| s.as_str())
+    /// Resolve a name, shortcut, config name or display name to an engine name
+    ///
+    /// Lookup is case-insensitive and accepts `_` in place of spaces, so
+    /// "google images" can be targeted as `!google_images`.
+    pub fn resolve_name(&self, name_or_shortcut: &str) -> Option<&str> {
+        if let Some((name, _)) = self.engines.get_key_value(name_or_shortcut) {
+            return Some(name.as_str());
+        }
+
+        let key = Self::normalize_bang(name_or_shortcut);
+        self.engines
+            .get_key_value(&key)
+            .map(|(name, _)| name)
+            .or_else(|| self.shortcuts.get(&key))
+            .or_else(|| self.aliases.get(&key))
+            .map(|s| s.as_str())
+    }
+
+    /// Resolve a bang to a registered category name
+    pub fn resolve_category(&self, bang: &str) -> Option<&str> {
+        let key = bang.trim_start_matches('!').to_lowercase();
+        let spaced = key.replace('_', " ");
+        self.categories
+            .keys()
+            .find(|c| c.to_lowercase() == key || c.to_lowercase() == spaced)
+            .map(|c| c.as_str())
+    }
+
+    /// Get all shortcuts with the engine they resolve to
+    pub fn shortcuts(&self) -> Vec<(&str, &str)> {
+        self.shortcuts
+            .iter()
+            .map(|(s, n)| (s.as_str(), n.as_str()))
+            .collect()
+    }
+
+    /// Find engine and category bangs that shadow external bangs
+    ///
+    /// Returns `(trigger, target)` pairs sorted by trigger. The engine or
+    /// category wins for `!trigger`; the external bang stays reachable
+    /// through `!!trigger`.
+    pub fn bang_collisions(&self, bangs: &BangRegistry) -> Vec<(String, String)> {
+        let mut collisions: Vec<(String, String)> = self
+            .engines
+            .keys()
+            .map(|name| (Self::normalize_bang(name), name.clone()))
+            .chain(self.shortcuts.iter().map(|(s, n)| (s.clone(), n.clone())))
+            .chain(self.aliases.iter().map(|(a, n)| (a.clone(), n.clone())))
+            .chain(
+                self.categories
+                    .keys()
+                    .map(|c| (Self::normalize_bang(c), format!("category {}", c))),
+            )
+            .filter(|(trigger, _)| bangs.contains(trigger))
+            .collect();
+
+        collisions.sort();
+        collisions.dedup();
+        collisions
+    }
+
+    /// Check if an engine is restricted to token holders
+    pub fn is_private(&self, name: &str) -> bool {
+        self.configs.get(name).is_some_and(|c| c.is_private())
+    }
+
+    /// Check if any registered engine is private
+    pub fn has_private(&self) -> bool {
+        self.configs.values().any(|c| c.is_private())
+    }
+
+    /// Get the registry as seen by a user presenting `tokens`
+    ///
+    /// Private engines the tokens do not unlock are removed entirely, so
+    /// they cannot be found by name, shortcut, bang or category.
+    pub fn visible_to(&self, tokens: &[String]) -> Self {
+        let mut registry = self.clone();
+        let hidden: Vec<String> = self
+            .configs
+            .iter()
+            .filter(|(_, config)| !config.is_accessible(tokens))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if hidden.is_empty() {
+            return registry;
+        }
+
+        registry.engines.retain(|name, _| !hidden.contains(name));
+        registry.configs.retain(|name, _| !hidden.contains(name));
+        registry.shortcuts.retain(|_, name| !hidden.contains(name));
+        registry.aliases.retain(|_, name| !hidden.contains(name));
+        for names in registry.categories.values_mut() {
+            names.retain(|name| !hidden.contains(name));
         }
+        registry.categories.retain(|_, names| !names.is_empty());
+        registry
+    }
+
+    /// Normalize a name for bang lookup ("Google Images" -> "google_images")
+    fn normalize_bang(name: &str) -> String {
+        name.trim_start_matches('!')
+            .trim()
+            .to_lowercase()
+            .replace(' ', "_")
     }
 
     /// Get effective timeout for an engine
@@ -136,8 +276,9 @@ impl EngineRegistry {
     pub fn get_weight(&self, name: &str) -> f64 {
         self.configs
             .get(name)
-            .map(|c| c.weight)
-            .unwrap_or_else(|| self.engines.get(name).map(|e| e.weight()).unwrap_or(1.0))
+            .and_then(|c| c.weight)
+            .or_else(|| self.engines.get(name).map(|e| e.weight()))
+            .unwrap_or(1.0)
     }
 }
 
@@ -150,7 +291,7 @@ impl Default for EngineRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::engines::google::Google;
+    use crate::engines::google::{Google, GoogleImages};
 
     #[test]
     fn test_registry() {
@@ -168,4 +309,95 @@ mod tests {
         assert!(registry.contains("google"));
         assert!(registry.get_by_shortcut("g").is_some());
     }
+
+    #[test]
+    fn test_resolve_names_and_categories() {
+        let mut registry = EngineRegistry::new();
+        let config = EngineConfig {
+            name: "google images".to_string(),
+            engine: "google_images".to_string(),
+            shortcut: "gi".to_string(),
+            display_name: Some("Image Search".to_string()),
+            categories: vec!["images".to_string(), "team pictures".to_string()],
+            ..Default::default()
+        };
+        registry.register(Arc::new(GoogleImages::new()), config);
+
+        for bang in ["google_images", "GI", "image_search", "!gi"] {
+            assert_eq!(
+                registry.resolve_name(bang),
+                Some("google_images"),
+                "{}",
+                bang
+            );
+        }
+        assert_eq!(registry.resolve_name("bing"), None);
+
+        // Config categories replace the engine's built-in ones
+        assert_eq!(
+            registry.resolve_category("team_pictures"),
+            Some("team pictures")
+        );
+        assert_eq!(registry.get_by_category("team pictures").len(), 1);
+        assert_eq!(
+            registry.categories_of("google_images"),
+            vec!["images", "team pictures"]
+        );
+    }
+
+    #[test]
+    fn test_bang_collisions() {
+        let mut registry = EngineRegistry::new();
+        let config = EngineConfig {
+            name: "google".to_string(),
+            engine: "google".to_string(),
+            shortcut: "g".to_string(),
+            ..Default::default()
+        };
+        registry.register(Arc::new(Google::new()), config);
+
+        let collisions = registry.bang_collisions(&BangRegistry::bundled().unwrap());
+        assert!(collisions.contains(&("g".to_string(), "google".to_string())));
+    }
+
+    #[test]
+    fn test_private_engines() {
+        let mut registry = EngineRegistry::new();
+        registry.register(
+            Arc::new(Google::new()),
+            EngineConfig {
+                name: "google".to_string(),
+                engine: "google".to_string(),
+                shortcut: "g".to_string(),
+                ..Default::default()
+            },
+        );
+        registry.register(
+            Arc::new(GoogleImages::new()),
+            EngineConfig {
+                name: "intranet".to_string(),
+                engine: "google_images".to_string(),
+                shortcut: "in".to_string(),
+                categories: vec!["internal".to_string()],
+                tokens: vec!["s3cret".to_string()],
+                ..Default::default()
+            },
+        );
+        assert!(registry.has_private());
+        assert!(registry.is_private("google_images"));
+
+        let public = registry.visible_to(&[]);
+        assert_eq!(public.len(), 1);
+        assert_eq!(public.resolve_name("in"), None);
+        assert_eq!(public.resolve_name("intranet"), None);
+        assert_eq!(public.resolve_category("internal"), None);
+
+        let wrong = registry.visible_to(&["guess".to_string()]);
+        assert!(!wrong.contains("google_images"));
+
+        let unlocked = registry.visible_to(&["s3cret".to_string()]);
+        assert_eq!(unlocked.len(), 2);
+        assert_eq!(unlocked.resolve_name("in"), Some("google_images"));
+        assert_eq!(unlocked.get_by_category("internal").len(), 1);
+    }
 }
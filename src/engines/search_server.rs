@@ -0,0 +1,200 @@
+//! Shared configuration of engines querying self-hosted search servers
+//!
+//! The Meilisearch, Elasticsearch/OpenSearch and Solr engines map documents
+//! to results through a configurable [`FieldMapping`], fill a query template
+//! with the user's query and may send an authentication header. The SQLite
+//! engine maps its rows through the same [`FieldMapping`].
+
+use super::dates;
+use crate::config::EngineConfig;
+use crate::results::Result;
+use anyhow::Result as AnyhowResult;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Placeholder replaced with the user's query in query templates
+pub const QUERY_PLACEHOLDER: &str = "{{query}}";
+
+/// Document fields holding the result's URL, title, content and date
+///
+/// Nested fields are addressed with dots, e.g. `meta.url`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FieldMapping {
+    pub url: String,
+    pub title: String,
+    pub content: String,
+    pub date: String,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        Self {
+            url: "url".to_string(),
+            title: "title".to_string(),
+            content: "content".to_string(),
+            date: "date".to_string(),
+        }
+    }
+}
+
+impl FieldMapping {
+    /// Read the `fields` option
+    pub fn from_config(config: &EngineConfig) -> Self {
+        config.extra_value("fields").unwrap_or_default()
+    }
+
+    /// Build a result from a document, if it has a URL and a title
+    pub fn result(&self, doc: &Value, engine: &str, position: u32) -> Option<Result> {
+        let url = lookup(doc, &self.url).and_then(text)?;
+        let title = lookup(doc, &self.title).and_then(text)?;
+
+        let mut result = Result::new(url, title, engine.to_string()).with_position(position);
+        if let Some(content) = lookup(doc, &self.content).and_then(text) {
+            result = result.with_content(content);
+        }
+        result.metadata.published_date = lookup(doc, &self.date).and_then(date);
+        Some(result)
+    }
+}
+
+/// Look up a dotted path in a document
+fn lookup<'a>(doc: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(doc, |value, key| value.get(key))
+}
+
+/// Text of a field; multi-valued fields use their first value
+fn text(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Array(values) => return values.first().and_then(text),
+        _ => return None,
+    };
+    Some(text).filter(|t| !t.is_empty())
+}
+
+/// Date of a field; numbers are read as Unix timestamps
+fn date(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Number(n) => DateTime::from_timestamp(n.as_i64()?, 0),
+        value => text(value).as_deref().and_then(dates::parse),
+    }
+}
+
+/// Authentication header to send
+///
+/// `auth_header` ("Name: value") is sent as is; otherwise an `api_key` is
+/// sent as `Authorization: <scheme> <api_key>`.
+pub fn auth_header(config: &EngineConfig, scheme: &str) -> AnyhowResult<Option<(String, String)>> {
+    if let Some(header) = config.extra_str("auth_header") {
+        let (name, value) = header.split_once(':').ok_or_else(|| {
+            anyhow::anyhow!(
+                "auth_header of engine '{}' must look like 'Name: value'",
+                config.name
+            )
+        })?;
+        return Ok(Some((name.trim().to_string(), value.trim().to_string())));
+    }
+
+    Ok(config
+        .api_key
+        .as_ref()
+        .filter(|k| !k.is_empty())
+        .map(|key| ("Authorization".to_string(), format!("{} {}", scheme, key))))
+}
+
+/// Read a JSON `query_template` given as a YAML mapping or a JSON string
+pub fn json_template(config: &EngineConfig, default: Value) -> AnyhowResult<Value> {
+    match config.extra_value::<Value>("query_template") {
+        Some(Value::String(json)) => serde_json::from_str(&json).map_err(|e| {
+            anyhow::anyhow!("Invalid query_template of engine '{}': {}", config.name, e)
+        }),
+        Some(template) => Ok(template),
+        None => Ok(default),
+    }
+}
+
+/// Replace the query placeholder inside every string of a JSON template
+///
+/// The query is only ever inserted into string values, so it cannot change
+/// the structure of the request.
+pub fn fill_template(template: &Value, query: &str) -> Value {
+    match template {
+        Value::String(s) => Value::String(s.replace(QUERY_PLACEHOLDER, query)),
+        Value::Array(values) => {
+            Value::Array(values.iter().map(|v| fill_template(v, query)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), fill_template(v, query)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_field_mapping() {
+        let config: EngineConfig = serde_yaml::from_str(
+            "name: wiki\nengine: elasticsearch\nfields:\n  url: meta.link\n  date: modified",
+        )
+        .unwrap();
+        let fields = FieldMapping::from_config(&config);
+        assert_eq!(fields.title, "title");
+
+        let doc = json!({
+            "meta": {"link": "https://wiki.example.org/Deploy"},
+            "title": ["Deploy", "Deployment"],
+            "content": "How to deploy",
+            "modified": 1700000000
+        });
+        let result = fields.result(&doc, "wiki", 1).unwrap();
+        assert_eq!(result.url, "https://wiki.example.org/Deploy");
+        assert_eq!(result.title, "Deploy");
+        assert_eq!(result.content.as_deref(), Some("How to deploy"));
+        assert_eq!(
+            result.metadata.published_date,
+            "2023-11-14T22:13:20Z".parse().ok()
+        );
+
+        assert!(fields
+            .result(&json!({"title": "No URL"}), "wiki", 1)
+            .is_none());
+    }
+
+    #[test]
+    fn test_auth_header() {
+        let config: EngineConfig =
+            serde_yaml::from_str("name: s\nengine: solr\nauth_header: 'X-Token: abc:def'").unwrap();
+        assert_eq!(
+            auth_header(&config, "Bearer").unwrap(),
+            Some(("X-Token".to_string(), "abc:def".to_string()))
+        );
+
+        let config: EngineConfig =
+            serde_yaml::from_str("name: s\nengine: meilisearch\napi_key: k").unwrap();
+        assert_eq!(
+            auth_header(&config, "Bearer").unwrap(),
+            Some(("Authorization".to_string(), "Bearer k".to_string()))
+        );
+
+        let config: EngineConfig =
+            serde_yaml::from_str("name: s\nengine: solr\nauth_header: token").unwrap();
+        assert!(auth_header(&config, "Basic").is_err());
+    }
+
+    #[test]
+    fn test_fill_template() {
+        let template = json!({"query": {"match": {"title": "{{query}}"}}, "size": 5});
+        let filled = fill_template(&template, r#"" }, "evil": {"#);
+        assert_eq!(filled["query"]["match"]["title"], r#"" }, "evil": {"#);
+        assert_eq!(filled["size"], 5);
+    }
+}
@@ -0,0 +1,330 @@
+//! SearXNG / searxng-rs federation engine implementation
+//!
+//! Forwards searches to another instance's JSON API and merges its results,
+//! answers, suggestions and infoboxes. Each forwarded search carries a hop
+//! count in [`HOPS_HEADER`]; instances skip their federation engines once it
+//! reaches `max_hops`, so instances federating with each other don't recurse.
+
+use super::dates;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{Answer, InfoBox, Result, ResultType, Suggestion};
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Header carrying the number of instances a search was forwarded through
+pub const HOPS_HEADER: &str = "X-Searx-Hops";
+
+/// Default number of hops after which searches are no longer forwarded
+const DEFAULT_MAX_HOPS: u32 = 1;
+
+/// `/search?format=json` response
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    number_of_results: Option<f64>,
+    #[serde(default)]
+    results: Vec<RemoteResult>,
+    #[serde(default)]
+    answers: Vec<RemoteAnswer>,
+    #[serde(default)]
+    suggestions: Vec<String>,
+    #[serde(default)]
+    infoboxes: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteResult {
+    url: Option<String>,
+    title: Option<String>,
+    content: Option<String>,
+    #[serde(default)]
+    engines: Vec<String>,
+    engine: Option<String>,
+    thumbnail: Option<String>,
+    img_src: Option<String>,
+    author: Option<String>,
+    /// SearXNG spells it `publishedDate`
+    #[serde(alias = "publishedDate")]
+    published_date: Option<String>,
+}
+
+/// Answers are plain strings (searxng-rs) or objects (SearXNG)
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RemoteAnswer {
+    Text(String),
+    Object { answer: String, url: Option<String> },
+}
+
+/// Federation engine querying another SearXNG or searxng-rs instance
+pub struct Searx {
+    name: String,
+    base_url: String,
+    max_hops: u32,
+}
+
+impl Searx {
+    pub fn new() -> Self {
+        Self {
+            name: "searx".to_string(),
+            base_url: String::new(),
+            max_hops: DEFAULT_MAX_HOPS,
+        }
+    }
+
+    fn parse_result(&self, remote: RemoteResult, position: u32) -> Option<Result> {
+        let url = remote.url.filter(|u| !u.is_empty())?;
+        let title = remote.title.filter(|t| !t.is_empty())?;
+
+        let mut result = Result::new(url, title, self.name.clone()).with_position(position);
+        if let Some(content) = remote.content.filter(|c| !c.is_empty()) {
+            result = result.with_content(content);
+        }
+        // Keep the engines that found the result upstream
+        result
+            .engines
+            .extend(remote.engines.into_iter().chain(remote.engine));
+        if remote.img_src.is_some() {
+            result.result_type = ResultType::Image;
+        }
+        result.metadata.thumbnail = remote.thumbnail.filter(|t| !t.is_empty());
+        result.metadata.img_src = remote.img_src.filter(|i| !i.is_empty());
+        result.metadata.author = remote.author.filter(|a| !a.is_empty());
+        result.metadata.published_date = remote.published_date.as_deref().and_then(dates::parse);
+        Some(result)
+    }
+
+    /// Parse an infobox in either the SearXNG or the searxng-rs layout
+    fn parse_infobox(&self, value: &Value) -> Option<InfoBox> {
+        let str_field = |key: &str| value[key].as_str().map(str::to_string);
+        // SearXNG: `infobox` is the title, links and attributes are objects
+        let title = str_field("title").or_else(|| str_field("infobox"))?;
+        let pairs = |key: &str, first: &str, second: &str| -> Vec<(String, String)> {
+            value[key]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|pair| match pair {
+                    Value::Array(p) => Some((p.first()?.as_str()?, p.get(1)?.as_str()?)),
+                    Value::Object(_) => Some((pair[first].as_str()?, pair[second].as_str()?)),
+                    _ => None,
+                })
+                .map(|(a, b)| (a.to_string(), b.to_string()))
+                .collect()
+        };
+
+        Some(InfoBox {
+            id: str_field("id").unwrap_or_else(|| title.clone()),
+            content: str_field("content").filter(|c| !c.is_empty()),
+            img_src: str_field("img_src"),
+            url: str_field("url"),
+            engine: self.name.clone(),
+            attributes: pairs("attributes", "label", "value"),
+            urls: pairs("urls", "title", "url"),
+            title,
+        })
+    }
+}
+
+impl Default for Searx {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Searx {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn max_hops(&self) -> Option<u32> {
+        Some(self.max_hops)
+    }
+
+    /// The remote instance waits for its own engines first
+    fn timeout(&self) -> f64 {
+        8.0
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        self.base_url = config
+            .extra_str("base_url")
+            .ok_or_else(|| anyhow::anyhow!("Searx engine '{}' needs a `base_url`", config.name))?
+            .trim_end_matches('/')
+            .to_string();
+        if let Some(max_hops) = config.extra_value::<u32>("max_hops") {
+            self.max_hops = max_hops;
+        }
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut request = EngineRequest::get(format!("{}/search", self.base_url))
+            .param("q", &params.query)
+            .param("format", "json")
+            .param("pageno", params.pageno.to_string())
+            .param("categories", &params.category)
+            .param("language", &params.lang)
+            .param("safesearch", params.safesearch.to_string())
+            .header(HOPS_HEADER, (params.hops + 1).to_string());
+        if let Some(time_range) = params.time_range {
+            request = request.param("time_range", time_range.as_str());
+        }
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            // SearXNG answers 403 when its JSON format is disabled
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: SearchResponse = response.json()?;
+        let mut results = EngineResults::new();
+        for remote in data.results {
+            let position = results.results.len() as u32 + 1;
+            if let Some(result) = self.parse_result(remote, position) {
+                results.add_result(result);
+            }
+        }
+
+        for answer in data.answers {
+            let answer = match answer {
+                RemoteAnswer::Text(text) => Answer::new(text, self.name.clone()),
+                RemoteAnswer::Object { answer, url } => Answer {
+                    url,
+                    ..Answer::new(answer, self.name.clone())
+                },
+            };
+            results.add_answer(answer);
+        }
+        for text in data.suggestions {
+            results.add_suggestion(Suggestion {
+                text,
+                engine: self.name.clone(),
+            });
+        }
+        results.infoboxes = data
+            .infoboxes
+            .iter()
+            .filter_map(|infobox| self.parse_infobox(infobox))
+            .collect();
+
+        results.number_of_results = data
+            .number_of_results
+            .filter(|n| *n > 0.0)
+            .map(|n| n as u64);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn searx() -> Searx {
+        let mut engine = Searx::new();
+        let config: EngineConfig =
+            serde_yaml::from_str("name: eu\nengine: searx\nbase_url: https://eu.example.org/")
+                .unwrap();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_searx_request() {
+        let engine = searx();
+        let mut params = RequestParams::new("rust");
+        params.category = "news".to_string();
+        params.hops = 1;
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://eu.example.org/search");
+        assert_eq!(request.params.get("format"), Some(&"json".to_string()));
+        assert_eq!(request.params.get("categories"), Some(&"news".to_string()));
+        assert_eq!(request.headers.get(HOPS_HEADER), Some(&"2".to_string()));
+        assert_eq!(engine.max_hops(), Some(1));
+    }
+
+    #[test]
+    fn test_searx_response() {
+        let engine = searx();
+        let response = EngineResponse::new(200, include_str!("fixtures/searx_search.json"));
+        let results = engine.response(response).unwrap();
+
+        assert_eq!(results.results.len(), 2);
+        let first = &results.results[0];
+        assert_eq!(first.url, "https://www.rust-lang.org/");
+        assert_eq!(first.engine, "eu");
+        let mut engines: Vec<&str> = first.engines.iter().map(String::as_str).collect();
+        engines.sort();
+        assert_eq!(engines, vec!["brave", "duckduckgo", "eu"]);
+        assert_eq!(
+            results.results[1].metadata.published_date,
+            "2024-02-08T00:00:00Z".parse().ok()
+        );
+
+        let answers: Vec<&str> = results.answers.iter().map(|a| a.answer.as_str()).collect();
+        assert_eq!(answers, vec!["Rust 1.76.0", "rust (n.): iron oxide"]);
+        assert_eq!(
+            results.answers[1].url.as_deref(),
+            Some("https://en.wiktionary.org/wiki/rust")
+        );
+        assert_eq!(results.suggestions.len(), 2);
+
+        let infobox = &results.infoboxes[0];
+        assert_eq!(infobox.title, "Rust (programming language)");
+        assert_eq!(infobox.engine, "eu");
+        assert_eq!(
+            infobox.attributes,
+            vec![("Designed by".to_string(), "Graydon Hoare".to_string())]
+        );
+        assert_eq!(
+            infobox.urls,
+            vec![(
+                "Official website".to_string(),
+                "https://www.rust-lang.org/".to_string()
+            )]
+        );
+        assert_eq!(results.number_of_results, Some(120000));
+    }
+
+    #[test]
+    fn test_searx_native_infobox() {
+        let engine = searx();
+        let response = EngineResponse::new(
+            200,
+            r#"{"results": [], "answers": [], "suggestions": [], "infoboxes": [{
+                "id": "https://en.wikipedia.org/wiki/Tokio", "title": "Tokio",
+                "content": "An async runtime", "engine": "wikipedia",
+                "attributes": [["License", "MIT"]], "urls": [["Website", "https://tokio.rs"]]
+            }]}"#,
+        );
+        let results = engine.response(response).unwrap();
+        let infobox = &results.infoboxes[0];
+        assert_eq!(infobox.id, "https://en.wikipedia.org/wiki/Tokio");
+        assert_eq!(
+            infobox.attributes,
+            vec![("License".to_string(), "MIT".to_string())]
+        );
+        assert_eq!(results.number_of_results, None);
+    }
+}
@@ -0,0 +1,241 @@
+//! Semantic Scholar search engine implementation
+//!
+//! Uses the Semantic Scholar Academic Graph API. Anonymous access shares a
+//! global rate limit; an `api_key` gives the instance its own quota.
+
+use super::dates;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::{EngineError, Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+
+/// Paper fields requested from the API
+const FIELDS: &str = "title,url,abstract,authors,venue,journal,year,publicationDate,\
+citationCount,isOpenAccess,openAccessPdf,externalIds";
+
+/// `GET /paper/search` response
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    data: Vec<Paper>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Paper {
+    paper_id: String,
+    url: Option<String>,
+    title: Option<String>,
+    #[serde(rename = "abstract")]
+    abstract_text: Option<String>,
+    #[serde(default)]
+    authors: Vec<Author>,
+    venue: Option<String>,
+    journal: Option<Journal>,
+    year: Option<u32>,
+    publication_date: Option<String>,
+    citation_count: Option<u64>,
+    #[serde(default)]
+    is_open_access: bool,
+    open_access_pdf: Option<OpenAccessPdf>,
+    external_ids: Option<ExternalIds>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Journal {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAccessPdf {
+    url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExternalIds {
+    #[serde(rename = "DOI")]
+    doi: Option<String>,
+}
+
+/// Semantic Scholar paper search engine
+pub struct SemanticScholar {
+    api_url: String,
+    api_key: Option<String>,
+}
+
+impl SemanticScholar {
+    pub fn new() -> Self {
+        Self {
+            api_url: "https://api.semanticscholar.org/graph/v1/paper/search".to_string(),
+            api_key: None,
+        }
+    }
+}
+
+impl Default for SemanticScholar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for SemanticScholar {
+    fn name(&self) -> &str {
+        "semantic_scholar"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://www.semanticscholar.org")
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["science"]
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.api_key = config.api_key.clone().filter(|k| !k.is_empty());
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let mut request = EngineRequest::get(&self.api_url)
+            .param("query", &params.query)
+            .param("fields", FIELDS)
+            .param("limit", "10")
+            .param("offset", ((params.pageno.max(1) - 1) * 10).to_string());
+
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-api-key", api_key);
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if response.is_rate_limited() {
+            return Err(EngineFailure::new(EngineError::TooManyRequests).into());
+        }
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: SearchResponse = response.json()?;
+        let mut results = Vec::new();
+
+        for paper in data.data {
+            let Some(title) = paper.title.filter(|t| !t.is_empty()) else {
+                continue;
+            };
+
+            let url = paper.url.unwrap_or_else(|| {
+                format!("https://www.semanticscholar.org/paper/{}", paper.paper_id)
+            });
+            let mut result = Result::new(url, title, self.name().to_string())
+                .with_position(results.len() as u32 + 1);
+            result.result_type = ResultType::Paper;
+
+            if let Some(abstract_text) = paper.abstract_text.filter(|a| !a.is_empty()) {
+                result = result.with_content(abstract_text);
+            }
+
+            let authors: Vec<String> = paper.authors.into_iter().map(|a| a.name).collect();
+
+            result.metadata.template = Some("paper.html".to_string());
+            result.metadata.author = Some(authors.join(", ")).filter(|a| !a.is_empty());
+            result.metadata.authors = authors;
+            result.metadata.journal = paper
+                .journal
+                .and_then(|j| j.name)
+                .or(paper.venue)
+                .filter(|j| !j.is_empty());
+            result.metadata.doi = paper.external_ids.and_then(|ids| ids.doi);
+            result.metadata.published_date = paper
+                .publication_date
+                .as_deref()
+                .and_then(dates::parse)
+                .or_else(|| {
+                    paper
+                        .year
+                        .and_then(|y| dates::from_parts(y as i32, None, None))
+                });
+            result.metadata.citations = paper.citation_count;
+            result.metadata.pdf_url = paper
+                .open_access_pdf
+                .and_then(|pdf| pdf.url)
+                .filter(|u| !u.is_empty());
+            result.metadata.open_access = paper.is_open_access;
+
+            results.push(result);
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semantic_scholar_request() {
+        let mut engine = SemanticScholar::new();
+        let config: EngineConfig = serde_yaml::from_str(
+            "name: semantic scholar\nengine: semantic_scholar\napi_key: s2-key",
+        )
+        .unwrap();
+        engine.init(&config).unwrap();
+
+        let request = engine.request(&RequestParams::new("alphafold")).unwrap();
+        assert_eq!(request.params.get("offset"), Some(&"0".to_string()));
+        assert!(request.params["fields"].contains("externalIds"));
+        assert_eq!(
+            request.headers.get("x-api-key"),
+            Some(&"s2-key".to_string())
+        );
+    }
+
+    #[test]
+    fn test_semantic_scholar_response() {
+        let engine = SemanticScholar::new();
+        let response =
+            EngineResponse::new(200, include_str!("fixtures/semantic_scholar_search.json"));
+        let results = engine.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        let paper = &results[0];
+        assert_eq!(paper.result_type, ResultType::Paper);
+        assert_eq!(paper.metadata.authors, vec!["J. Jumper", "Richard Evans"]);
+        assert_eq!(paper.metadata.journal.as_deref(), Some("Nature"));
+        assert_eq!(
+            paper.metadata.doi.as_deref(),
+            Some("10.1038/s41586-021-03819-2")
+        );
+        assert_eq!(
+            paper.metadata.published_date,
+            "2021-07-15T00:00:00Z".parse().ok()
+        );
+        assert_eq!(paper.metadata.citations, Some(24123));
+        assert!(paper.metadata.open_access);
+        assert!(paper.metadata.pdf_url.is_some());
+
+        let preprint = &results[1];
+        assert_eq!(preprint.metadata.journal.as_deref(), Some("bioRxiv"));
+        assert_eq!(
+            preprint.metadata.published_date,
+            "2022-01-01T00:00:00Z".parse().ok()
+        );
+        assert_eq!(preprint.metadata.pdf_url, None);
+
+        let response = EngineResponse::new(429, r#"{"message": "Too Many Requests"}"#);
+        let error = engine.response(response).unwrap_err();
+        assert!(error.downcast_ref::<EngineFailure>().is_some());
+    }
+}
@@ -0,0 +1,232 @@
+//! Apache Solr engine implementation
+//!
+//! Searches a Solr collection through its `select` handler with the edismax
+//! query parser. The `query_template` is the `q` parameter; the user's query
+//! is escaped before it is inserted.
+
+use super::search_server::{self, FieldMapping, QUERY_PLACEHOLDER};
+use super::traits::*;
+use crate::config::EngineConfig;
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// `GET /{collection}/select` response
+#[derive(Debug, Deserialize)]
+struct SelectResponse {
+    response: DocList,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocList {
+    num_found: Option<u64>,
+    #[serde(default)]
+    docs: Vec<Value>,
+}
+
+/// Solr collection search engine
+pub struct Solr {
+    name: String,
+    base_url: String,
+    collection: String,
+    fields: FieldMapping,
+    template: String,
+    auth: Option<(String, String)>,
+}
+
+impl Solr {
+    pub fn new() -> Self {
+        Self {
+            name: "solr".to_string(),
+            base_url: "http://localhost:8983/solr".to_string(),
+            collection: String::new(),
+            fields: FieldMapping::default(),
+            template: QUERY_PLACEHOLDER.to_string(),
+            auth: None,
+        }
+    }
+
+    /// Escape the characters of the Solr query syntax
+    fn escape(query: &str) -> String {
+        let mut escaped = String::with_capacity(query.len());
+        for c in query.chars() {
+            if "+-&|!(){}[]^\"~*?:\\/".contains(c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+}
+
+impl Default for Solr {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Solr {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(&self.base_url)
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(base_url) = config.extra_str("base_url") {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        self.collection = config
+            .extra_str("index")
+            .ok_or_else(|| anyhow::anyhow!("Solr engine '{}' needs an `index`", config.name))?;
+        self.fields = FieldMapping::from_config(config);
+        if let Some(template) = config.extra_str("query_template") {
+            self.template = template;
+        }
+        self.auth = search_server::auth_header(config, "Basic")?;
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let q = self
+            .template
+            .replace(QUERY_PLACEHOLDER, &Self::escape(&params.query));
+        let per_page = self.results_per_page();
+
+        let url = format!("{}/{}/select", self.base_url, self.collection);
+        let mut request = EngineRequest::get(url)
+            .param("q", q)
+            .param("defType", "edismax")
+            .param(
+                "qf",
+                format!("{}^2 {}", self.fields.title, self.fields.content),
+            )
+            .param("start", ((params.pageno.max(1) - 1) * per_page).to_string())
+            .param("rows", per_page.to_string())
+            .param("wt", "json");
+        if let Some((name, value)) = &self.auth {
+            request = request.header(name, value);
+        }
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            // Errors come as {"error": {"msg": ..., "code": ...}}
+            let message = response
+                .json::<Value>()
+                .ok()
+                .and_then(|e| e["error"]["msg"].as_str().map(str::to_string));
+            return Err(match message {
+                Some(message) => anyhow::anyhow!("Solr error: {}", message),
+                None => anyhow::anyhow!("HTTP error: {}", response.status),
+            });
+        }
+
+        let data: SelectResponse = response.json()?;
+        let mut results = Vec::new();
+        for doc in &data.response.docs {
+            if let Some(result) = self
+                .fields
+                .result(doc, &self.name, results.len() as u32 + 1)
+            {
+                results.push(result);
+            }
+        }
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = data.response.num_found;
+        Ok(engine_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::HttpClient;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn solr(yaml: &str) -> Solr {
+        let mut engine = Solr::new();
+        let config: EngineConfig = serde_yaml::from_str(yaml).unwrap();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_solr_request() {
+        let engine = solr(
+            "name: kb\nengine: solr\nindex: kb\nquery_template: '{{query}} AND visibility:public'",
+        );
+        let mut params = RequestParams::new("c++ (build)");
+        params.pageno = 2;
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "http://localhost:8983/solr/kb/select");
+        assert_eq!(
+            request.params.get("q"),
+            Some(&r"c\+\+ \(build\) AND visibility:public".to_string())
+        );
+        assert_eq!(
+            request.params.get("qf"),
+            Some(&"title^2 content".to_string())
+        );
+        assert_eq!(request.params.get("start"), Some(&"10".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_solr_mock_server() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/solr/kb/select"))
+            .and(query_param("q", "deploy"))
+            .and(query_param("wt", "json"))
+            .and(header("Authorization", "Basic dXNlcjpwYXNz"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("fixtures/solr_select.json")),
+            )
+            .mount(&server)
+            .await;
+
+        let engine = solr(&format!(
+            "name: kb\nengine: solr\nbase_url: {}/solr\nindex: kb\napi_key: dXNlcjpwYXNz\n\
+             fields:\n  date: last_modified",
+            server.uri()
+        ));
+        let request = engine.request(&RequestParams::new("deploy")).unwrap();
+        let response = HttpClient::new().unwrap().execute(request).await.unwrap();
+        let results = engine.response(response).unwrap();
+
+        assert_eq!(results.number_of_results, Some(14));
+        let first = &results.results[0];
+        assert_eq!(first.url, "https://kb.example.org/17");
+        // Multi-valued fields use their first value
+        assert_eq!(first.title, "Deploying with Ansible");
+        assert_eq!(first.content.as_deref(), Some("Playbooks for the deploy."));
+        assert_eq!(
+            first.metadata.published_date,
+            "2023-10-01T00:00:00Z".parse().ok()
+        );
+        assert_eq!(results.results[1].content, None);
+    }
+
+    #[test]
+    fn test_solr_error() {
+        let engine = solr("name: kb\nengine: solr\nindex: kb");
+        let response = EngineResponse::new(
+            400,
+            r#"{"error": {"msg": "undefined field visibility", "code": 400}}"#,
+        );
+        let error = engine.response(response).unwrap_err();
+        assert!(error.to_string().contains("undefined field visibility"));
+    }
+}
@@ -0,0 +1,222 @@
+//! SoundCloud search engine implementation
+//!
+//! Searches tracks with the API behind soundcloud.com, which needs the
+//! website's client id as `api_key`. Results embed SoundCloud's widget.
+
+use super::dates;
+use super::media;
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::Result;
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+
+/// `GET /search/tracks` response
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    collection: Vec<Track>,
+    total_results: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Track {
+    title: Option<String>,
+    permalink_url: Option<String>,
+    /// API URI of the track, used by the widget
+    uri: Option<String>,
+    description: Option<String>,
+    /// Milliseconds
+    full_duration: Option<u64>,
+    duration: Option<u64>,
+    playback_count: Option<u64>,
+    created_at: Option<String>,
+    genre: Option<String>,
+    artwork_url: Option<String>,
+    user: Option<User>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    username: String,
+    avatar_url: Option<String>,
+}
+
+/// SoundCloud search engine
+pub struct SoundCloud {
+    name: String,
+    client_id: String,
+}
+
+impl SoundCloud {
+    pub fn new() -> Self {
+        Self {
+            name: "soundcloud".to_string(),
+            client_id: String::new(),
+        }
+    }
+
+    fn track_result(&self, track: Track) -> Option<Result> {
+        let url = track.permalink_url.filter(|u| !u.is_empty())?;
+        let title = track.title.filter(|t| !t.is_empty())?;
+
+        let mut result = Result::new(url, title, self.name.clone());
+        if let Some(description) = track.description.filter(|d| !d.trim().is_empty()) {
+            result = result.with_content(description.trim().to_string());
+        }
+
+        let avatar = track.user.as_ref().and_then(|u| u.avatar_url.clone());
+        let meta = &mut result.metadata;
+        meta.template = Some("audio.html".to_string());
+        meta.author = track.user.map(|u| u.username);
+        // Previews of paid tracks report their length in `duration`
+        meta.duration = track
+            .full_duration
+            .or(track.duration)
+            .map(|ms| media::format_duration(ms / 1000));
+        meta.views = track.playback_count;
+        meta.published_date = track.created_at.as_deref().and_then(dates::parse);
+        meta.thumbnail = track.artwork_url.filter(|a| !a.is_empty()).or(avatar);
+        meta.tags = track.genre.filter(|g| !g.is_empty()).into_iter().collect();
+        meta.iframe_src = track.uri.map(|uri| {
+            format!(
+                "https://w.soundcloud.com/player/?url={}&visual=false&show_comments=false",
+                urlencoding::encode(&uri)
+            )
+        });
+        Some(result)
+    }
+}
+
+impl Default for SoundCloud {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for SoundCloud {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://soundcloud.com")
+            .official_api(false)
+            .api_key_required(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["music"]
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        self.client_id = config
+            .api_key
+            .clone()
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "SoundCloud engine '{}' needs the client id as `api_key`",
+                    config.name
+                )
+            })?;
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let per_page = self.results_per_page();
+        Ok(
+            EngineRequest::get("https://api-v2.soundcloud.com/search/tracks")
+                .param("q", &params.query)
+                .param("client_id", &self.client_id)
+                .param("limit", per_page.to_string())
+                .param(
+                    "offset",
+                    ((params.pageno.max(1) - 1) * per_page).to_string(),
+                ),
+        )
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            if response.status == 401 || response.status == 403 {
+                return Err(anyhow::anyhow!(
+                    "SoundCloud rejected the client id ({})",
+                    response.status
+                ));
+            }
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: SearchResponse = response.json()?;
+        let results = data
+            .collection
+            .into_iter()
+            .filter_map(|track| self.track_result(track))
+            .enumerate()
+            .map(|(i, r)| r.with_position(i as u32 + 1))
+            .collect();
+
+        let mut engine_results = EngineResults::with_results(results);
+        engine_results.number_of_results = data.total_results;
+        Ok(engine_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn soundcloud() -> SoundCloud {
+        let mut engine = SoundCloud::new();
+        let config: EngineConfig =
+            serde_yaml::from_str("name: soundcloud\nengine: soundcloud\napi_key: abc123").unwrap();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_soundcloud_request() {
+        let engine = soundcloud();
+        let mut params = RequestParams::new("lofi");
+        params.pageno = 3;
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://api-v2.soundcloud.com/search/tracks");
+        assert_eq!(request.params.get("client_id"), Some(&"abc123".to_string()));
+        assert_eq!(request.params.get("offset"), Some(&"20".to_string()));
+
+        let config: EngineConfig =
+            serde_yaml::from_str("name: soundcloud\nengine: soundcloud").unwrap();
+        assert!(SoundCloud::new().init(&config).is_err());
+    }
+
+    #[test]
+    fn test_soundcloud_response() {
+        let engine = soundcloud();
+        let response = EngineResponse::new(200, include_str!("fixtures/soundcloud_search.json"));
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.number_of_results, Some(3812));
+        assert_eq!(results.results.len(), 2);
+
+        let track = &results.results[0];
+        assert_eq!(track.url, "https://soundcloud.com/lofi-girl/snowman");
+        assert_eq!(track.metadata.author.as_deref(), Some("Lofi Girl"));
+        assert_eq!(track.metadata.duration.as_deref(), Some("2:31"));
+        assert_eq!(track.metadata.views, Some(98231));
+        assert_eq!(track.metadata.tags, vec!["Lofi"]);
+        assert_eq!(
+            track.metadata.iframe_src.as_deref(),
+            Some("https://w.soundcloud.com/player/?url=https%3A%2F%2Fapi.soundcloud.com%2Ftracks%2F1234&visual=false&show_comments=false")
+        );
+
+        // Tracks without artwork show the uploader's avatar
+        assert_eq!(
+            results.results[1].metadata.thumbnail.as_deref(),
+            Some("https://i1.sndcdn.com/avatars-xyz-large.jpg")
+        );
+    }
+}
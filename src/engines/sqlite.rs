@@ -0,0 +1,334 @@
+//! SQLite engine implementation
+//!
+//! Runs a configured SQL query against a local SQLite database. The user's
+//! query is bound to the `:query` parameter, never interpolated, and the
+//! database is opened read-only. Columns are mapped to results through
+//! `fields`.
+
+use super::search_server::FieldMapping;
+use super::traits::*;
+use crate::config::EngineConfig;
+use anyhow::Result as AnyhowResult;
+use rusqlite::types::ValueRef;
+use rusqlite::{Batch, Connection, OpenFlags, Statement};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Named parameters available to the query
+const QUERY_PARAM: &str = ":query";
+const LIMIT_PARAM: &str = ":limit";
+const OFFSET_PARAM: &str = ":offset";
+
+/// Search engine over a SQLite database
+pub struct Sqlite {
+    name: String,
+    database: PathBuf,
+    /// SELECT statement, paged with `:limit` and `:offset`
+    query: String,
+    fields: FieldMapping,
+    connection: Option<Mutex<Connection>>,
+}
+
+impl Sqlite {
+    pub fn new() -> Self {
+        Self {
+            name: "sqlite".to_string(),
+            database: PathBuf::new(),
+            query: String::new(),
+            fields: FieldMapping::default(),
+            connection: None,
+        }
+    }
+
+    /// Check the configured query and add paging if it has none
+    fn prepare_query(connection: &Connection, query: &str) -> AnyhowResult<String> {
+        let query = query.trim().trim_end_matches(';').trim_end();
+
+        let mut statements = 0;
+        let mut batch = Batch::new(connection, query);
+        while batch.next()?.is_some() {
+            statements += 1;
+        }
+        if statements != 1 {
+            return Err(anyhow::anyhow!("query must be a single statement"));
+        }
+
+        let statement = connection.prepare(query)?;
+        if !statement.readonly() {
+            return Err(anyhow::anyhow!("query must not write to the database"));
+        }
+        if statement.parameter_index(QUERY_PARAM)?.is_none() {
+            return Err(anyhow::anyhow!(
+                "query must use the {} parameter",
+                QUERY_PARAM
+            ));
+        }
+
+        let has_paging = statement.parameter_index(LIMIT_PARAM)?.is_some()
+            && statement.parameter_index(OFFSET_PARAM)?.is_some();
+        Ok(if has_paging {
+            query.to_string()
+        } else {
+            format!(
+                "SELECT * FROM ({}) LIMIT {} OFFSET {}",
+                query, LIMIT_PARAM, OFFSET_PARAM
+            )
+        })
+    }
+
+    /// Bind a named parameter if the query uses it
+    fn bind(
+        statement: &mut Statement<'_>,
+        name: &str,
+        value: impl rusqlite::ToSql,
+    ) -> rusqlite::Result<()> {
+        if let Some(index) = statement.parameter_index(name)? {
+            statement.raw_bind_parameter(index, value)?;
+        }
+        Ok(())
+    }
+
+    /// Row as a JSON object keyed by column name
+    fn row_to_json(row: &rusqlite::Row<'_>, columns: &[String]) -> rusqlite::Result<Value> {
+        let mut doc = Map::new();
+        for (i, column) in columns.iter().enumerate() {
+            let value = match row.get_ref(i)? {
+                ValueRef::Integer(n) => Value::from(n),
+                ValueRef::Real(n) => Value::from(n),
+                ValueRef::Text(text) => Value::from(String::from_utf8_lossy(text).into_owned()),
+                ValueRef::Null | ValueRef::Blob(_) => Value::Null,
+            };
+            doc.insert(column.clone(), value);
+        }
+        Ok(Value::Object(doc))
+    }
+}
+
+impl Default for Sqlite {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Sqlite {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new().results_format("local")
+    }
+
+    fn engine_type(&self) -> EngineType {
+        EngineType::Offline
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+
+        let database = config
+            .extra_str("database")
+            .ok_or_else(|| anyhow::anyhow!("SQLite engine '{}' needs a `database`", config.name))?;
+        let query = config
+            .extra_str("query")
+            .ok_or_else(|| anyhow::anyhow!("SQLite engine '{}' needs a `query`", config.name))?;
+        self.database = PathBuf::from(database);
+        self.fields = FieldMapping::from_config(config);
+
+        let connection = Connection::open_with_flags(
+            &self.database,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| anyhow::anyhow!("Cannot open '{}': {}", self.database.display(), e))?;
+        connection.pragma_update(None, "query_only", true)?;
+
+        self.query = Self::prepare_query(&connection, &query)
+            .map_err(|e| anyhow::anyhow!("Invalid query of engine '{}': {}", config.name, e))?;
+        self.connection = Some(Mutex::new(connection));
+        Ok(())
+    }
+
+    fn search(&self, params: &RequestParams) -> AnyhowResult<EngineResults> {
+        let connection = self
+            .connection
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("SQLite engine '{}' is not initialized", self.name))?
+            .lock()
+            .unwrap();
+
+        let per_page = self.results_per_page();
+        let mut statement = connection.prepare_cached(&self.query)?;
+        Self::bind(&mut statement, QUERY_PARAM, &params.query)?;
+        Self::bind(&mut statement, LIMIT_PARAM, per_page)?;
+        Self::bind(
+            &mut statement,
+            OFFSET_PARAM,
+            (params.pageno.max(1) - 1) * per_page,
+        )?;
+
+        let columns: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let mut rows = statement.raw_query();
+        let mut results = Vec::new();
+        while let Some(row) = rows.next()? {
+            let doc = Self::row_to_json(row, &columns)?;
+            let position = results.len() as u32 + 1;
+            if let Some(result) = self.fields.result(&doc, &self.name, position) {
+                results.push(result);
+            }
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+
+    fn request(&self, _params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        Err(anyhow::anyhow!(
+            "The SQLite engine searches a local database"
+        ))
+    }
+
+    fn response(&self, _response: EngineResponse) -> AnyhowResult<EngineResults> {
+        Err(anyhow::anyhow!(
+            "The SQLite engine searches a local database"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Links database with five entries
+    fn links_db() -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let connection = Connection::open(file.path()).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE links (href TEXT, name TEXT, note TEXT, added INTEGER);
+                 INSERT INTO links VALUES
+                   ('https://a.example.org', 'Rust book', 'Learn Rust', 1700000000),
+                   ('https://b.example.org', 'Rust by example', NULL, 1700000100),
+                   ('https://c.example.org', 'Rustlings', 'Small exercises', 1700000200),
+                   ('https://d.example.org', 'Go tour', 'Learn Go', 1700000300),
+                   ('https://e.example.org', 'Rust API guidelines', NULL, 1700000400);",
+            )
+            .unwrap();
+        file
+    }
+
+    fn sqlite(db: &tempfile::NamedTempFile, query: &str) -> AnyhowResult<Sqlite> {
+        let mut config: EngineConfig = serde_yaml::from_str(
+            "name: links\nengine: sqlite\nfields:\n  url: href\n  title: name\n  content: note\n  date: added",
+        )
+        .unwrap();
+        config.extra.insert(
+            "database".to_string(),
+            db.path().to_string_lossy().into_owned().into(),
+        );
+        config
+            .extra
+            .insert("query".to_string(), query.to_string().into());
+
+        let mut engine = Sqlite::new();
+        engine.init(&config)?;
+        Ok(engine)
+    }
+
+    #[test]
+    fn test_sqlite_search() {
+        let db = links_db();
+        let engine = sqlite(
+            &db,
+            "SELECT * FROM links WHERE name LIKE '%' || :query || '%' ORDER BY added;",
+        )
+        .unwrap();
+
+        let results = engine.search(&RequestParams::new("rust")).unwrap();
+        let titles: Vec<&str> = results.results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Rust book",
+                "Rust by example",
+                "Rustlings",
+                "Rust API guidelines"
+            ]
+        );
+        let first = &results.results[0];
+        assert_eq!(first.url, "https://a.example.org");
+        assert_eq!(first.engine, "links");
+        assert_eq!(first.content.as_deref(), Some("Learn Rust"));
+        assert_eq!(
+            first.metadata.published_date,
+            "2023-11-14T22:13:20Z".parse().ok()
+        );
+        assert_eq!(results.results[1].content, None);
+    }
+
+    #[test]
+    fn test_sqlite_paging() {
+        let db = links_db();
+        Connection::open(db.path())
+            .unwrap()
+            .execute_batch(
+                "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 7)
+                 INSERT INTO links SELECT 'https://x.example.org/' || i, 'Extra ' || i, 'Extra', i FROM n;",
+            )
+            .unwrap();
+        let mut params = RequestParams::new("");
+        params.pageno = 2;
+
+        // Queries without paging are wrapped
+        let engine = sqlite(
+            &db,
+            "SELECT * FROM links WHERE name LIKE :query || '%' ORDER BY rowid",
+        )
+        .unwrap();
+        let results = engine.search(&params).unwrap();
+        let titles: Vec<&str> = results.results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["Extra 6", "Extra 7"]);
+
+        // Queries with their own paging are used as is
+        let engine = sqlite(
+            &db,
+            "SELECT * FROM links WHERE :query = '' ORDER BY added DESC LIMIT :limit OFFSET :offset",
+        )
+        .unwrap();
+        let results = engine.search(&params).unwrap();
+        let titles: Vec<&str> = results.results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["Extra 2", "Extra 1"]);
+    }
+
+    #[test]
+    fn test_sqlite_query_is_bound() {
+        let db = links_db();
+        let engine = sqlite(&db, "SELECT * FROM links WHERE name = :query").unwrap();
+
+        let results = engine.search(&RequestParams::new("x' OR '1'='1")).unwrap();
+        assert!(results.results.is_empty());
+        let results = engine.search(&RequestParams::new("Go tour")).unwrap();
+        assert_eq!(results.results.len(), 1);
+    }
+
+    #[test]
+    fn test_sqlite_refuses_writes() {
+        let db = links_db();
+        for query in [
+            "DELETE FROM links WHERE name = :query",
+            "SELECT * FROM links WHERE name = :query; DROP TABLE links",
+            "SELECT * FROM links",
+        ] {
+            assert!(sqlite(&db, query).is_err(), "accepted {:?}", query);
+        }
+
+        let connection = Connection::open(db.path()).unwrap();
+        let count: u32 = connection
+            .query_row("SELECT COUNT(*) FROM links", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 5);
+    }
+}
@@ -0,0 +1,347 @@
+//! StackExchange search engine implementation
+//!
+//! Uses the StackExchange API to search for questions on any site of the
+//! network. Each configured engine picks its site through `site`
+//! (stackoverflow by default) and may set an `api_key` for a higher quota.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::query::TimeRange;
+use crate::results::{EngineError, Result};
+use anyhow::Result as AnyhowResult;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+/// StackExchange API response wrapper
+#[derive(Debug, Deserialize)]
+struct ApiResponse {
+    #[serde(default)]
+    items: Vec<Question>,
+    error_id: Option<u32>,
+    error_message: Option<String>,
+}
+
+/// Question as returned by `/search/advanced` with the default filter
+#[derive(Debug, Deserialize)]
+struct Question {
+    question_id: u64,
+    title: String,
+    link: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    owner: Option<Owner>,
+    #[serde(default)]
+    is_answered: bool,
+    accepted_answer_id: Option<u64>,
+    answer_count: Option<u64>,
+    score: Option<i64>,
+    view_count: Option<u64>,
+    creation_date: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Owner {
+    display_name: Option<String>,
+    profile_image: Option<String>,
+}
+
+/// StackExchange search engine
+pub struct StackExchange {
+    name: String,
+    api_url: String,
+    site: String,
+    api_key: Option<String>,
+}
+
+impl StackExchange {
+    pub fn new() -> Self {
+        Self::with_site("stackoverflow")
+    }
+
+    /// Create a StackExchange engine for a different site
+    pub fn with_site(site: impl Into<String>) -> Self {
+        let site = site.into();
+        Self {
+            name: site.clone(),
+            api_url: "https://api.stackexchange.com/2.3/search/advanced".to_string(),
+            site,
+            api_key: None,
+        }
+    }
+
+    /// Base URL of the configured site
+    ///
+    /// Most sites live below stackexchange.com; a few have their own domain.
+    fn site_url(&self) -> String {
+        match self.site.as_str() {
+            "stackoverflow" | "serverfault" | "superuser" | "askubuntu" | "stackapps" => {
+                format!("https://{}.com", self.site)
+            }
+            "mathoverflow" => "https://mathoverflow.net".to_string(),
+            site if site.contains('.') => format!("https://{}", site),
+            site => format!("https://{}.stackexchange.com", site),
+        }
+    }
+
+    /// Unix timestamp for the start of a time range
+    fn fromdate(time_range: TimeRange, now: i64) -> i64 {
+        now - time_range.duration().num_seconds()
+    }
+
+    /// Unescape HTML entities
+    fn unescape_html(s: &str) -> String {
+        s.replace("&amp;", "&")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&apos;", "'")
+    }
+}
+
+impl Default for StackExchange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for StackExchange {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website(self.site_url())
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["it", "q&a"]
+    }
+
+    fn supports_paging(&self) -> bool {
+        true
+    }
+
+    fn supports_time_range(&self) -> bool {
+        true
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(site) = config.extra_str("site") {
+            self.site = site;
+        }
+        self.api_key = config.api_key.clone().filter(|k| !k.is_empty());
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        // The default filter includes link, tags, owner, score, answer and
+        // accepted answer info
+        let mut request = EngineRequest::get(&self.api_url)
+            .param("q", &params.query)
+            .param("site", &self.site)
+            .param("sort", "relevance")
+            .param("order", "desc")
+            .param("pagesize", "10")
+            .param("page", params.pageno.to_string());
+
+        if let Some(time_range) = params.time_range {
+            let fromdate = Self::fromdate(time_range, Utc::now().timestamp());
+            request = request.param("fromdate", fromdate.to_string());
+        }
+
+        if let Some(api_key) = &self.api_key {
+            request = request.param("key", api_key);
+        }
+
+        Ok(request)
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        // Errors come with a JSON body, so parse before checking the status
+        let data: ApiResponse = match response.json() {
+            Ok(data) => data,
+            Err(_) if !response.is_success() => {
+                return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+            }
+            Err(e) => return Err(anyhow::anyhow!("Failed to parse JSON: {}", e)),
+        };
+
+        // Check for API errors
+        if let Some(error_id) = data.error_id {
+            let error_msg = data.error_message.as_deref().unwrap_or("Unknown error");
+            // 502 is throttle_violation
+            if error_id == 502 {
+                return Err(EngineFailure::new(EngineError::TooManyRequests).into());
+            }
+            return Err(anyhow::anyhow!(
+                "StackExchange API error {}: {}",
+                error_id,
+                error_msg
+            ));
+        }
+
+        let mut results = Vec::new();
+
+        for question in data.items {
+            let title = Self::unescape_html(&question.title);
+            if title.is_empty() {
+                continue;
+            }
+
+            let url = question
+                .link
+                .unwrap_or_else(|| format!("{}/q/{}", self.site_url(), question.question_id));
+
+            // Build content from tags and metadata
+            let mut content_parts = Vec::new();
+
+            if !question.tags.is_empty() {
+                content_parts.push(format!("[{}]", question.tags.join(", ")));
+            }
+
+            if let Some(name) = question
+                .owner
+                .as_ref()
+                .and_then(|o| o.display_name.as_ref())
+            {
+                content_parts.push(Self::unescape_html(name));
+            }
+
+            if question.accepted_answer_id.is_some() {
+                content_parts.push("✓ accepted".to_string());
+            } else if question.is_answered {
+                content_parts.push("✓ answered".to_string());
+            }
+
+            if let Some(score) = question.score {
+                content_parts.push(format!("score: {}", score));
+            }
+
+            let mut result = Result::new(url, title, self.name().to_string())
+                .with_position(results.len() as u32 + 1);
+
+            if !content_parts.is_empty() {
+                result = result.with_content(content_parts.join(" // "));
+            }
+
+            result.metadata.thumbnail = question.owner.and_then(|o| o.profile_image);
+            result.metadata.views = question.view_count;
+            result.metadata.votes = question.score;
+            result.metadata.answer_count = question.answer_count;
+            result.metadata.accepted = question.accepted_answer_id.is_some();
+            result.metadata.tags = question.tags;
+            result.metadata.published_date = question
+                .creation_date
+                .and_then(|ts| DateTime::from_timestamp(ts, 0));
+
+            results.push(result);
+        }
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stackexchange(yaml: &str) -> StackExchange {
+        let mut engine = StackExchange::new();
+        let config: EngineConfig = serde_yaml::from_str(yaml).unwrap();
+        engine.init(&config).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_stackexchange_request() {
+        let so = StackExchange::new();
+        let params = RequestParams::new("rust async");
+        let request = so.request(&params).unwrap();
+
+        assert!(request.url.contains("api.stackexchange.com"));
+        assert!(request.params.contains_key("q"));
+        assert_eq!(
+            request.params.get("site"),
+            Some(&"stackoverflow".to_string())
+        );
+        assert!(!request.params.contains_key("fromdate"));
+        assert!(!request.params.contains_key("key"));
+    }
+
+    #[test]
+    fn test_stackexchange_site_config() {
+        let engine = stackexchange("name: unix\nengine: stackexchange\nsite: unix\napi_key: k3y");
+        assert_eq!(engine.name(), "unix");
+        assert_eq!(engine.site_url(), "https://unix.stackexchange.com");
+
+        let mut params = RequestParams::new("sed in place");
+        params.time_range = Some(TimeRange::Week);
+        let request = engine.request(&params).unwrap();
+        assert_eq!(request.params.get("site"), Some(&"unix".to_string()));
+        assert_eq!(request.params.get("key"), Some(&"k3y".to_string()));
+        assert!(request.params.contains_key("fromdate"));
+
+        assert_eq!(
+            StackExchange::with_site("serverfault").site_url(),
+            "https://serverfault.com"
+        );
+        assert_eq!(
+            StackExchange::fromdate(TimeRange::Day, 1_000_000),
+            1_000_000 - 86_400
+        );
+    }
+
+    #[test]
+    fn test_stackexchange_response() {
+        let engine = stackexchange("name: superuser\nengine: stackexchange\nsite: superuser");
+        let response = EngineResponse::new(200, include_str!("fixtures/stackexchange_search.json"));
+        let results = engine.response(response).unwrap().results;
+
+        assert_eq!(results.len(), 2);
+        let first = &results[0];
+        assert_eq!(first.engine, "superuser");
+        assert_eq!(
+            first.url,
+            "https://superuser.com/questions/1510322/how-to-use-ssh-agent-with-tmux"
+        );
+        assert_eq!(first.title, "How to use ssh-agent with tmux & screen?");
+        assert_eq!(first.metadata.votes, Some(42));
+        assert_eq!(first.metadata.answer_count, Some(3));
+        assert!(first.metadata.accepted);
+        assert_eq!(first.metadata.tags, vec!["ssh", "tmux"]);
+        assert_eq!(
+            first.metadata.published_date,
+            "2019-12-16T10:38:20Z".parse().ok()
+        );
+
+        assert!(!results[1].metadata.accepted);
+        assert_eq!(results[1].url, "https://superuser.com/q/1790001");
+    }
+
+    #[test]
+    fn test_stackexchange_throttle() {
+        let engine = StackExchange::new();
+        let response = EngineResponse::new(
+            400,
+            r#"{"error_id": 502, "error_message": "too many requests from this IP", "error_name": "throttle_violation"}"#,
+        );
+        let error = engine.response(response).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<EngineFailure>().map(|f| &f.error),
+            Some(&EngineError::TooManyRequests)
+        );
+    }
+
+    #[test]
+    fn test_unescape_html() {
+        assert_eq!(
+            StackExchange::unescape_html("&amp;&lt;&gt;&quot;&#39;"),
+            "&<>\"'"
+        );
+    }
+}
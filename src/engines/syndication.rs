@@ -0,0 +1,382 @@
+//! Atom and RSS feed parsing
+//!
+//! Parses Atom, RSS 2.0 and RSS 1.0 (RDF) documents into a common [`Feed`]
+//! shape for engines built on syndication formats. Elements from other
+//! namespaces (e.g. arXiv's `arxiv:doi`) are kept as [`FeedExtension`]s.
+
+use super::dates;
+use anyhow::Result as AnyhowResult;
+use chrono::{DateTime, Utc};
+use roxmltree::{Document, Node};
+use scraper::Html;
+
+const ATOM_NS: &str = "http://www.w3.org/2005/Atom";
+const DC_NS: &str = "http://purl.org/dc/elements/1.1/";
+const CONTENT_NS: &str = "http://purl.org/rss/1.0/modules/content/";
+
+/// A parsed feed
+#[derive(Debug, Clone, Default)]
+pub struct Feed {
+    pub entries: Vec<FeedEntry>,
+    /// Feed-level extension elements (e.g. `opensearch:totalResults`)
+    pub extensions: Vec<FeedExtension>,
+}
+
+/// An Atom entry or RSS item
+#[derive(Debug, Clone, Default)]
+pub struct FeedEntry {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub links: Vec<FeedLink>,
+    pub summary: Option<String>,
+    pub content: Option<String>,
+    pub authors: Vec<String>,
+    pub categories: Vec<String>,
+    pub published: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+    pub extensions: Vec<FeedExtension>,
+}
+
+/// A link of an entry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedLink {
+    pub href: String,
+    /// Link relation, `alternate` when not given
+    pub rel: String,
+    pub title: Option<String>,
+    pub media_type: Option<String>,
+}
+
+/// An element from a namespace other than the feed's own
+#[derive(Debug, Clone, Default)]
+pub struct FeedExtension {
+    pub namespace: String,
+    pub name: String,
+    pub text: String,
+    pub attributes: Vec<(String, String)>,
+}
+
+impl Feed {
+    /// Text of the first feed-level extension element with this name
+    pub fn extension_text(&self, namespace: &str, name: &str) -> Option<&str> {
+        find_extension(&self.extensions, namespace, name).map(|e| e.text.as_str())
+    }
+}
+
+impl FeedEntry {
+    /// URL of the entry: its alternate link, or the id if that is a URL
+    pub fn link(&self) -> Option<&str> {
+        self.links
+            .iter()
+            .find(|l| l.rel == "alternate")
+            .map(|l| l.href.as_str())
+            .or_else(|| self.id.as_deref().filter(|id| id.starts_with("http")))
+    }
+
+    /// First link matching a predicate
+    pub fn find_link(&self, f: impl Fn(&FeedLink) -> bool) -> Option<&FeedLink> {
+        self.links.iter().find(|l| f(l))
+    }
+
+    /// First extension element with this name
+    pub fn extension(&self, namespace: &str, name: &str) -> Option<&FeedExtension> {
+        find_extension(&self.extensions, namespace, name)
+    }
+
+    /// Text of the first extension element with this name, if not empty
+    pub fn extension_text(&self, namespace: &str, name: &str) -> Option<&str> {
+        self.extension(namespace, name)
+            .map(|e| e.text.as_str())
+            .filter(|t| !t.is_empty())
+    }
+}
+
+impl FeedExtension {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn find_extension<'a>(
+    extensions: &'a [FeedExtension],
+    namespace: &str,
+    name: &str,
+) -> Option<&'a FeedExtension> {
+    extensions
+        .iter()
+        .find(|e| e.namespace == namespace && e.name == name)
+}
+
+/// Parse an Atom or RSS document
+pub fn parse_feed(xml: &str) -> AnyhowResult<Feed> {
+    let doc = Document::parse(xml).map_err(|e| anyhow::anyhow!("Invalid feed XML: {}", e))?;
+    let root = doc.root_element();
+
+    match root.tag_name().name() {
+        "feed" if root.tag_name().namespace() == Some(ATOM_NS) => Ok(parse_atom(root)),
+        "rss" | "RDF" => Ok(parse_rss(root)),
+        other => Err(anyhow::anyhow!("Not an Atom or RSS feed: <{}>", other)),
+    }
+}
+
+fn parse_atom(feed: Node) -> Feed {
+    let mut result = Feed::default();
+
+    for node in feed.children().filter(Node::is_element) {
+        match (node.tag_name().namespace(), node.tag_name().name()) {
+            (Some(ATOM_NS), "entry") => result.entries.push(parse_atom_entry(node)),
+            (Some(ATOM_NS), _) => {}
+            _ => result.extensions.push(extension(node)),
+        }
+    }
+
+    result
+}
+
+fn parse_atom_entry(entry: Node) -> FeedEntry {
+    let mut result = FeedEntry::default();
+
+    for node in entry.children().filter(Node::is_element) {
+        if node.tag_name().namespace() != Some(ATOM_NS) {
+            result.extensions.push(extension(node));
+            continue;
+        }
+
+        match node.tag_name().name() {
+            "id" => result.id = text(node),
+            "title" => result.title = text_construct(node),
+            "summary" => result.summary = text_construct(node),
+            "content" => result.content = text_construct(node),
+            "published" => result.published = text(node).as_deref().and_then(dates::parse),
+            "updated" => result.updated = text(node).as_deref().and_then(dates::parse),
+            "author" => result.authors.extend(child(node, "name").and_then(text)),
+            "category" => result
+                .categories
+                .extend(node.attribute("term").map(str::to_string)),
+            "link" => {
+                if let Some(href) = node.attribute("href") {
+                    result.links.push(FeedLink {
+                        href: href.to_string(),
+                        rel: node.attribute("rel").unwrap_or("alternate").to_string(),
+                        title: node.attribute("title").map(str::to_string),
+                        media_type: node.attribute("type").map(str::to_string),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    result
+}
+
+fn parse_rss(root: Node) -> Feed {
+    // RSS 2.0 nests items in the channel, RSS 1.0 puts them next to it
+    Feed {
+        entries: root
+            .descendants()
+            .filter(|n| n.has_tag_name("item"))
+            .map(parse_rss_item)
+            .collect(),
+        extensions: Vec::new(),
+    }
+}
+
+fn parse_rss_item(item: Node) -> FeedEntry {
+    let own_ns = item.tag_name().namespace();
+    let mut result = FeedEntry::default();
+
+    for node in item.children().filter(Node::is_element) {
+        let name = node.tag_name().name();
+        match node.tag_name().namespace() {
+            ns if ns == own_ns => match name {
+                "title" => result.title = text(node),
+                "link" => result.links.extend(text(node).map(|href| FeedLink {
+                    href,
+                    rel: "alternate".to_string(),
+                    ..Default::default()
+                })),
+                "guid" => result.id = text(node),
+                "description" => result.summary = text(node).map(|t| strip_html(&t)),
+                "pubDate" => result.published = text(node).as_deref().and_then(dates::parse),
+                "author" => result.authors.extend(text(node)),
+                "category" => result.categories.extend(text(node)),
+                "enclosure" => result
+                    .links
+                    .extend(node.attribute("url").map(|url| FeedLink {
+                        href: url.to_string(),
+                        rel: "enclosure".to_string(),
+                        title: None,
+                        media_type: node.attribute("type").map(str::to_string),
+                    })),
+                _ => {}
+            },
+            Some(DC_NS) if name == "creator" => result.authors.extend(text(node)),
+            Some(DC_NS) if name == "date" => {
+                result.published = text(node).as_deref().and_then(dates::parse)
+            }
+            Some(DC_NS) if name == "subject" => result.categories.extend(text(node)),
+            Some(CONTENT_NS) if name == "encoded" => {
+                result.content = text(node).map(|t| strip_html(&t))
+            }
+            _ => result.extensions.push(extension(node)),
+        }
+    }
+
+    // An RSS 1.0 item is identified by its rdf:about attribute
+    if result.id.is_none() {
+        result.id = item
+            .attributes()
+            .find(|a| a.name() == "about")
+            .map(|a| a.value().to_string());
+    }
+
+    result
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    let ns = node.tag_name().namespace();
+    node.children()
+        .find(|n| n.tag_name().name() == name && n.tag_name().namespace() == ns)
+}
+
+/// Text content of an element with whitespace collapsed, if not empty
+fn text(node: Node) -> Option<String> {
+    let text: String = node
+        .descendants()
+        .filter(Node::is_text)
+        .filter_map(|n| n.text())
+        .collect();
+    let text = collapse_whitespace(&text);
+    Some(text).filter(|t| !t.is_empty())
+}
+
+/// Text of an Atom text construct, whose markup may be escaped HTML
+fn text_construct(node: Node) -> Option<String> {
+    let text = text(node)?;
+    match node.attribute("type") {
+        Some("html") => Some(strip_html(&text)).filter(|t| !t.is_empty()),
+        _ => Some(text),
+    }
+}
+
+fn strip_html(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let text: String = fragment.root_element().text().collect();
+    collapse_whitespace(&text)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn extension(node: Node) -> FeedExtension {
+    FeedExtension {
+        namespace: node.tag_name().namespace().unwrap_or_default().to_string(),
+        name: node.tag_name().name().to_string(),
+        text: text(node).unwrap_or_default(),
+        attributes: node
+            .attributes()
+            .map(|a| (a.name().to_string(), a.value().to_string()))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_atom() {
+        let xml = r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:x="urn:example">
+  <title type="html">Release &lt;b&gt;notes&lt;/b&gt;</title>
+  <x:total>2</x:total>
+  <entry>
+    <id>urn:uuid:1</id>
+    <title type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml">Version <em>2.0</em></div></title>
+    <link rel="alternate" href="https://example.org/2.0"/>
+    <link rel="enclosure" type="application/zip" href="https://example.org/2.0.zip"/>
+    <summary><![CDATA[Faster & <smaller>]]></summary>
+    <author><name>Ada</name></author>
+    <author><name>Grace</name></author>
+    <category term="release"/>
+    <published>2024-03-01T10:00:00+01:00</published>
+    <x:build number="42">stable</x:build>
+  </entry>
+</feed>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.extension_text("urn:example", "total"), Some("2"));
+        assert_eq!(feed.entries.len(), 1);
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.title.as_deref(), Some("Version 2.0"));
+        assert_eq!(entry.link(), Some("https://example.org/2.0"));
+        assert_eq!(entry.summary.as_deref(), Some("Faster & <smaller>"));
+        assert_eq!(entry.authors, vec!["Ada", "Grace"]);
+        assert_eq!(entry.categories, vec!["release"]);
+        assert_eq!(
+            entry.published.map(|d| d.to_rfc3339()),
+            Some("2024-03-01T09:00:00+00:00".to_string())
+        );
+        let build = entry.extension("urn:example", "build").unwrap();
+        assert_eq!(build.text, "stable");
+        assert_eq!(build.attribute("number"), Some("42"));
+    }
+
+    #[test]
+    fn test_parse_rss() {
+        let xml = r#"<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>Engineering blog</title>
+    <item>
+      <title>Moving to Rust</title>
+      <link>https://blog.example.org/rust</link>
+      <guid isPermaLink="false">post-17</guid>
+      <description>&lt;p&gt;Why we &lt;em&gt;rewrote&lt;/em&gt; it.&lt;/p&gt;</description>
+      <dc:creator>Linus</dc:creator>
+      <category>rust</category>
+      <pubDate>Tue, 05 Mar 2024 08:30:00 GMT</pubDate>
+    </item>
+  </channel>
+</rss>"#;
+
+        let feed = parse_feed(xml).unwrap();
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.id.as_deref(), Some("post-17"));
+        assert_eq!(entry.link(), Some("https://blog.example.org/rust"));
+        assert_eq!(entry.summary.as_deref(), Some("Why we rewrote it."));
+        assert_eq!(entry.authors, vec!["Linus"]);
+        assert_eq!(
+            entry.published.map(|d| d.to_rfc3339()),
+            Some("2024-03-05T08:30:00+00:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_rdf() {
+        let xml = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel rdf:about="https://example.org/"><title>Old school</title></channel>
+  <item rdf:about="https://example.org/1">
+    <title>First</title>
+    <link>https://example.org/1</link>
+    <dc:date>2003-12-13T18:30:02Z</dc:date>
+  </item>
+</rdf:RDF>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.entries[0].id.as_deref(), Some("https://example.org/1"));
+        assert!(feed.entries[0].published.is_some());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_feed("<html><body>Not a feed</body></html>").is_err());
+        assert!(parse_feed("<feed><entry>").is_err());
+    }
+}
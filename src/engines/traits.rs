@@ -2,11 +2,12 @@
 
 use crate::config::EngineConfig;
 use crate::network::HttpClient;
-use crate::query::TimeRange;
-use crate::results::{Answer, InfoBox, Result, Suggestion};
+use crate::query::{OperatorKind, QueryOperator, TimeRange};
+use crate::results::{Answer, EngineError, InfoBox, Result, Suggestion};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Result of an engine search
 #[derive(Debug, Clone, Default)]
@@ -60,6 +61,9 @@ impl EngineResults {
 pub struct RequestParams {
     /// Search query string
     pub query: String,
+    /// Query as typed with its operators, for engines parsing it themselves
+    #[serde(default)]
+    pub full_query: String,
     /// Page number (1-indexed)
     pub pageno: u32,
     /// Language code
@@ -70,6 +74,9 @@ pub struct RequestParams {
     pub time_range: Option<TimeRange>,
     /// Category context
     pub category: String,
+    /// Number of instances the search was already forwarded through
+    #[serde(default)]
+    pub hops: u32,
     /// Engine-specific data
     #[serde(default)]
     pub engine_data: HashMap<String, serde_json::Value>,
@@ -78,13 +85,16 @@ pub struct RequestParams {
 impl RequestParams {
     /// Create new request parameters
     pub fn new(query: impl Into<String>) -> Self {
+        let query = query.into();
         Self {
-            query: query.into(),
+            full_query: query.clone(),
+            query,
             pageno: 1,
             lang: "en".to_string(),
             safesearch: 0,
             time_range: None,
             category: "general".to_string(),
+            hops: 0,
             engine_data: HashMap::new(),
         }
     }
@@ -192,6 +202,16 @@ pub struct EngineResponse {
 }
 
 impl EngineResponse {
+    /// Create a response from a status and body
+    pub fn new(status: u16, text: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: HashMap::new(),
+            text: text.into(),
+            url: String::new(),
+        }
+    }
+
     /// Parse response as JSON
     pub fn json<T: serde::de::DeserializeOwned>(&self) -> anyhow::Result<T> {
         Ok(serde_json::from_str(&self.text)?)
@@ -215,6 +235,63 @@ impl EngineResponse {
             || self.text.contains("unusual traffic")
             || self.text.contains("automated requests")
     }
+
+    /// Get a response header (names are matched case-insensitively)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Typed engine failure
+///
+/// Engines return this (wrapped in an `anyhow::Error`) when they know why a
+/// request failed. `suspend_for` overrides the configured suspension time,
+/// e.g. with the reset time of an API rate limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineFailure {
+    /// Kind of failure
+    pub error: EngineError,
+    /// How long the engine should be suspended
+    pub suspend_for: Option<Duration>,
+}
+
+impl EngineFailure {
+    pub fn new(error: EngineError) -> Self {
+        Self {
+            error,
+            suspend_for: None,
+        }
+    }
+
+    /// Suspend the engine for exactly `duration`
+    pub fn suspend_for(mut self, duration: Duration) -> Self {
+        self.suspend_for = Some(duration);
+        self
+    }
+}
+
+impl std::fmt::Display for EngineFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.suspend_for {
+            Some(duration) => write!(f, "{} (retry in {}s)", self.error, duration.as_secs()),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+impl std::error::Error for EngineFailure {}
+
+/// How an engine produces results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EngineType {
+    /// Sends HTTP requests to a search service
+    #[default]
+    Online,
+    /// Searches local data through [`Engine::search`]
+    Offline,
 }
 
 /// Main engine trait that all search engines must implement
@@ -243,11 +320,35 @@ pub trait Engine: Send + Sync {
         false
     }
 
+    /// Whether the time range is enforced on the results' published dates
+    ///
+    /// For APIs without a date filter: results published before the range,
+    /// or without a date, are dropped.
+    fn filters_time_range_locally(&self) -> bool {
+        false
+    }
+
     /// Whether this engine supports safe search
     fn supports_safesearch(&self) -> bool {
         false
     }
 
+    /// Hops after which the engine is skipped (`None` = never)
+    ///
+    /// For engines forwarding searches to other instances, so instances
+    /// federating with each other don't recurse.
+    fn max_hops(&self) -> Option<u32> {
+        None
+    }
+
+    /// Whether the engine answers this query
+    ///
+    /// For engines triggered by keywords, such as dictionary lookups, which
+    /// are skipped for every other search.
+    fn matches_query(&self, _query: &str) -> bool {
+        true
+    }
+
     /// Default weight for result scoring
     fn weight(&self) -> f64 {
         1.0
@@ -263,9 +364,81 @@ pub trait Engine: Send + Sync {
         10
     }
 
+    /// Languages the engine can restrict results to (empty = language-agnostic)
+    fn languages(&self) -> Vec<&str> {
+        vec![]
+    }
+
+    /// Search operators this engine understands natively
+    ///
+    /// Operators not listed here are enforced locally by filtering results.
+    fn supported_operators(&self) -> Vec<OperatorKind> {
+        vec![]
+    }
+
+    /// Whether a specific operator can be expressed in this engine's syntax
+    fn supports_operator(&self, operator: &QueryOperator) -> bool {
+        self.supported_operators().contains(&operator.kind())
+    }
+
+    /// Build the engine query from plain terms and natively supported operators
+    ///
+    /// Also returns the operators that could not be expressed after all,
+    /// which are then enforced locally.
+    fn format_query(
+        &self,
+        terms: &str,
+        operators: &[QueryOperator],
+    ) -> (String, Vec<QueryOperator>) {
+        let query = std::iter::once(terms.to_string())
+            .chain(operators.iter().map(|op| op.to_string()))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        (query, Vec::new())
+    }
+
+    /// Whether the engine answers over HTTP or from local data
+    fn engine_type(&self) -> EngineType {
+        EngineType::Online
+    }
+
+    /// Answer a search from local data
+    ///
+    /// Called instead of [`Engine::request`] for [`EngineType::Offline`] engines.
+    fn search(&self, _params: &RequestParams) -> anyhow::Result<EngineResults> {
+        Err(anyhow::anyhow!("{} is not an offline engine", self.name()))
+    }
+
+    /// How often [`Engine::refresh`] runs in the background (`None` = never)
+    fn refresh_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Requests to make before each refresh, e.g. the feeds to download
+    fn refresh_requests(&self) -> Vec<EngineRequest> {
+        vec![]
+    }
+
+    /// Update local data from the responses to [`Engine::refresh_requests`]
+    ///
+    /// Responses are passed in request order.
+    fn refresh(&self, _responses: Vec<anyhow::Result<EngineResponse>>) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     /// Build the HTTP request for a search
     fn request(&self, params: &RequestParams) -> anyhow::Result<EngineRequest>;
 
+    /// Build a follow-up request from a response
+    ///
+    /// For APIs that need more than one round trip, e.g. a search endpoint
+    /// returning ids and a second endpoint returning the records. The last
+    /// response (the one without a follow-up) is passed to [`Engine::response`].
+    fn next_request(&self, _response: &EngineResponse) -> anyhow::Result<Option<EngineRequest>> {
+        Ok(None)
+    }
+
     /// Parse the HTTP response into results
     fn response(&self, response: EngineResponse) -> anyhow::Result<EngineResults>;
 
@@ -281,7 +454,7 @@ pub trait Engine: Send + Sync {
 }
 
 /// Engine metadata
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct EngineAbout {
     /// Website URL
     pub website: Option<String>,
@@ -0,0 +1,229 @@
+//! Wikimedia Commons search engine implementation
+//!
+//! Searches the File namespace of Wikimedia Commons through the MediaWiki
+//! API, with image info for the thumbnails, sizes and licenses.
+
+use super::media;
+use super::traits::*;
+use crate::results::{Result, ResultType};
+use anyhow::Result as AnyhowResult;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// `action=query` response
+#[derive(Debug, Deserialize)]
+struct QueryResponse {
+    query: Option<Query>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Query {
+    #[serde(default)]
+    pages: HashMap<String, Page>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    title: String,
+    /// Rank in the search results
+    index: Option<u32>,
+    #[serde(default)]
+    imageinfo: Vec<ImageInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageInfo {
+    url: String,
+    descriptionurl: String,
+    thumburl: Option<String>,
+    width: Option<u64>,
+    height: Option<u64>,
+    size: Option<u64>,
+    mime: Option<String>,
+    #[serde(default)]
+    extmetadata: HashMap<String, ExtMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtMetadata {
+    value: serde_json::Value,
+}
+
+/// Wikimedia Commons image search engine
+pub struct WikiCommons {
+    api_url: String,
+}
+
+impl WikiCommons {
+    pub fn new() -> Self {
+        Self {
+            api_url: "https://commons.wikimedia.org/w/api.php".to_string(),
+        }
+    }
+
+    /// Plain text of an `extmetadata` field, which may hold HTML
+    fn ext_text(info: &ImageInfo, key: &str) -> Option<String> {
+        let value = info.extmetadata.get(key)?.value.as_str()?;
+        let fragment = scraper::Html::parse_fragment(value);
+        let text = fragment
+            .root_element()
+            .text()
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(text).filter(|t| !t.is_empty())
+    }
+
+    fn image_result(&self, page: Page, position: u32) -> Option<Result> {
+        let info = page.imageinfo.into_iter().next()?;
+        let title = page
+            .title
+            .strip_prefix("File:")
+            .unwrap_or(&page.title)
+            .to_string();
+
+        let mut result = Result::new(info.descriptionurl.clone(), title, self.name().to_string())
+            .with_position(position);
+        result.result_type = ResultType::Image;
+        if let Some(description) = Self::ext_text(&info, "ImageDescription") {
+            result = result.with_content(description);
+        }
+
+        let meta = &mut result.metadata;
+        meta.template = Some("images.html".to_string());
+        meta.thumbnail = info.thumburl.clone();
+        meta.resolution = info
+            .width
+            .zip(info.height)
+            .map(|(w, h)| media::format_resolution(w, h));
+        meta.file_size = info.size.map(media::format_file_size);
+        meta.file_type = info.mime.clone();
+        meta.author = Self::ext_text(&info, "Artist");
+        meta.license = Self::ext_text(&info, "LicenseShortName");
+        meta.img_src = Some(info.url);
+        Some(result)
+    }
+}
+
+impl Default for WikiCommons {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for WikiCommons {
+    fn name(&self) -> &str {
+        "wikicommons"
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://commons.wikimedia.org")
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["images"]
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let per_page = self.results_per_page();
+        Ok(EngineRequest::get(&self.api_url)
+            .param("action", "query")
+            .param("format", "json")
+            .param("generator", "search")
+            // Images and drawings only, no audio, video or documents
+            .param(
+                "gsrsearch",
+                format!("{} filetype:bitmap|drawing", params.query),
+            )
+            .param("gsrnamespace", "6")
+            .param("gsrlimit", per_page.to_string())
+            .param(
+                "gsroffset",
+                ((params.pageno.max(1) - 1) * per_page).to_string(),
+            )
+            .param("prop", "imageinfo")
+            .param("iiprop", "url|size|mime|extmetadata")
+            .param(
+                "iiextmetadatafilter",
+                "ImageDescription|Artist|LicenseShortName",
+            )
+            .param("iiurlwidth", "300"))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: QueryResponse = response.json()?;
+        let mut pages: Vec<Page> = data
+            .query
+            .map(|q| q.pages.into_values().collect())
+            .unwrap_or_default();
+        // Pages are keyed by id; `index` holds the search rank
+        pages.sort_by_key(|p| p.index.unwrap_or(u32::MAX));
+
+        let results = pages
+            .into_iter()
+            .filter_map(|page| {
+                let position = page.index.unwrap_or(0);
+                self.image_result(page, position)
+            })
+            .collect();
+
+        Ok(EngineResults::with_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wikicommons_request() {
+        let engine = WikiCommons::new();
+        let mut params = RequestParams::new("ferris crab");
+        params.pageno = 2;
+        let request = engine.request(&params).unwrap();
+
+        assert_eq!(request.url, "https://commons.wikimedia.org/w/api.php");
+        assert_eq!(
+            request.params.get("gsrsearch"),
+            Some(&"ferris crab filetype:bitmap|drawing".to_string())
+        );
+        assert_eq!(request.params.get("gsrnamespace"), Some(&"6".to_string()));
+        assert_eq!(request.params.get("gsroffset"), Some(&"10".to_string()));
+    }
+
+    #[test]
+    fn test_wikicommons_response() {
+        let engine = WikiCommons::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/wikicommons_search.json"));
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.results.len(), 2);
+
+        // Ordered by search rank, not page id
+        let image = &results.results[0];
+        assert_eq!(image.title, "Rustacean-orig-noshadow.svg");
+        assert_eq!(
+            image.url,
+            "https://commons.wikimedia.org/wiki/File:Rustacean-orig-noshadow.svg"
+        );
+        assert_eq!(image.result_type, ResultType::Image);
+        assert_eq!(
+            image.metadata.img_src.as_deref(),
+            Some("https://upload.wikimedia.org/wikipedia/commons/2/20/Rustacean-orig-noshadow.svg")
+        );
+        assert_eq!(image.metadata.resolution.as_deref(), Some("1200 x 800"));
+        assert_eq!(image.metadata.file_size.as_deref(), Some("8.3 KB"));
+        assert_eq!(image.metadata.author.as_deref(), Some("Karen Rustad Tölva"));
+        assert_eq!(image.metadata.license.as_deref(), Some("CC0"));
+        assert_eq!(image.content.as_deref(), Some("Ferris, the Rust mascot"));
+
+        assert_eq!(results.results[1].title, "Ferris crab photo.jpg");
+    }
+}
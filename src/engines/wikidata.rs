@@ -0,0 +1,593 @@
+//! Wikidata infobox engine implementation
+//!
+//! Resolves the query to a Wikidata entity through the Wikipedia article of
+//! the same title and turns its key properties into an infobox. The infobox
+//! id is the Wikipedia article URL, so it merges with the Wikipedia infobox.
+
+use super::traits::*;
+use crate::results::InfoBox;
+use anyhow::Result as AnyhowResult;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// How a Wikidata property is rendered
+#[derive(Debug, Clone, Copy)]
+enum PropertyKind {
+    /// Point in time, shown as a date
+    Time,
+    /// Amount with an optional unit
+    Quantity,
+    /// Plain string value
+    Text,
+    /// Globe coordinate, shown with a map link
+    Coordinates,
+    /// Full URL, added to the infobox links
+    Url,
+    /// Identifier turned into a link (`$1` is replaced by the value)
+    Link(&'static str),
+}
+
+/// Properties shown in the infobox, with English labels for properties
+/// Wikidata has no label for in the search language
+const PROPERTIES: &[(&str, PropertyKind, &str)] = &[
+    ("P571", PropertyKind::Time, "Inception"),
+    ("P569", PropertyKind::Time, "Born"),
+    ("P570", PropertyKind::Time, "Died"),
+    ("P577", PropertyKind::Time, "Published"),
+    ("P1082", PropertyKind::Quantity, "Population"),
+    ("P2046", PropertyKind::Quantity, "Area"),
+    ("P2044", PropertyKind::Quantity, "Elevation"),
+    ("P348", PropertyKind::Text, "Latest version"),
+    ("P212", PropertyKind::Text, "ISBN"),
+    ("P625", PropertyKind::Coordinates, "Coordinates"),
+    ("P856", PropertyKind::Url, "Official website"),
+    ("P1324", PropertyKind::Url, "Source code"),
+    (
+        "P2037",
+        PropertyKind::Link("https://github.com/$1"),
+        "GitHub",
+    ),
+    (
+        "P2002",
+        PropertyKind::Link("https://twitter.com/$1"),
+        "Twitter",
+    ),
+    (
+        "P2013",
+        PropertyKind::Link("https://www.facebook.com/$1"),
+        "Facebook",
+    ),
+    (
+        "P2003",
+        PropertyKind::Link("https://www.instagram.com/$1"),
+        "Instagram",
+    ),
+    (
+        "P2397",
+        PropertyKind::Link("https://www.youtube.com/channel/$1"),
+        "YouTube",
+    ),
+    ("P496", PropertyKind::Link("https://orcid.org/$1"), "ORCID"),
+    ("P356", PropertyKind::Link("https://doi.org/$1"), "DOI"),
+    (
+        "P214",
+        PropertyKind::Link("https://viaf.org/viaf/$1"),
+        "VIAF",
+    ),
+];
+
+/// Image property (Commons file name)
+const IMAGE_PROPERTY: &str = "P18";
+
+/// Unit symbols for common quantity units
+const UNITS: &[(&str, &str)] = &[
+    ("Q712226", "km²"),
+    ("Q25343", "m²"),
+    ("Q11573", "m"),
+    ("Q828224", "km"),
+    ("Q11570", "kg"),
+];
+
+/// Wikidata infobox engine
+pub struct Wikidata {
+    api_url: String,
+    default_lang: String,
+}
+
+impl Wikidata {
+    pub fn new() -> Self {
+        Self {
+            api_url: "https://www.wikidata.org/w/api.php".to_string(),
+            default_lang: "en".to_string(),
+        }
+    }
+
+    /// Get the base language code for a search language
+    fn base_lang<'a>(&'a self, lang: &'a str) -> &'a str {
+        if lang == "all" || lang.is_empty() {
+            &self.default_lang
+        } else {
+            lang.split('-').next().unwrap_or(&self.default_lang)
+        }
+    }
+
+    /// Pick the best statement value of a property
+    ///
+    /// Preferred statements win over normal ones; deprecated statements and
+    /// "no value"/"unknown value" snaks are ignored.
+    fn claim_value<'a>(entity: &'a Value, property: &str) -> Option<&'a Value> {
+        let statements = entity.get("claims")?.get(property)?.as_array()?;
+        let has_rank = |s: &Value, rank: &str| {
+            s.get("rank").and_then(|r| r.as_str()).unwrap_or("normal") == rank
+        };
+
+        let mut valid = statements
+            .iter()
+            .filter(|s| !has_rank(s, "deprecated"))
+            .filter(|s| s["mainsnak"]["snaktype"].as_str() == Some("value"));
+
+        valid
+            .clone()
+            .find(|s| has_rank(s, "preferred"))
+            .or_else(|| valid.next())
+            .and_then(|s| s["mainsnak"].get("datavalue"))
+            .and_then(|d| d.get("value"))
+    }
+
+    /// Format a Wikidata time value according to its precision
+    fn format_time(value: &Value) -> Option<String> {
+        let time = value.get("time")?.as_str()?;
+        let precision = value
+            .get("precision")
+            .and_then(|p| p.as_u64())
+            .unwrap_or(11);
+
+        let (sign, rest) = time.split_at(1);
+        let date = rest.split('T').next()?;
+        let mut parts = date.splitn(3, '-');
+        let year: i64 = parts.next()?.parse().ok()?;
+        let month = parts.next().unwrap_or("00");
+        let day = parts.next().unwrap_or("00");
+
+        let year = if sign == "-" {
+            format!("{} BC", year)
+        } else {
+            year.to_string()
+        };
+
+        Some(match precision {
+            11.. if month != "00" && day != "00" => format!("{}-{}-{}", year, month, day),
+            10.. if month != "00" => format!("{}-{}", year, month),
+            _ => year,
+        })
+    }
+
+    /// Format a Wikidata quantity with localized digit grouping and unit
+    fn format_quantity(value: &Value, lang: &str) -> Option<String> {
+        let amount = value.get("amount")?.as_str()?.trim_start_matches('+');
+        let (sign, amount) = match amount.strip_prefix('-') {
+            Some(a) => ("-", a),
+            None => ("", amount),
+        };
+        let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+
+        let (group_sep, decimal_sep) = match lang {
+            "de" | "nl" | "it" | "es" | "pt" => ('.', ','),
+            "fr" | "pl" | "ru" => ('\u{202f}', ','),
+            _ => (',', '.'),
+        };
+
+        let mut grouped = String::new();
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.push(group_sep);
+            }
+            grouped.push(c);
+        }
+
+        let mut formatted = format!("{}{}", sign, grouped);
+        if !fraction.is_empty() {
+            formatted.push(decimal_sep);
+            formatted.push_str(fraction);
+        }
+
+        let unit = value
+            .get("unit")
+            .and_then(|u| u.as_str())
+            .and_then(|u| u.rsplit('/').next())
+            .and_then(|id| UNITS.iter().find(|(q, _)| *q == id))
+            .map(|(_, symbol)| *symbol);
+        if let Some(unit) = unit {
+            formatted.push(' ');
+            formatted.push_str(unit);
+        }
+
+        Some(formatted)
+    }
+
+    /// Language of an entity's labels; only the requested one is returned
+    fn entity_lang(entity: &Value) -> Option<&str> {
+        entity
+            .get("labels")?
+            .as_object()?
+            .keys()
+            .next()
+            .map(|l| l.as_str())
+    }
+
+    /// Build the infobox for an entity
+    ///
+    /// `labels` holds the property labels in the search language.
+    fn entity_infobox(
+        &self,
+        entity: &Value,
+        lang: &str,
+        labels: &HashMap<&str, &str>,
+    ) -> Option<InfoBox> {
+        let id = entity.get("id")?.as_str()?;
+        let title = entity
+            .get("labels")
+            .and_then(|l| l.get(lang))
+            .and_then(|l| l.get("value"))
+            .and_then(|v| v.as_str())?;
+        let description = entity
+            .get("descriptions")
+            .and_then(|d| d.get(lang))
+            .and_then(|d| d.get("value"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let wikidata_url = format!("https://www.wikidata.org/wiki/{}", id);
+        let wikipedia_url = entity
+            .get("sitelinks")
+            .and_then(|s| s.get(format!("{}wiki", lang)))
+            .and_then(|s| s.get("url"))
+            .and_then(|u| u.as_str())
+            .map(|u| u.to_string());
+
+        let mut attributes = Vec::new();
+        let mut urls = Vec::new();
+
+        for (property, kind, fallback) in PROPERTIES {
+            let Some(value) = Self::claim_value(entity, property) else {
+                continue;
+            };
+            let label = labels.get(property).unwrap_or(fallback).to_string();
+
+            match kind {
+                PropertyKind::Time => {
+                    if let Some(date) = Self::format_time(value) {
+                        attributes.push((label, date));
+                    }
+                }
+                PropertyKind::Quantity => {
+                    if let Some(amount) = Self::format_quantity(value, lang) {
+                        attributes.push((label, amount));
+                    }
+                }
+                PropertyKind::Text => {
+                    if let Some(text) = value.as_str() {
+                        attributes.push((label, text.to_string()));
+                    }
+                }
+                PropertyKind::Coordinates => {
+                    let lat = value.get("latitude").and_then(|v| v.as_f64());
+                    let lon = value.get("longitude").and_then(|v| v.as_f64());
+                    if let (Some(lat), Some(lon)) = (lat, lon) {
+                        attributes.push((label, format!("{:.4}, {:.4}", lat, lon)));
+                        urls.push((
+                            "OpenStreetMap".to_string(),
+                            format!(
+                                "https://www.openstreetmap.org/?mlat={}&mlon={}&zoom=12",
+                                lat, lon
+                            ),
+                        ));
+                    }
+                }
+                PropertyKind::Url => {
+                    if let Some(url) = value.as_str() {
+                        urls.push((label, url.to_string()));
+                    }
+                }
+                PropertyKind::Link(template) => {
+                    if let Some(ident) = value.as_str() {
+                        urls.push((label, template.replace("$1", ident)));
+                    }
+                }
+            }
+        }
+
+        if let Some(ref url) = wikipedia_url {
+            urls.push(("Wikipedia".to_string(), url.clone()));
+        }
+        urls.push(("Wikidata".to_string(), wikidata_url.clone()));
+
+        let img_src = Self::claim_value(entity, IMAGE_PROPERTY)
+            .and_then(|v| v.as_str())
+            .map(|file| {
+                format!(
+                    "https://commons.wikimedia.org/wiki/Special:FilePath/{}?width=300",
+                    urlencoding::encode(&file.replace(' ', "_"))
+                )
+            });
+
+        let url = wikipedia_url.unwrap_or(wikidata_url);
+        Some(InfoBox {
+            id: url.clone(),
+            title: title.to_string(),
+            content: description,
+            img_src,
+            url: Some(url),
+            engine: self.name().to_string(),
+            attributes,
+            urls,
+        })
+    }
+}
+
+impl Default for Wikidata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Wikidata {
+    fn name(&self) -> &str {
+        "wikidata"
+    }
+
+    fn languages(&self) -> Vec<&str> {
+        crate::locales::language_codes()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://www.wikidata.org")
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["general"]
+    }
+
+    fn supports_paging(&self) -> bool {
+        false
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let lang = self.base_lang(&params.lang);
+
+        let mut query_params = HashMap::new();
+        query_params.insert("action".to_string(), "wbgetentities".to_string());
+        query_params.insert("format".to_string(), "json".to_string());
+        query_params.insert("sites".to_string(), format!("{}wiki", lang));
+        query_params.insert("titles".to_string(), params.query.clone());
+        query_params.insert("normalize".to_string(), "1".to_string());
+        query_params.insert(
+            "props".to_string(),
+            "labels|descriptions|claims|sitelinks/urls".to_string(),
+        );
+        query_params.insert("languages".to_string(), lang.to_string());
+        query_params.insert("languagefallback".to_string(), "1".to_string());
+        query_params.insert("sitefilter".to_string(), format!("{}wiki", lang));
+
+        let mut request = EngineRequest::get(&self.api_url);
+        request.params = query_params;
+
+        Ok(request)
+    }
+
+    fn next_request(&self, response: &EngineResponse) -> AnyhowResult<Option<EngineRequest>> {
+        if !response.is_success() {
+            return Ok(None);
+        }
+
+        // The title lookup is followed by a lookup of the item together with
+        // its properties, for their labels in the search language
+        let json: Value = serde_json::from_str(&response.text)?;
+        let entities: Vec<&Value> = json
+            .get("entities")
+            .and_then(|e| e.as_object())
+            .into_iter()
+            .flat_map(|e| e.values())
+            .filter(|e| e.get("missing").is_none())
+            .collect();
+        if entities
+            .iter()
+            .any(|e| e.get("type").and_then(|t| t.as_str()) == Some("property"))
+        {
+            return Ok(None);
+        }
+        let Some(entity) = entities.first() else {
+            return Ok(None);
+        };
+        let Some(id) = entity.get("id").and_then(|i| i.as_str()) else {
+            return Ok(None);
+        };
+        let lang = Self::entity_lang(entity).unwrap_or(&self.default_lang);
+
+        let properties: Vec<&str> = PROPERTIES
+            .iter()
+            .map(|(property, _, _)| *property)
+            .filter(|property| Self::claim_value(entity, property).is_some())
+            .collect();
+        if properties.is_empty() {
+            return Ok(None);
+        }
+
+        let ids = std::iter::once(id)
+            .chain(properties)
+            .collect::<Vec<_>>()
+            .join("|");
+        Ok(Some(
+            EngineRequest::get(&self.api_url)
+                .param("action", "wbgetentities")
+                .param("format", "json")
+                .param("ids", ids)
+                .param("props", "labels|descriptions|claims|sitelinks/urls")
+                .param("languages", lang)
+                .param("languagefallback", "1")
+                .param("sitefilter", format!("{}wiki", lang)),
+        ))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let json: Value = serde_json::from_str(&response.text)?;
+        let mut engine_results = EngineResults::new();
+
+        let entities: Vec<&Value> = json
+            .get("entities")
+            .and_then(|e| e.as_object())
+            .into_iter()
+            .flat_map(|e| e.values())
+            .filter(|e| e.get("missing").is_none())
+            .collect();
+        let is_property = |e: &Value| e.get("type").and_then(|t| t.as_str()) == Some("property");
+
+        let labels: HashMap<&str, &str> = entities
+            .iter()
+            .filter(|e| is_property(e))
+            .filter_map(|e| {
+                let id = e.get("id")?.as_str()?;
+                let label = e
+                    .get("labels")?
+                    .as_object()?
+                    .values()
+                    .next()?
+                    .get("value")?
+                    .as_str()?;
+                Some((id, label))
+            })
+            .collect();
+
+        for entity in entities.into_iter().filter(|e| !is_property(e)) {
+            let lang = Self::entity_lang(entity).unwrap_or(&self.default_lang);
+            if let Some(infobox) = self.entity_infobox(entity, lang, &labels) {
+                engine_results.infoboxes.push(infobox);
+            }
+        }
+
+        Ok(engine_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wikidata_request() {
+        let wikidata = Wikidata::new();
+        let mut params = RequestParams::new("berlin");
+        params.lang = "de-DE".to_string();
+        let request = wikidata.request(&params).unwrap();
+
+        assert!(request.url.contains("wikidata.org"));
+        assert_eq!(request.params.get("sites"), Some(&"dewiki".to_string()));
+        assert_eq!(request.params.get("titles"), Some(&"berlin".to_string()));
+    }
+
+    #[test]
+    fn test_wikidata_infobox() {
+        let wikidata = Wikidata::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/wikidata_berlin.json"));
+        let results = wikidata.response(response).unwrap();
+
+        assert_eq!(results.infoboxes.len(), 1);
+        let infobox = &results.infoboxes[0];
+        assert_eq!(infobox.id, "https://en.wikipedia.org/wiki/Berlin");
+        assert_eq!(infobox.title, "Berlin");
+        assert_eq!(
+            infobox.content.as_deref(),
+            Some("capital and largest city of Germany")
+        );
+        assert!(infobox
+            .img_src
+            .as_deref()
+            .unwrap()
+            .ends_with("Special:FilePath/Berlin_skyline.jpg?width=300"));
+
+        let attr = |name: &str| {
+            infobox
+                .attributes
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(attr("Inception"), Some("1237"));
+        // The preferred statement wins over older figures
+        assert_eq!(attr("Population"), Some("3,755,251"));
+        assert_eq!(attr("Area"), Some("891.12 km²"));
+        assert_eq!(attr("Coordinates"), Some("52.5167, 13.3833"));
+
+        let links: Vec<_> = infobox.urls.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(
+            links,
+            vec![
+                "OpenStreetMap",
+                "Official website",
+                "Twitter",
+                "Wikipedia",
+                "Wikidata"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wikidata_localized() {
+        let wikidata = Wikidata::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/wikidata_berlin_de.json"));
+        let results = wikidata.response(response).unwrap();
+
+        let infobox = &results.infoboxes[0];
+        assert_eq!(infobox.id, "https://de.wikipedia.org/wiki/Berlin");
+        assert_eq!(infobox.content.as_deref(), Some("Hauptstadt Deutschlands"));
+        // Labels come from the property entities of the follow-up lookup
+        assert!(infobox
+            .attributes
+            .contains(&("Einwohnerzahl".to_string(), "3.755.251".to_string())));
+        assert!(infobox
+            .attributes
+            .contains(&("Fläche".to_string(), "891,12 km²".to_string())));
+    }
+
+    #[test]
+    fn test_wikidata_property_labels_request() {
+        let wikidata = Wikidata::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/wikidata_berlin_de.json"));
+        // Responses with property labels are final
+        assert!(wikidata.next_request(&response).unwrap().is_none());
+
+        let response = EngineResponse::new(200, include_str!("fixtures/wikidata_berlin.json"));
+        let request = wikidata.next_request(&response).unwrap().unwrap();
+        let ids = &request.params["ids"];
+        assert!(ids.starts_with("Q64|"));
+        assert!(ids.split('|').any(|id| id == "P1082"));
+        assert_eq!(request.params.get("languages"), Some(&"en".to_string()));
+    }
+
+    #[test]
+    fn test_wikidata_missing() {
+        let wikidata = Wikidata::new();
+        let response = EngineResponse::new(
+            200,
+            r#"{"entities":{"-1":{"site":"enwiki","title":"Xyzzy","missing":""}},"success":1}"#,
+        );
+        assert!(wikidata.response(response).unwrap().infoboxes.is_empty());
+    }
+
+    #[test]
+    fn test_format_time() {
+        let time = |t: &str, p: u64| {
+            Wikidata::format_time(&serde_json::json!({ "time": t, "precision": p }))
+        };
+        assert_eq!(time("+1879-03-14T00:00:00Z", 11).unwrap(), "1879-03-14");
+        assert_eq!(time("+1879-03-00T00:00:00Z", 10).unwrap(), "1879-03");
+        assert_eq!(time("-0044-03-15T00:00:00Z", 9).unwrap(), "44 BC");
+    }
+}
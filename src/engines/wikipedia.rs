@@ -41,6 +41,10 @@ impl Engine for Wikipedia {
         "wikipedia"
     }
 
+    fn languages(&self) -> Vec<&str> {
+        crate::locales::language_codes()
+    }
+
     fn about(&self) -> EngineAbout {
         EngineAbout::new()
             .website("https://www.wikipedia.org")
@@ -134,6 +138,21 @@ impl Engine for Wikipedia {
                     .and_then(|s| s.as_str())
                     .map(|s| s.to_string());
 
+                // The best match on the first page also becomes an infobox,
+                // keyed by article URL so Wikidata can merge into it
+                if page.get("index").and_then(|i| i.as_i64()) == Some(1) {
+                    engine_results.infoboxes.push(InfoBox {
+                        id: url.to_string(),
+                        title: title.to_string(),
+                        content: extract.clone(),
+                        img_src: thumbnail.clone(),
+                        url: Some(url.to_string()),
+                        engine: self.name().to_string(),
+                        attributes: vec![],
+                        urls: vec![("Wikipedia".to_string(), url.to_string())],
+                    });
+                }
+
                 let mut result =
                     Result::new(url.to_string(), title.to_string(), self.name().to_string());
 
@@ -202,6 +221,26 @@ mod tests {
         assert!(request.params.contains_key("gsrsearch"));
     }
 
+    #[test]
+    fn test_wikipedia_infobox() {
+        let wiki = Wikipedia::new();
+        let response = EngineResponse::new(
+            200,
+            r#"{"query":{"pages":{
+                "9":{"pageid":9,"title":"Berlin Wall","index":2,"fullurl":"https://en.wikipedia.org/wiki/Berlin_Wall","extract":"A wall."},
+                "3354":{"pageid":3354,"title":"Berlin","index":1,"fullurl":"https://en.wikipedia.org/wiki/Berlin","extract":"Berlin is the capital of Germany."}
+            }}}"#,
+        );
+        let results = wiki.response(response).unwrap();
+
+        assert_eq!(results.results[0].title, "Berlin");
+        assert_eq!(results.infoboxes.len(), 1);
+        assert_eq!(
+            results.infoboxes[0].id,
+            "https://en.wikipedia.org/wiki/Berlin"
+        );
+    }
+
     #[test]
     fn test_language_url() {
         let wiki = Wikipedia::new();
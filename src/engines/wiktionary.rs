@@ -0,0 +1,484 @@
+//! Wiktionary dictionary engine implementation
+//!
+//! Answers `define <word>` style queries with an infobox built from the
+//! English Wiktionary entry: pronunciation, definitions by part of speech
+//! and etymology. Other searches don't reach the engine.
+
+use super::traits::*;
+use crate::config::EngineConfig;
+use crate::results::InfoBox;
+use anyhow::Result as AnyhowResult;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::Deserialize;
+
+/// Queries asking for a definition; the first group is the word
+static TRIGGERS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        r"(?i)^(?:define|definition of|meaning of)\s+(.+)$",
+        r"(?i)^(.+?)\s+(?:definition|meaning)$",
+        r"(?i)^what does\s+(.+?)\s+mean\??$",
+    ]
+    .iter()
+    .map(|r| Regex::new(r).unwrap())
+    .collect()
+});
+
+/// Longest phrase looked up, in words
+const MAX_WORDS: usize = 3;
+
+/// Definitions shown per part of speech
+const MAX_DEFINITIONS: usize = 3;
+
+/// Pronunciations shown
+const MAX_PRONUNCIATIONS: usize = 3;
+
+/// Section headings holding definitions
+const PARTS_OF_SPEECH: &[&str] = &[
+    "Noun",
+    "Proper noun",
+    "Verb",
+    "Adjective",
+    "Adverb",
+    "Pronoun",
+    "Preposition",
+    "Conjunction",
+    "Interjection",
+    "Determiner",
+    "Article",
+    "Numeral",
+    "Particle",
+    "Prefix",
+    "Suffix",
+    "Phrase",
+    "Prepositional phrase",
+    "Proverb",
+    "Idiom",
+    "Contraction",
+    "Symbol",
+    "Letter",
+];
+
+/// `action=parse` response
+#[derive(Debug, Deserialize)]
+struct ParseResponse {
+    parse: Option<Parse>,
+    error: Option<ApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Parse {
+    title: String,
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    code: String,
+    info: Option<String>,
+}
+
+/// Entry of one language on a Wiktionary page
+#[derive(Debug, Default)]
+struct Entry {
+    pronunciations: Vec<String>,
+    /// Part of speech and its definitions, in page order
+    definitions: Vec<(String, Vec<String>)>,
+    etymology: Option<String>,
+}
+
+/// Wiktionary dictionary engine
+pub struct Wiktionary {
+    name: String,
+    base_url: String,
+    /// Language section of the entries, as titled on the page
+    language: String,
+}
+
+impl Wiktionary {
+    pub fn new() -> Self {
+        Self {
+            name: "wiktionary".to_string(),
+            base_url: "https://en.wiktionary.org".to_string(),
+            language: "English".to_string(),
+        }
+    }
+
+    /// Word to look up, if the query asks for a definition
+    fn word(query: &str) -> Option<String> {
+        let query = query.trim();
+        let word = TRIGGERS
+            .iter()
+            .find_map(|r| r.captures(query))?
+            .get(1)?
+            .as_str()
+            .trim_matches(|c: char| c == '"' || c == '\'' || c.is_whitespace());
+        let words = word.split_whitespace().count();
+        (1..=MAX_WORDS).contains(&words).then(|| word.to_string())
+    }
+
+    fn parse_request(&self, page: &str) -> EngineRequest {
+        EngineRequest::get(format!("{}/w/api.php", self.base_url))
+            .param("action", "parse")
+            .param("page", page)
+            .param("prop", "text")
+            .param("redirects", "1")
+            .param("disableeditsection", "1")
+            .param("format", "json")
+            .param("formatversion", "2")
+    }
+
+    /// Level and title of a section heading, wrapped in a `div.mw-heading`
+    /// by newer MediaWiki versions
+    fn heading(element: ElementRef) -> Option<(u8, String)> {
+        let heading = if element.value().name() == "div"
+            && element.value().classes().any(|c| c == "mw-heading")
+        {
+            element.children().filter_map(ElementRef::wrap).next()?
+        } else {
+            element
+        };
+        let level = heading.value().name().strip_prefix('h')?.parse().ok()?;
+        Some((level, Self::text(heading)))
+    }
+
+    fn text(element: ElementRef) -> String {
+        element
+            .text()
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Text of a definition without its examples, quotations, subsenses
+    /// and reference marks
+    fn definition_text(element: ElementRef) -> String {
+        fn collect(element: ElementRef, text: &mut String) {
+            for child in element.children() {
+                match child.value() {
+                    Node::Text(t) => text.push_str(t),
+                    Node::Element(e)
+                        if !matches!(e.name(), "ul" | "ol" | "dl" | "style" | "sup") =>
+                    {
+                        if let Some(child) = ElementRef::wrap(child) {
+                            collect(child, text);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut text = String::new();
+        collect(element, &mut text);
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn parse_entry(&self, html: &str) -> Entry {
+        let fragment = Html::parse_fragment(html);
+        let output_selector = Selector::parse(".mw-parser-output").unwrap();
+        let ipa_selector = Selector::parse("span.IPA").unwrap();
+
+        let mut entry = Entry::default();
+        let Some(output) = fragment.select(&output_selector).next() else {
+            return entry;
+        };
+
+        let mut in_language = false;
+        let mut section = String::new();
+        for element in output.children().filter_map(ElementRef::wrap) {
+            if let Some((level, title)) = Self::heading(element) {
+                if level == 2 {
+                    // Languages come one after the other; the rest is unused
+                    if in_language {
+                        break;
+                    }
+                    in_language = title == self.language;
+                }
+                section = title;
+                continue;
+            }
+            if !in_language {
+                continue;
+            }
+
+            if section.starts_with("Pronunciation") {
+                for ipa in element.select(&ipa_selector).map(Self::text) {
+                    if entry.pronunciations.len() < MAX_PRONUNCIATIONS
+                        && !entry.pronunciations.contains(&ipa)
+                    {
+                        entry.pronunciations.push(ipa);
+                    }
+                }
+            } else if section.starts_with("Etymology") {
+                if entry.etymology.is_none() && element.value().name() == "p" {
+                    entry.etymology =
+                        Some(Self::definition_text(element)).filter(|t| !t.is_empty());
+                }
+            } else if PARTS_OF_SPEECH.contains(&section.as_str()) && element.value().name() == "ol"
+            {
+                let senses = element
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .filter_map(|li| {
+                        if li.value().name() != "li" {
+                            return None;
+                        }
+                        Some(Self::definition_text(li)).filter(|t| !t.is_empty())
+                    });
+
+                // Parts of speech repeat under each etymology
+                let index = match entry
+                    .definitions
+                    .iter()
+                    .position(|(pos, _)| *pos == section)
+                {
+                    Some(index) => index,
+                    None => {
+                        entry.definitions.push((section.clone(), Vec::new()));
+                        entry.definitions.len() - 1
+                    }
+                };
+                let definitions = &mut entry.definitions[index].1;
+                for sense in senses {
+                    if definitions.len() < MAX_DEFINITIONS {
+                        definitions.push(sense);
+                    }
+                }
+            }
+        }
+
+        entry.definitions.retain(|(_, d)| !d.is_empty());
+        entry
+    }
+
+    fn infobox(&self, title: &str, entry: Entry) -> InfoBox {
+        let url = format!(
+            "{}/wiki/{}",
+            self.base_url,
+            urlencoding::encode(&title.replace(' ', "_"))
+        );
+
+        let mut attributes = Vec::new();
+        if !entry.pronunciations.is_empty() {
+            attributes.push(("Pronunciation".to_string(), entry.pronunciations.join(", ")));
+        }
+        for (pos, definitions) in entry.definitions {
+            let numbered: Vec<String> = definitions
+                .iter()
+                .enumerate()
+                .map(|(i, d)| format!("{}. {}", i + 1, d))
+                .collect();
+            attributes.push((pos, numbered.join(" ")));
+        }
+        if let Some(etymology) = entry.etymology {
+            attributes.push(("Etymology".to_string(), etymology));
+        }
+
+        InfoBox {
+            id: url.clone(),
+            title: title.to_string(),
+            content: None,
+            img_src: None,
+            url: Some(url.clone()),
+            engine: self.name.clone(),
+            attributes,
+            urls: vec![("Wiktionary".to_string(), url)],
+        }
+    }
+}
+
+impl Default for Wiktionary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for Wiktionary {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new()
+            .website("https://www.wiktionary.org")
+            .official_api(true)
+            .results_format("JSON")
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["general"]
+    }
+
+    fn supports_paging(&self) -> bool {
+        false
+    }
+
+    fn matches_query(&self, query: &str) -> bool {
+        Self::word(query).is_some()
+    }
+
+    fn init(&mut self, config: &EngineConfig) -> AnyhowResult<()> {
+        self.name = config.name.clone();
+        if let Some(language) = config.extra_str("language") {
+            self.language = language;
+        }
+        Ok(())
+    }
+
+    fn request(&self, params: &RequestParams) -> AnyhowResult<EngineRequest> {
+        let word = Self::word(&params.full_query)
+            .ok_or_else(|| anyhow::anyhow!("Not a definition query: {}", params.full_query))?;
+        Ok(self.parse_request(&word))
+    }
+
+    /// Titles are case-sensitive: `NASA` and `Paris` are looked up as
+    /// typed, and a missing capitalized word again in lowercase
+    fn next_request(&self, response: &EngineResponse) -> AnyhowResult<Option<EngineRequest>> {
+        let missing = response
+            .json::<ParseResponse>()
+            .is_ok_and(|data| data.error.is_some_and(|e| e.code == "missingtitle"));
+        if !missing {
+            return Ok(None);
+        }
+
+        let page = url::Url::parse(&response.url).ok().and_then(|url| {
+            url.query_pairs()
+                .find(|(key, _)| key == "page")
+                .map(|(_, page)| page.into_owned())
+        });
+        Ok(page
+            .filter(|page| *page != page.to_lowercase())
+            .map(|page| self.parse_request(&page.to_lowercase())))
+    }
+
+    fn response(&self, response: EngineResponse) -> AnyhowResult<EngineResults> {
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status));
+        }
+
+        let data: ParseResponse = response.json()?;
+        let mut engine_results = EngineResults::new();
+        if let Some(error) = data.error {
+            // No entry for the word
+            if error.code == "missingtitle" {
+                return Ok(engine_results);
+            }
+            return Err(anyhow::anyhow!(
+                "Wiktionary error: {}",
+                error.info.unwrap_or(error.code)
+            ));
+        }
+        let Some(parse) = data.parse else {
+            return Ok(engine_results);
+        };
+
+        let entry = self.parse_entry(&parse.text);
+        if !entry.definitions.is_empty() {
+            engine_results
+                .infoboxes
+                .push(self.infobox(&parse.title, entry));
+        }
+        Ok(engine_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wiktionary_trigger() {
+        let engine = Wiktionary::new();
+        assert_eq!(Wiktionary::word("define crab"), Some("crab".to_string()));
+        assert_eq!(
+            Wiktionary::word("Definition of Crab"),
+            Some("Crab".to_string())
+        );
+        assert_eq!(
+            Wiktionary::word(r#"define "ice cream""#),
+            Some("ice cream".to_string())
+        );
+        assert_eq!(Wiktionary::word("crab meaning"), Some("crab".to_string()));
+        assert_eq!(
+            Wiktionary::word("what does \"hermit crab\" mean?"),
+            Some("hermit crab".to_string())
+        );
+        assert!(!engine.matches_query("crab recipes"));
+        assert!(!engine.matches_query("define a word that is far too long"));
+
+        let request = engine.request(&RequestParams::new("define crab")).unwrap();
+        assert_eq!(request.url, "https://en.wiktionary.org/w/api.php");
+        assert_eq!(request.params.get("page"), Some(&"crab".to_string()));
+        assert_eq!(request.params.get("action"), Some(&"parse".to_string()));
+    }
+
+    #[test]
+    fn test_wiktionary_lowercase_fallback() {
+        let engine = Wiktionary::new();
+        let request = engine.request(&RequestParams::new("define NASA")).unwrap();
+        assert_eq!(request.params.get("page"), Some(&"NASA".to_string()));
+
+        let missing = |page: &str| EngineResponse {
+            url: format!(
+                "https://en.wiktionary.org/w/api.php?action=parse&page={}",
+                page
+            ),
+            ..EngineResponse::new(
+                200,
+                r#"{"error": {"code": "missingtitle", "info": "The page you specified doesn't exist."}}"#,
+            )
+        };
+
+        // A missing capitalized word is looked up again in lowercase
+        let next = engine.next_request(&missing("Crab")).unwrap().unwrap();
+        assert_eq!(next.params.get("page"), Some(&"crab".to_string()));
+        assert!(engine.next_request(&missing("crab")).unwrap().is_none());
+
+        // Found entries need no follow-up
+        let found = EngineResponse::new(200, include_str!("fixtures/wiktionary_crab.json"));
+        assert!(engine.next_request(&found).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_wiktionary_response() {
+        let engine = Wiktionary::new();
+        let response = EngineResponse::new(200, include_str!("fixtures/wiktionary_crab.json"));
+        let results = engine.response(response).unwrap();
+        assert_eq!(results.infoboxes.len(), 1);
+
+        let infobox = &results.infoboxes[0];
+        assert_eq!(infobox.title, "crab");
+        assert_eq!(infobox.id, "https://en.wiktionary.org/wiki/crab");
+        assert_eq!(
+            infobox.attributes,
+            vec![
+                ("Pronunciation".to_string(), "/kɹæb/".to_string()),
+                (
+                    "Noun".to_string(),
+                    "1. A crustacean of the infraorder Brachyura. \
+                     2. A grumpy person. \
+                     3. The crab louse."
+                        .to_string()
+                ),
+                (
+                    "Verb".to_string(),
+                    "1. To fish for crabs. 2. To complain.".to_string()
+                ),
+                (
+                    "Etymology".to_string(),
+                    "From Middle English crabbe, from Old English crabba.".to_string()
+                ),
+            ]
+        );
+
+        // Missing pages are no error
+        let response = EngineResponse::new(
+            200,
+            r#"{"error": {"code": "missingtitle", "info": "The page you specified doesn't exist."}}"#,
+        );
+        assert!(engine.response(response).unwrap().is_empty());
+    }
+}
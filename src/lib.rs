@@ -4,6 +4,7 @@
 //! providing improved performance, memory safety, and type safety.
 
 pub mod autocomplete;
+pub mod bangs;
 pub mod cache;
 pub mod config;
 pub mod engines;
@@ -98,6 +98,15 @@ pub fn get_supported_languages() -> Vec<(&'static str, &'static str)> {
     SUPPORTED_LANGUAGES.to_vec()
 }
 
+/// Get the codes of all supported languages (without the "all" wildcard)
+pub fn language_codes() -> Vec<&'static str> {
+    SUPPORTED_LANGUAGES
+        .iter()
+        .map(|(code, _)| *code)
+        .filter(|code| *code != "all")
+        .collect()
+}
+
 /// Simple translation store (for demo - would load from files in production)
 pub struct Translations {
     translations: HashMap<String, HashMap<String, String>>,
@@ -43,6 +43,9 @@ async fn main() -> Result<()> {
     let state = AppState::new(settings.clone(), registry, client)?;
     info!("Application state initialized");
 
+    // Start refreshing engines that search local data (e.g. feeds)
+    state.search.spawn_refresh_tasks();
+
     // Create router
     let app = create_router(state);
 
@@ -1,6 +1,7 @@
 //! Plugin registry for managing plugins
 
 use super::traits::{Plugin, PreSearchResult};
+use crate::config::PluginsSettings;
 use crate::results::{Answer, Result};
 use crate::search::SearchQuery;
 use std::sync::Arc;
@@ -33,6 +34,21 @@ impl PluginRegistry {
         registry
     }
 
+    /// Create registry with default plugins, applying enabled/disabled settings
+    pub fn from_settings(settings: &PluginsSettings) -> Self {
+        let mut registry = Self::with_defaults();
+        let known: Vec<String> = registry.plugins.iter().map(|p| p.info().id).collect();
+
+        for id in settings.enabled.iter().filter(|id| known.contains(id)) {
+            registry.enable(id);
+        }
+        for id in &settings.disabled {
+            registry.disable(id);
+        }
+
+        registry
+    }
+
     /// Register a plugin
     pub fn register(&mut self, plugin: Arc<dyn Plugin>) {
         let info = plugin.info();
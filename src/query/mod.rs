@@ -3,11 +3,18 @@
 //! Handles parsing of user queries including special syntax like:
 //! - Language specifiers: `:en`, `:de`
 //! - Category/engine bangs: `!images`, `!google`
-//! - External bangs: `!g`, `!yt`
+//! - External bangs: `!!g`, `!!yt` (or `!amazon` when no engine matches)
 //! - Timeout specifiers: `<3`
 //! - Safe search toggle: `!safesearch`
 //! - Time range: `!day`, `!week`, `!month`, `!year`
+//! - Search operators: `site:`, `-site:`, `filetype:`, `intitle:`, `"phrase"`, `-term`
 
+mod operators;
+
+pub use operators::*;
+
+use crate::bangs::BangRegistry;
+use crate::engines::EngineRegistry;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
@@ -36,15 +43,34 @@ pub struct ParsedQuery {
     pub pageno: u32,
     /// Redirect to first result
     pub redirect_to_first: bool,
+    /// Search operators (site:, filetype:, quoted phrases, ...)
+    #[serde(default)]
+    pub filter: QueryFilter,
 }
 
 impl ParsedQuery {
-    /// Parse a raw query string
+    /// Parse a raw query string against the default engines and the
+    /// bundled external bangs
+    ///
+    /// Use [`ParsedQuery::parse_with`] to resolve bangs against the
+    /// configured engines instead.
     pub fn parse(raw: &str) -> Self {
-        let mut query = raw.to_string();
+        Self::parse_with(
+            raw,
+            crate::engines::default_registry(),
+            crate::bangs::default_registry(),
+        )
+    }
+
+    /// Parse a raw query string, resolving `!bang` against the configured
+    /// engines and categories and falling back to external bangs
+    pub fn parse_with(raw: &str, engines: &EngineRegistry, bangs: &BangRegistry) -> Self {
+        // Extract search operators first so values like `filetype:js`
+        // are not mistaken for language specifiers
+        let (mut query, filter) = QueryFilter::extract(raw);
         let mut languages = Vec::new();
         let mut categories = Vec::new();
-        let mut engines = Vec::new();
+        let mut engine_names = Vec::new();
         let mut external_bang = None;
         let mut timeout = None;
         let mut safesearch = None;
@@ -95,111 +121,75 @@ impl ParsedQuery {
             }
         }
 
-        // Parse redirect to first result
+        // Parse redirect to first result ("! query" or "!! query")
         if query.starts_with('!') && query.chars().nth(1).map(|c| c == ' ').unwrap_or(true) {
             redirect_to_first = true;
             query = query.trim_start_matches('!').to_string();
         }
-        if query.starts_with("!!") {
+        if query == "!!" || query.starts_with("!! ") {
             redirect_to_first = true;
             query = query.trim_start_matches("!!").to_string();
         }
 
-        // Parse category bangs (!images, !videos, etc.)
-        let category_bangs = [
-            ("!images", "images"),
-            ("!videos", "videos"),
-            ("!news", "news"),
-            ("!music", "music"),
-            ("!files", "files"),
-            ("!it", "it"),
-            ("!science", "science"),
-            ("!social", "social"),
-            ("!maps", "maps"),
-        ];
-        for (bang, category) in category_bangs {
-            if query.contains(bang) {
-                categories.push(category.to_string());
-                query = query.replace(bang, " ");
+        // Parse explicit external bangs (!!g, !!w)
+        let external_re = Regex::new(r"(?:^|\s)!!(\S+)").unwrap();
+        if let Some(cap) = external_re.captures(&query) {
+            let trigger = cap[1].to_lowercase();
+            if bangs.contains(&trigger) {
+                external_bang = Some(trigger);
+                query = external_re.replace(&query, " ").to_string();
             }
         }
 
-        // Parse engine bangs (!google, !ddg, etc.)
-        let engine_re = Regex::new(r"!(\w+)(?:\s|$)").unwrap();
-        let engine_bangs = Self::get_engine_bangs();
-        let mut remaining_bangs = Vec::new();
+        // Parse category and engine bangs (!images, !google, !gi), keeping
+        // unrecognized bangs in place as ordinary words
+        let mut words = Vec::new();
 
-        for cap in engine_re.captures_iter(&query) {
-            let bang = cap[1].to_lowercase();
-            if let Some(engine) = engine_bangs.get(bang.as_str()) {
-                engines.push(engine.to_string());
-            } else if Self::is_external_bang(&bang) {
+        for word in query.split_whitespace() {
+            let bang = match word.strip_prefix('!') {
+                Some(b) if !b.is_empty() && !b.starts_with('!') => b.to_lowercase(),
+                _ => {
+                    words.push(word);
+                    continue;
+                }
+            };
+
+            if let Some(category) = engines.resolve_category(&bang) {
+                if !categories.iter().any(|c| c == category) {
+                    categories.push(category.to_string());
+                }
+            } else if let Some(engine) = engines.resolve_name(&bang) {
+                if !engine_names.iter().any(|e| e == engine) {
+                    engine_names.push(engine.to_string());
+                }
+            } else if external_bang.is_none() && bangs.contains(&bang) {
                 external_bang = Some(bang);
             } else {
-                remaining_bangs.push(format!("!{}", bang));
+                words.push(word);
             }
         }
 
-        // Remove processed bangs
-        query = engine_re.replace_all(&query, " ").to_string();
-
-        // Add back unrecognized bangs
-        for bang in remaining_bangs {
-            query = format!("{} {}", bang, query);
-        }
-
-        // Clean up whitespace
-        query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+        query = words.join(" ");
 
         Self {
             query,
             raw_query: raw.to_string(),
             languages,
             categories,
-            engines,
+            engines: engine_names,
             external_bang,
             timeout,
             safesearch,
             time_range,
             pageno: 1,
             redirect_to_first,
+            filter,
         }
     }
 
-    /// Get map of engine shortcuts to engine names
-    fn get_engine_bangs() -> std::collections::HashMap<&'static str, &'static str> {
-        let mut map = std::collections::HashMap::new();
-        map.insert("g", "google");
-        map.insert("google", "google");
-        map.insert("ddg", "duckduckgo");
-        map.insert("duckduckgo", "duckduckgo");
-        map.insert("bi", "bing");
-        map.insert("bing", "bing");
-        map.insert("br", "brave");
-        map.insert("brave", "brave");
-        map.insert("wp", "wikipedia");
-        map.insert("wikipedia", "wikipedia");
-        map.insert("yt", "youtube");
-        map.insert("youtube", "youtube");
-        map.insert("gh", "github");
-        map.insert("github", "github");
-        map.insert("so", "stackoverflow");
-        map.insert("stackoverflow", "stackoverflow");
-        map.insert("arx", "arxiv");
-        map.insert("arxiv", "arxiv");
-        map
-    }
-
-    /// Check if a bang should redirect to external site
-    fn is_external_bang(bang: &str) -> bool {
-        // External bangs (redirect to external search)
-        let external = ["g", "yt", "w", "wa", "amazon", "imdb"];
-        external.contains(&bang)
-    }
-
     /// Check if query is empty after parsing
     pub fn is_empty(&self) -> bool {
-        self.query.trim().is_empty()
+        self.query.trim().is_empty() && self.filter.is_empty()
     }
 
     /// Get the effective categories (requested or default)
@@ -232,6 +222,16 @@ impl TimeRange {
             Self::Year => "year",
         }
     }
+
+    /// How far back the range reaches
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            Self::Day => chrono::Duration::days(1),
+            Self::Week => chrono::Duration::weeks(1),
+            Self::Month => chrono::Duration::days(30),
+            Self::Year => chrono::Duration::days(365),
+        }
+    }
 }
 
 impl std::fmt::Display for TimeRange {
@@ -280,6 +280,40 @@ mod tests {
         assert_eq!(parsed.engines, vec!["google"]);
     }
 
+    #[test]
+    fn test_engine_shortcut_bang() {
+        // Shortcuts and config names resolve too, case-insensitively
+        let parsed = ParsedQuery::parse("ferris !GI !google_images");
+        assert_eq!(parsed.query, "ferris");
+        assert_eq!(parsed.engines, vec!["google_images"]);
+    }
+
+    #[test]
+    fn test_external_bang() {
+        // !! always means an external bang, even if an engine shares the trigger
+        let parsed = ParsedQuery::parse("!!gh tokio runtime");
+        assert_eq!(parsed.external_bang, Some("gh".to_string()));
+        assert_eq!(parsed.query, "tokio runtime");
+        assert!(parsed.engines.is_empty());
+        assert!(!parsed.redirect_to_first);
+
+        // ! falls back to external bangs when no engine matches
+        let parsed = ParsedQuery::parse("dune !imdb");
+        assert_eq!(parsed.external_bang, Some("imdb".to_string()));
+
+        // Unknown bangs stay in the query
+        let parsed = ParsedQuery::parse("hello !!notabang");
+        assert_eq!(parsed.external_bang, None);
+        assert_eq!(parsed.query, "hello !!notabang");
+    }
+
+    #[test]
+    fn test_redirect_to_first() {
+        let parsed = ParsedQuery::parse("!! rust book");
+        assert!(parsed.redirect_to_first);
+        assert_eq!(parsed.query, "rust book");
+    }
+
     #[test]
     fn test_time_range() {
         let parsed = ParsedQuery::parse("news !week");
@@ -287,6 +321,19 @@ mod tests {
         assert_eq!(parsed.time_range, Some(TimeRange::Week));
     }
 
+    #[test]
+    fn test_search_operators() {
+        let parsed = ParsedQuery::parse(r#"serde site:docs.rs filetype:rs "zero copy" :en"#);
+        assert_eq!(parsed.query, "serde");
+        assert_eq!(parsed.languages, vec!["en"]);
+        assert_eq!(parsed.filter.operators.len(), 3);
+        assert!(!parsed.is_empty());
+
+        let parsed = ParsedQuery::parse(r#""exact phrase only""#);
+        assert!(parsed.query.is_empty());
+        assert!(!parsed.is_empty());
+    }
+
     #[test]
     fn test_safesearch() {
         let parsed = ParsedQuery::parse("query !safesearch");
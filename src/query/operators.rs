@@ -0,0 +1,508 @@
+//! Search operator parsing
+//!
+//! Extracts web-style search operators from a query:
+//! - Site restriction: `site:docs.rs`, `-site:pinterest.com`
+//! - File type: `filetype:pdf` (alias `ext:pdf`)
+//! - Title match: `intitle:rust`, `intitle:"async book"`
+//! - Exact phrases: `"borrow checker"`
+//! - Negation: `-tokio`, `-"hello world"`
+//!
+//! Engines that understand an operator receive it rewritten into their own
+//! syntax; everything else is enforced locally by post-filtering results.
+
+use crate::results::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Kind of search operator, used by engines to declare native support
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum OperatorKind {
+    Site,
+    ExcludeSite,
+    FileType,
+    InTitle,
+    Phrase,
+    Exclude,
+}
+
+impl OperatorKind {
+    /// All operator kinds
+    pub fn all() -> Vec<OperatorKind> {
+        vec![
+            Self::Site,
+            Self::ExcludeSite,
+            Self::FileType,
+            Self::InTitle,
+            Self::Phrase,
+            Self::Exclude,
+        ]
+    }
+}
+
+/// A single search operator extracted from the query
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum QueryOperator {
+    /// Only results from this domain (optionally with a path prefix)
+    Site(String),
+    /// No results from this domain
+    ExcludeSite(String),
+    /// Only results with this file extension
+    FileType(String),
+    /// Title must contain this text
+    InTitle(String),
+    /// Exact phrase must appear
+    Phrase(String),
+    /// Term or phrase must not appear
+    Exclude(String),
+}
+
+impl QueryOperator {
+    /// Get the operator kind
+    pub fn kind(&self) -> OperatorKind {
+        match self {
+            Self::Site(_) => OperatorKind::Site,
+            Self::ExcludeSite(_) => OperatorKind::ExcludeSite,
+            Self::FileType(_) => OperatorKind::FileType,
+            Self::InTitle(_) => OperatorKind::InTitle,
+            Self::Phrase(_) => OperatorKind::Phrase,
+            Self::Exclude(_) => OperatorKind::Exclude,
+        }
+    }
+
+    /// Get the operator value
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Site(v)
+            | Self::ExcludeSite(v)
+            | Self::FileType(v)
+            | Self::InTitle(v)
+            | Self::Phrase(v)
+            | Self::Exclude(v) => v,
+        }
+    }
+
+    /// Check whether a result satisfies this operator
+    pub fn matches(&self, result: &Result) -> bool {
+        match self {
+            Self::Site(site) => url_matches_site(&result.url, site),
+            Self::ExcludeSite(site) => !url_matches_site(&result.url, site),
+            Self::FileType(ext) => result_has_file_type(result, ext),
+            Self::InTitle(text) => contains_ignore_case(&result.title, text),
+            Self::Phrase(phrase) => {
+                contains_ignore_case(&result.title, phrase)
+                    || result
+                        .content
+                        .as_deref()
+                        .map(|c| contains_ignore_case(c, phrase))
+                        .unwrap_or(false)
+            }
+            Self::Exclude(term) => {
+                !contains_word_ignore_case(&result.title, term)
+                    && !result
+                        .content
+                        .as_deref()
+                        .map(|c| contains_word_ignore_case(c, term))
+                        .unwrap_or(false)
+            }
+        }
+    }
+
+    /// Parse a single query token into an operator
+    fn parse_token(token: &str) -> Option<Self> {
+        if let Some(rest) = token.strip_prefix('-') {
+            if rest.starts_with('"') {
+                return non_empty(unquote(rest)).map(Self::Exclude);
+            }
+            if let Some(site) = strip_prefix_ignore_case(rest, "site:") {
+                return non_empty(normalize_site(site)).map(Self::ExcludeSite);
+            }
+            if rest.chars().next().is_some_and(|c| c.is_alphabetic()) {
+                return Some(Self::Exclude(rest.to_string()));
+            }
+            return None;
+        }
+
+        if token.starts_with('"') {
+            return non_empty(unquote(token)).map(Self::Phrase);
+        }
+
+        if let Some(site) = strip_prefix_ignore_case(token, "site:") {
+            return non_empty(normalize_site(site)).map(Self::Site);
+        }
+
+        if let Some(ext) = strip_prefix_ignore_case(token, "filetype:")
+            .or_else(|| strip_prefix_ignore_case(token, "ext:"))
+        {
+            return non_empty(ext.trim_start_matches('.').to_lowercase()).map(Self::FileType);
+        }
+
+        if let Some(text) = strip_prefix_ignore_case(token, "intitle:") {
+            return non_empty(unquote(text)).map(Self::InTitle);
+        }
+
+        None
+    }
+}
+
+impl fmt::Display for QueryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Site(v) => write!(f, "site:{}", v),
+            Self::ExcludeSite(v) => write!(f, "-site:{}", v),
+            Self::FileType(v) => write!(f, "filetype:{}", v),
+            Self::InTitle(v) => write!(f, "intitle:{}", quote_if_needed(v)),
+            Self::Phrase(v) => write!(f, "\"{}\"", v),
+            Self::Exclude(v) => write!(f, "-{}", quote_if_needed(v)),
+        }
+    }
+}
+
+/// Structured filter built from the search operators in a query
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QueryFilter {
+    /// Operators in query order
+    pub operators: Vec<QueryOperator>,
+}
+
+impl QueryFilter {
+    /// Create an empty filter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Extract operators from a query, returning the remaining plain terms
+    pub fn extract(query: &str) -> (String, Self) {
+        let mut terms = Vec::new();
+        let mut operators = Vec::new();
+
+        for token in tokenize(query) {
+            match QueryOperator::parse_token(&token) {
+                Some(op) => operators.push(op),
+                None => terms.push(token),
+            }
+        }
+
+        (terms.join(" "), Self { operators })
+    }
+
+    /// Rebuild a query with its operators where they were typed
+    ///
+    /// `terms` are what is left of `raw` once the operators and any other
+    /// syntax are removed. Returns `None` if they can't be found in `raw`
+    /// in order.
+    pub fn in_place(raw: &str, terms: &str) -> Option<String> {
+        let mut words = terms.split_whitespace().peekable();
+        let mut parts = Vec::new();
+
+        for token in tokenize(raw) {
+            if let Some(op) = QueryOperator::parse_token(&token) {
+                parts.push(op.to_string());
+                continue;
+            }
+            for word in token.split_whitespace() {
+                if words.peek() == Some(&word) {
+                    parts.push(word.to_string());
+                    words.next();
+                }
+            }
+        }
+
+        words.next().is_none().then(|| parts.join(" "))
+    }
+
+    /// Check if the filter has no operators
+    pub fn is_empty(&self) -> bool {
+        self.operators.is_empty()
+    }
+
+    /// Add an operator
+    pub fn push(&mut self, operator: QueryOperator) {
+        self.operators.push(operator);
+    }
+
+    /// Split into operators an engine supports natively and those that
+    /// must be enforced locally
+    pub fn partition<F>(&self, supported: F) -> (Self, Self)
+    where
+        F: Fn(&QueryOperator) -> bool,
+    {
+        let (native, local): (Vec<_>, Vec<_>) =
+            self.operators.iter().cloned().partition(|op| supported(op));
+        (Self { operators: native }, Self { operators: local })
+    }
+
+    /// Plain search terms that stand in for operators an engine can't express
+    ///
+    /// Phrases and title words are still sent as keywords so the engine
+    /// returns candidates that can then be filtered locally.
+    pub fn fallback_terms(&self) -> Vec<&str> {
+        self.operators
+            .iter()
+            .filter_map(|op| match op {
+                QueryOperator::Phrase(v) | QueryOperator::InTitle(v) => Some(v.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Check whether a result satisfies every operator
+    pub fn matches(&self, result: &Result) -> bool {
+        self.operators.iter().all(|op| op.matches(result))
+    }
+}
+
+impl fmt::Display for QueryFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.operators.iter().map(|op| op.to_string()).collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Split a query on whitespace, keeping quoted sections together
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in query.chars() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+            current.push(c);
+        } else if c.is_whitespace() && !in_quotes {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Remove surrounding quotes and collapse whitespace
+fn unquote(s: &str) -> String {
+    s.trim_matches('"')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Wrap a value in quotes if it contains whitespace
+pub fn quote_if_needed(s: &str) -> String {
+    if s.contains(char::is_whitespace) {
+        format!("\"{}\"", s)
+    } else {
+        s.to_string()
+    }
+}
+
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    if s.len() >= prefix.len()
+        && s.is_char_boundary(prefix.len())
+        && s[..prefix.len()].eq_ignore_ascii_case(prefix)
+    {
+        Some(&s[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/// Normalize a site value: drop scheme, `www.` and trailing slashes
+fn normalize_site(site: &str) -> String {
+    let site = site.trim_matches('"').to_lowercase();
+    let site = site
+        .strip_prefix("https://")
+        .or_else(|| site.strip_prefix("http://"))
+        .unwrap_or(&site);
+    site.strip_prefix("www.")
+        .unwrap_or(site)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+/// Like [`contains_ignore_case`], but only whole words: `java` is not
+/// found in "javascript"
+fn contains_word_ignore_case(haystack: &str, needle: &str) -> bool {
+    let haystack = haystack.to_lowercase();
+    let needle = needle.to_lowercase();
+    if needle.is_empty() {
+        return false;
+    }
+    let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+    haystack.match_indices(&needle).any(|(start, _)| {
+        !is_word(haystack[..start].chars().next_back())
+            && !is_word(haystack[start + needle.len()..].chars().next())
+    })
+}
+
+/// Check if a URL belongs to a site (domain or subdomain, optional path prefix)
+fn url_matches_site(url: &str, site: &str) -> bool {
+    let parsed = match url::Url::parse(url) {
+        Ok(u) => u,
+        Err(_) => return false,
+    };
+    let host = parsed.host_str().unwrap_or_default().to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+
+    let site = site.to_lowercase();
+    let (domain, path) = match site.find('/') {
+        Some(idx) => (&site[..idx], &site[idx..]),
+        None => (site.as_str(), ""),
+    };
+
+    let host_matches = host == domain || host.ends_with(&format!(".{}", domain));
+    host_matches && path_has_prefix(&parsed.path().to_lowercase(), path)
+}
+
+/// Check if a URL path starts with the segments of `prefix`
+fn path_has_prefix(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'),
+        None => false,
+    }
+}
+
+/// Check if a result points to a file of the given extension
+fn result_has_file_type(result: &Result, ext: &str) -> bool {
+    if let Some(ref file_type) = result.metadata.file_type {
+        if file_type.eq_ignore_ascii_case(ext) {
+            return true;
+        }
+    }
+
+    url::Url::parse(&result.url)
+        .map(|u| u.path().to_lowercase().ends_with(&format!(".{}", ext)))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str, title: &str, content: &str) -> Result {
+        Result::new(url.to_string(), title.to_string(), "test".to_string())
+            .with_content(content.to_string())
+    }
+
+    #[test]
+    fn test_extract_operators() {
+        let (terms, filter) = QueryFilter::extract(
+            r#"serde site:docs.rs -site:www.reddit.com filetype:PDF intitle:"derive macro" "zero copy" -json"#,
+        );
+        assert_eq!(terms, "serde");
+        assert_eq!(
+            filter.operators,
+            vec![
+                QueryOperator::Site("docs.rs".to_string()),
+                QueryOperator::ExcludeSite("reddit.com".to_string()),
+                QueryOperator::FileType("pdf".to_string()),
+                QueryOperator::InTitle("derive macro".to_string()),
+                QueryOperator::Phrase("zero copy".to_string()),
+                QueryOperator::Exclude("json".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operators_in_place() {
+        let raw = r#"define "ice cream" -site:example.com :de"#;
+        assert_eq!(
+            QueryFilter::in_place(raw, "define"),
+            Some(r#"define "ice cream" -site:example.com"#.to_string())
+        );
+        // Terms that changed since can't be placed
+        assert_eq!(QueryFilter::in_place(raw, "define dessert"), None);
+    }
+
+    #[test]
+    fn test_non_operators_kept() {
+        let (terms, filter) = QueryFilter::extract("x -5 - site: c++");
+        assert_eq!(terms, "x -5 - site: c++");
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        let query = r#"site:docs.rs intitle:"a b" "exact words" -"not this""#;
+        let (_, filter) = QueryFilter::extract(query);
+        assert_eq!(filter.to_string(), query);
+    }
+
+    #[test]
+    fn test_partition() {
+        let (_, filter) = QueryFilter::extract("site:docs.rs -tokio");
+        let (native, local) = filter.partition(|op| op.kind() == OperatorKind::Site);
+        assert_eq!(native.operators.len(), 1);
+        assert_eq!(
+            local.operators,
+            vec![QueryOperator::Exclude("tokio".into())]
+        );
+    }
+
+    #[test]
+    fn test_site_matching() {
+        let op = QueryOperator::Site("docs.rs".to_string());
+        assert!(op.matches(&result("https://docs.rs/serde", "serde", "")));
+        assert!(op.matches(&result("https://www.docs.rs/", "docs", "")));
+        assert!(!op.matches(&result("https://notdocs.rs/", "x", "")));
+
+        let op = QueryOperator::Site("github.com/rust-lang".to_string());
+        assert!(op.matches(&result("https://github.com/rust-lang/rust", "rust", "")));
+        assert!(!op.matches(&result("https://github.com/tokio-rs/tokio", "tokio", "")));
+
+        // The path matches whole segments, whatever the case
+        let op = QueryOperator::Site("GitHub.com/Rust".to_string());
+        assert!(op.matches(&result("https://github.com/rust", "rust", "")));
+        assert!(op.matches(&result("https://github.com/rust/issues", "issues", "")));
+        assert!(!op.matches(&result("https://github.com/rust-lang/rust", "rust", "")));
+
+        let op = QueryOperator::Site("wikipedia.org".to_string());
+        assert!(op.matches(&result("https://en.wikipedia.org/wiki/Rust", "Rust", "")));
+    }
+
+    #[test]
+    fn test_local_filter() {
+        let (_, filter) = QueryFilter::extract(r#"filetype:pdf "memory safety" -java"#);
+
+        assert!(filter.matches(&result(
+            "https://example.com/paper.PDF",
+            "Paper",
+            "Memory safety in Rust"
+        )));
+        assert!(!filter.matches(&result(
+            "https://example.com/paper.html",
+            "Paper",
+            "memory safety in Rust"
+        )));
+        assert!(!filter.matches(&result(
+            "https://example.com/paper.pdf",
+            "Paper",
+            "memory safety in Java"
+        )));
+        // Exclusions match whole words only
+        assert!(filter.matches(&result(
+            "https://example.com/paper.pdf",
+            "Paper",
+            "memory safety in JavaScript"
+        )));
+    }
+}
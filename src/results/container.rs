@@ -1,6 +1,7 @@
 //! Result container for aggregating and deduplicating search results
 
 use super::types::*;
+use crate::query::QueryFilter;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 
@@ -9,6 +10,8 @@ use std::sync::{Arc, RwLock};
 pub struct ResultContainer {
     /// Main results map (URL hash -> Result) for deduplication
     results_map: Arc<RwLock<HashMap<String, Result>>>,
+    /// Every dedup key of the stored results -> their key in `results_map`
+    aliases: Arc<RwLock<HashMap<String, String>>>,
     /// Direct answers
     answers: Arc<RwLock<Vec<Answer>>>,
     /// Search suggestions
@@ -38,6 +41,7 @@ impl ResultContainer {
     pub fn new() -> Self {
         Self {
             results_map: Arc::new(RwLock::new(HashMap::new())),
+            aliases: Arc::new(RwLock::new(HashMap::new())),
             answers: Arc::new(RwLock::new(Vec::new())),
             suggestions: Arc::new(RwLock::new(HashSet::new())),
             corrections: Arc::new(RwLock::new(HashSet::new())),
@@ -56,18 +60,84 @@ impl ResultContainer {
         container
     }
 
-    /// Add a result, merging with existing if URL matches
+    /// Add a result, merging with existing if URL, DOI or image matches
     pub fn add_result(&self, result: Result) {
-        let url_hash = Self::url_hash(&result.url);
+        let keys = Self::dedup_keys(&result);
 
         let mut map = self.results_map.write().unwrap();
-        if let Some(existing) = map.get_mut(&url_hash) {
-            existing.merge(&result);
-        } else {
-            map.insert(url_hash, result);
+        let mut aliases = self.aliases.write().unwrap();
+        let existing = keys
+            .iter()
+            .filter_map(|key| aliases.get(key))
+            .find(|primary| !Self::distinct(&map[*primary], &result))
+            .cloned();
+
+        match existing {
+            Some(primary) => {
+                let existing = map.get_mut(&primary).unwrap();
+                existing.merge(&result);
+                // Merging can add a DOI or image the result wasn't known by
+                for key in Self::dedup_keys(existing).into_iter().chain(keys) {
+                    aliases.entry(key).or_insert_with(|| primary.clone());
+                }
+            }
+            None => {
+                let primary = keys[0].clone();
+                for key in keys {
+                    aliases.entry(key).or_insert_with(|| primary.clone());
+                }
+                map.insert(primary, result);
+            }
         }
     }
 
+    /// Keys identifying duplicate results, most specific first
+    ///
+    /// Results are known by their URL, papers also by their DOI, as every
+    /// scholarly engine links to a different landing page for the same
+    /// paper, and images also by their source, as one image can be found on
+    /// many pages.
+    fn dedup_keys(result: &Result) -> Vec<String> {
+        let mut keys = Vec::new();
+        if let Some(doi) = result.doi() {
+            keys.push(format!("doi:{}", doi));
+        }
+        if let Some(img) = Self::image_key(result) {
+            keys.push(img);
+        }
+        keys.push(Self::url_hash(&result.url));
+        keys
+    }
+
+    fn image_key(result: &Result) -> Option<String> {
+        let img_src = result
+            .metadata
+            .img_src
+            .as_deref()
+            .filter(|src| !src.is_empty())?;
+        (result.result_type == ResultType::Image)
+            .then(|| format!("img:{}", Self::url_hash(img_src)))
+    }
+
+    /// Whether two results sharing a key are still different, like two
+    /// papers on one page or two images on one page
+    fn distinct(a: &Result, b: &Result) -> bool {
+        let differ =
+            |a: Option<String>, b: Option<String>| matches!((a, b), (Some(a), Some(b)) if a != b);
+        differ(a.doi(), b.doi()) || differ(Self::image_key(a), Self::image_key(b))
+    }
+
+    /// Add a result only if it satisfies the locally enforced operators
+    ///
+    /// Returns whether the result was kept.
+    pub fn add_result_filtered(&self, result: Result, filter: &QueryFilter) -> bool {
+        if !filter.matches(&result) {
+            return false;
+        }
+        self.add_result(result);
+        true
+    }
+
     /// Add multiple results
     pub fn extend_results(&self, results: Vec<Result>) {
         for result in results {
@@ -95,19 +165,55 @@ impl ResultContainer {
     }
 
     /// Add an infobox
+    ///
+    /// Infoboxes with the same id (usually the Wikipedia article URL) are
+    /// merged: the longer content wins, missing fields are filled in and
+    /// attributes and links are combined.
     pub fn add_infobox(&self, infobox: InfoBox) {
         let mut boxes = self.infoboxes.write().unwrap();
-        // Merge if same ID exists
-        if let Some(existing) = boxes.iter_mut().find(|b| b.id == infobox.id) {
-            // Keep the one with more content
-            if infobox.content.as_ref().map(|c| c.len()).unwrap_or(0)
-                > existing.content.as_ref().map(|c| c.len()).unwrap_or(0)
-            {
-                *existing = infobox;
-            }
-        } else {
+        let key = Self::infobox_key(&infobox.id);
+
+        let Some(existing) = boxes.iter_mut().find(|b| Self::infobox_key(&b.id) == key) else {
             boxes.push(infobox);
+            return;
+        };
+
+        let content_len = |b: &InfoBox| b.content.as_ref().map(|c| c.len()).unwrap_or(0);
+        let (mut merged, other) = if content_len(&infobox) > content_len(existing) {
+            (infobox, existing.clone())
+        } else {
+            (existing.clone(), infobox)
+        };
+
+        merged.img_src = merged.img_src.or(other.img_src);
+        merged.url = merged.url.or(other.url);
+        for (label, value) in other.attributes {
+            if !merged.attributes.iter().any(|(l, _)| *l == label) {
+                merged.attributes.push((label, value));
+            }
+        }
+        for (title, url) in other.urls {
+            if !merged.urls.iter().any(|(_, u)| *u == url) {
+                merged.urls.push((title, url));
+            }
         }
+
+        *existing = merged;
+    }
+
+    /// Normalize an infobox id for merging
+    ///
+    /// Ignores the scheme, percent-encoding and `_` vs space differences
+    /// between article URLs from different APIs.
+    fn infobox_key(id: &str) -> String {
+        let id = id
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .trim_end_matches('/');
+        urlencoding::decode(id)
+            .map(|d| d.into_owned())
+            .unwrap_or_else(|_| id.to_string())
+            .replace(' ', "_")
     }
 
     /// Record an unresponsive engine
@@ -153,6 +259,16 @@ impl ResultContainer {
         results
     }
 
+    /// Get all results, most recent first
+    ///
+    /// Results without a publish date follow in score order.
+    pub fn get_results_by_date(&self) -> Vec<Result> {
+        let mut results = self.get_ordered_results();
+        // The sort is stable, so results of the same date keep their score order
+        results.sort_by_key(|r| std::cmp::Reverse(r.metadata.published_date));
+        results
+    }
+
     /// Get paginated results
     pub fn get_results_page(&self, page: usize, per_page: usize) -> Vec<Result> {
         let results = self.get_ordered_results();
@@ -304,6 +420,130 @@ mod tests {
         assert_eq!(results[0].engines.len(), 2);
     }
 
+    #[test]
+    fn test_doi_deduplication() {
+        let container = ResultContainer::new();
+
+        let mut crossref = Result::new(
+            "https://doi.org/10.1038/S41586-021-03819-2".to_string(),
+            "Highly accurate protein structure prediction".to_string(),
+            "crossref".to_string(),
+        )
+        .with_position(1);
+        crossref.metadata.journal = Some("Nature".to_string());
+        crossref.metadata.citations = Some(120);
+
+        let mut semantic_scholar = Result::new(
+            "https://www.semanticscholar.org/paper/dc32a984".to_string(),
+            "Highly accurate protein structure prediction with AlphaFold".to_string(),
+            "semantic_scholar".to_string(),
+        )
+        .with_position(2);
+        semantic_scholar.metadata.doi = Some("10.1038/s41586-021-03819-2".to_string());
+        semantic_scholar.metadata.citations = Some(150);
+        semantic_scholar.metadata.pdf_url = Some("https://example.org/alphafold.pdf".to_string());
+
+        container.add_result(crossref);
+        container.add_result(semantic_scholar);
+        assert_eq!(container.result_count(), 1);
+
+        let paper = &container.get_ordered_results()[0];
+        assert_eq!(paper.engines.len(), 2);
+        assert_eq!(paper.metadata.journal.as_deref(), Some("Nature"));
+        assert_eq!(paper.metadata.citations, Some(150));
+        assert!(paper.metadata.pdf_url.is_some());
+    }
+
+    #[test]
+    fn test_image_deduplication() {
+        let container = ResultContainer::new();
+        let image = |page: &str, img_src: &str, engine: &str| {
+            let mut result =
+                Result::new(page.to_string(), "Ferris".to_string(), engine.to_string())
+                    .with_position(1);
+            result.result_type = ResultType::Image;
+            result.metadata.img_src = Some(img_src.to_string());
+            result
+        };
+
+        // The same image on two pages is one result
+        let mut bing = image(
+            "https://a.example.org/crab",
+            "https://cdn.example.org/ferris.png",
+            "bing_images",
+        );
+        bing.metadata.resolution = Some("800 x 600".to_string());
+        container.add_result(bing);
+        container.add_result(image(
+            "https://b.example.org/",
+            "https://cdn.example.org/ferris.png",
+            "duckduckgo_images",
+        ));
+        // Two images on the same page are two results
+        container.add_result(image(
+            "https://a.example.org/crab",
+            "https://cdn.example.org/ferris-2.png",
+            "bing_images",
+        ));
+        assert_eq!(container.result_count(), 2);
+
+        let merged = container
+            .get_ordered_results()
+            .into_iter()
+            .find(|r| r.engines.len() == 2)
+            .unwrap();
+        assert_eq!(merged.metadata.resolution.as_deref(), Some("800 x 600"));
+
+        // A web result for the page of an image is the same result
+        container.add_result(Result::new(
+            "https://b.example.org".to_string(),
+            "Ferris".to_string(),
+            "google".to_string(),
+        ));
+        assert_eq!(container.result_count(), 2);
+    }
+
+    #[test]
+    fn test_doi_and_url_deduplication() {
+        let container = ResultContainer::new();
+
+        // A web hit on the abstract page and the arXiv result for the paper
+        container.add_result(Result::new(
+            "https://arxiv.org/abs/1706.03762".to_string(),
+            "Attention Is All You Need".to_string(),
+            "google".to_string(),
+        ));
+        let mut arxiv = Result::new(
+            "https://arxiv.org/abs/1706.03762".to_string(),
+            "Attention Is All You Need".to_string(),
+            "arxiv".to_string(),
+        );
+        arxiv.metadata.doi = Some("10.48550/arXiv.1706.03762".to_string());
+        container.add_result(arxiv);
+        assert_eq!(container.result_count(), 1);
+
+        // The merged result is now also known by its DOI
+        let mut crossref = Result::new(
+            "https://doi.org/10.48550/arxiv.1706.03762".to_string(),
+            "Attention Is All You Need".to_string(),
+            "crossref".to_string(),
+        );
+        crossref.metadata.journal = Some("arXiv".to_string());
+        container.add_result(crossref);
+        assert_eq!(container.result_count(), 1);
+        assert_eq!(container.get_ordered_results()[0].engines.len(), 3);
+
+        // Another paper on the same page stays apart
+        let mut other = Result::new(
+            "https://arxiv.org/abs/1706.03762".to_string(),
+            "Attention Is All You Need (v2)".to_string(),
+            "semantic_scholar".to_string(),
+        );
+        other.metadata.doi = Some("10.5555/3295222.3295349".to_string());
+        container.add_result(other);
+        assert_eq!(container.result_count(), 2);
+    }
+
     #[test]
     fn test_result_ordering() {
         let container = ResultContainer::new();
@@ -329,6 +569,31 @@ mod tests {
         assert_eq!(results[0].url, "https://second.com");
     }
 
+    #[test]
+    fn test_date_ordering() {
+        let container = ResultContainer::new();
+        let dated = |url: &str, position: u32, date: Option<&str>| {
+            let mut result = Result::new(url.to_string(), url.to_string(), "bing_news".to_string())
+                .with_position(position);
+            result.metadata.published_date = date.and_then(|d| d.parse().ok());
+            result
+        };
+
+        container.add_result(dated("https://old.com", 1, Some("2024-01-01T00:00:00Z")));
+        container.add_result(dated("https://undated.com", 2, None));
+        container.add_result(dated("https://new.com", 3, Some("2024-02-01T00:00:00Z")));
+
+        let urls: Vec<String> = container
+            .get_results_by_date()
+            .into_iter()
+            .map(|r| r.url)
+            .collect();
+        assert_eq!(
+            urls,
+            vec!["https://new.com", "https://old.com", "https://undated.com"]
+        );
+    }
+
     #[test]
     fn test_url_hash_normalization() {
         // Test www prefix removal
@@ -390,6 +655,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_filtered_result() {
+        let container = ResultContainer::new();
+        let (_, filter) = QueryFilter::extract("site:docs.rs");
+
+        let kept = Result::new(
+            "https://docs.rs/serde".to_string(),
+            "serde".to_string(),
+            "duckduckgo".to_string(),
+        );
+        let dropped = Result::new(
+            "https://crates.io/crates/serde".to_string(),
+            "serde".to_string(),
+            "duckduckgo".to_string(),
+        );
+
+        assert!(container.add_result_filtered(kept, &filter));
+        assert!(!container.add_result_filtered(dropped, &filter));
+        assert_eq!(container.result_count(), 1);
+    }
+
+    #[test]
+    fn test_infobox_merge() {
+        let container = ResultContainer::new();
+        container.add_infobox(InfoBox {
+            id: "https://en.wikipedia.org/wiki/Rust_(programming_language)".to_string(),
+            title: "Rust".to_string(),
+            content: Some("general-purpose programming language".to_string()),
+            img_src: None,
+            url: None,
+            engine: "wikidata".to_string(),
+            attributes: vec![("Inception".to_string(), "2010".to_string())],
+            urls: vec![(
+                "Official website".to_string(),
+                "https://www.rust-lang.org/".to_string(),
+            )],
+        });
+        container.add_infobox(InfoBox {
+            id: "https://en.wikipedia.org/wiki/Rust_%28programming_language%29".to_string(),
+            title: "Rust (programming language)".to_string(),
+            content: Some("Rust is a general-purpose programming language emphasizing performance, type safety and concurrency.".to_string()),
+            img_src: Some("https://upload.wikimedia.org/rust.png".to_string()),
+            url: Some("https://en.wikipedia.org/wiki/Rust_(programming_language)".to_string()),
+            engine: "wikipedia".to_string(),
+            attributes: vec![],
+            urls: vec![],
+        });
+
+        let boxes = container.get_infoboxes();
+        assert_eq!(boxes.len(), 1);
+        assert_eq!(boxes[0].engine, "wikipedia");
+        assert!(boxes[0].content.as_deref().unwrap().starts_with("Rust is"));
+        assert_eq!(boxes[0].attributes.len(), 1);
+        assert_eq!(boxes[0].urls.len(), 1);
+        assert!(boxes[0].img_src.is_some());
+    }
+
     #[test]
     fn test_fragment_ignored() {
         // Fragment identifiers should be ignored
@@ -1,5 +1,6 @@
 //! Result type definitions
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use url::Url;
@@ -85,6 +86,30 @@ impl Result {
         if self.content.is_none() && other.content.is_some() {
             self.content = other.content.clone();
         }
+        self.metadata.published_date = self
+            .metadata
+            .published_date
+            .or(other.metadata.published_date);
+
+        self.metadata.merge_paper(&other.metadata);
+        self.metadata.merge_image(&other.metadata);
+    }
+
+    /// Normalized DOI of the result, if it has one
+    ///
+    /// Taken from the metadata or from a doi.org URL.
+    pub fn doi(&self) -> Option<String> {
+        let doi = match &self.metadata.doi {
+            Some(doi) => doi.as_str(),
+            None => {
+                let url = self.parsed_url.as_ref()?;
+                match url.host_str()? {
+                    "doi.org" | "dx.doi.org" => url.path().trim_start_matches('/'),
+                    _ => return None,
+                }
+            }
+        };
+        normalize_doi(doi)
     }
 
     /// Calculate the score based on positions and engine weights
@@ -103,6 +128,27 @@ impl Result {
     }
 }
 
+/// Normalize a DOI for comparison
+///
+/// Strips resolver prefixes and lowercases, as DOIs are case-insensitive.
+pub fn normalize_doi(doi: &str) -> Option<String> {
+    let doi = doi.trim();
+    let doi = [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "doi:",
+    ]
+    .iter()
+    .find_map(|prefix| doi.strip_prefix(prefix))
+    .unwrap_or(doi);
+    let doi = urlencoding::decode(doi)
+        .map(|d| d.into_owned())
+        .unwrap_or_else(|_| doi.to_string());
+
+    Some(doi.to_lowercase()).filter(|d| d.starts_with("10."))
+}
+
 /// Additional result metadata
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ResultMetadata {
@@ -110,12 +156,16 @@ pub struct ResultMetadata {
     pub thumbnail: Option<String>,
     /// Image URL (for image results)
     pub img_src: Option<String>,
+    /// Image dimensions as `width x height` (for image results)
+    pub resolution: Option<String>,
     /// Template to use for rendering
     pub template: Option<String>,
     /// Author name
     pub author: Option<String>,
-    /// Published date
-    pub published_date: Option<String>,
+    /// Publish date, normalized to UTC
+    pub published_date: Option<DateTime<Utc>>,
+    /// Content as HTML with the query terms in `<mark>` (escaped by the engine)
+    pub snippet: Option<String>,
     /// File type
     pub file_type: Option<String>,
     /// File size
@@ -130,6 +180,103 @@ pub struct ResultMetadata {
     pub audio_src: Option<String>,
     /// Is official result
     pub is_official: bool,
+    /// Latitude (for map results)
+    pub latitude: Option<f64>,
+    /// Longitude (for map results)
+    pub longitude: Option<f64>,
+    /// Bounding box as [south, north, west, east] (for map results)
+    pub boundingbox: Option<[f64; 4]>,
+    /// Postal address (for map results)
+    pub address: Option<Address>,
+    /// OpenStreetMap object type: node, way or relation
+    pub osm_type: Option<String>,
+    /// OpenStreetMap object id
+    pub osm_id: Option<u64>,
+    /// Latest version (for package results)
+    pub version: Option<String>,
+    /// Download count (for package results)
+    pub downloads: Option<u64>,
+    /// License (for package results)
+    pub license: Option<String>,
+    /// Source repository URL (for package results)
+    pub repository: Option<String>,
+    /// Last updated date (for package and repository results)
+    pub updated_date: Option<String>,
+    /// Star count (for repository results)
+    pub stars: Option<u64>,
+    /// Main programming language (for repository results)
+    pub language: Option<String>,
+    /// Vote score (for Q&A and discussion results)
+    pub votes: Option<i64>,
+    /// Number of answers (for Q&A results)
+    pub answer_count: Option<u64>,
+    /// Whether an answer was accepted (for Q&A results)
+    pub accepted: bool,
+    /// Number of comments (for discussion results)
+    pub comment_count: Option<u64>,
+    /// URL of the discussion page (for discussion results)
+    pub comments_url: Option<String>,
+    /// Boost count (for social results)
+    pub boosts: Option<u64>,
+    /// Instance the post or account is from (for social results)
+    pub instance: Option<String>,
+    /// Tags
+    pub tags: Vec<String>,
+    /// Authors (for paper results)
+    pub authors: Vec<String>,
+    /// Journal or venue (for paper results)
+    pub journal: Option<String>,
+    /// DOI (for paper results)
+    pub doi: Option<String>,
+    /// Author comment, e.g. page count or venue (for paper results)
+    pub comment: Option<String>,
+    /// Citation count (for paper results)
+    pub citations: Option<u64>,
+    /// Link to the full text PDF (for paper results)
+    pub pdf_url: Option<String>,
+    /// Whether the full text is open access (for paper results)
+    pub open_access: bool,
+}
+
+impl ResultMetadata {
+    /// Fill in missing paper metadata from the same paper found by another engine
+    fn merge_paper(&mut self, other: &ResultMetadata) {
+        if self.authors.is_empty() {
+            self.authors = other.authors.clone();
+        }
+        self.journal = self.journal.take().or_else(|| other.journal.clone());
+        self.doi = self.doi.take().or_else(|| other.doi.clone());
+        self.citations = self.citations.max(other.citations);
+        self.pdf_url = self.pdf_url.take().or_else(|| other.pdf_url.clone());
+        self.open_access |= other.open_access;
+    }
+
+    /// Fill in missing image metadata from the same image found by another engine
+    fn merge_image(&mut self, other: &ResultMetadata) {
+        self.img_src = self.img_src.take().or_else(|| other.img_src.clone());
+        self.thumbnail = self.thumbnail.take().or_else(|| other.thumbnail.clone());
+        self.resolution = self.resolution.take().or_else(|| other.resolution.clone());
+        self.file_size = self.file_size.take().or_else(|| other.file_size.clone());
+    }
+}
+
+/// Postal address of a place
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Address {
+    /// Name of the place (POI, building)
+    pub name: Option<String>,
+    /// Street
+    pub road: Option<String>,
+    /// House number
+    pub house_number: Option<String>,
+    /// Postal code
+    pub postcode: Option<String>,
+    /// City, town or village
+    pub locality: Option<String>,
+    /// Country
+    pub country: Option<String>,
+    /// ISO 3166-1 alpha-2 country code
+    pub country_code: Option<String>,
 }
 
 /// Type of result
@@ -145,6 +292,7 @@ pub enum ResultType {
     Paper,
     File,
     Code,
+    Package,
     Answer,
     InfoBox,
 }
@@ -191,7 +339,7 @@ pub struct InfoBox {
     pub id: String,
     /// Title
     pub title: String,
-    /// Content (HTML)
+    /// Content (plain text)
     pub content: Option<String>,
     /// Image URL
     pub img_src: Option<String>,
@@ -1,16 +1,25 @@
 //! Search execution and orchestration
 
 use super::models::{EngineRef, SearchQuery};
-use crate::engines::{Engine, EngineRegistry, RequestParams};
+use crate::bangs::BangRegistry;
+use crate::engines::{
+    Engine, EngineFailure, EngineRegistry, EngineRequest, EngineResponse, EngineResults,
+    EngineType, RequestParams,
+};
 use crate::network::HttpClient;
+use crate::query::{QueryFilter, TimeRange};
 use crate::results::{EngineError, ResultContainer, Timing};
+use chrono::Utc;
 use futures::future::join_all;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
 
+/// Maximum number of follow-up requests an engine may make per search
+const MAX_FOLLOW_UPS: usize = 3;
+
 /// Search executor that coordinates searching across multiple engines
 pub struct Search {
     /// HTTP client for making requests
@@ -21,6 +30,10 @@ pub struct Search {
     default_timeout: Duration,
     /// Maximum timeout
     max_timeout: Duration,
+    /// External bangs (bundled bangs if not set)
+    bangs: Option<Arc<BangRegistry>>,
+    /// Suspended engines and when their suspension ends
+    suspended: Mutex<HashMap<String, Instant>>,
 }
 
 impl Search {
@@ -31,6 +44,8 @@ impl Search {
             registry,
             default_timeout: Duration::from_secs(5),
             max_timeout: Duration::from_secs(30),
+            bangs: None,
+            suspended: Mutex::new(HashMap::new()),
         }
     }
 
@@ -46,6 +61,65 @@ impl Search {
         self
     }
 
+    /// Set the external bang registry
+    pub fn with_bangs(mut self, bangs: Arc<BangRegistry>) -> Self {
+        self.bangs = Some(bangs);
+        self
+    }
+
+    /// Suspend an engine for `duration`
+    pub fn suspend(&self, engine: &str, duration: Duration) {
+        info!("Suspending engine {} for {:?}", engine, duration);
+        self.suspended
+            .lock()
+            .unwrap()
+            .insert(engine.to_string(), Instant::now() + duration);
+    }
+
+    /// Check whether an engine is currently suspended
+    pub fn is_suspended(&self, engine: &str) -> bool {
+        let mut suspended = self.suspended.lock().unwrap();
+        match suspended.get(engine) {
+            Some(until) if *until > Instant::now() => true,
+            Some(_) => {
+                suspended.remove(engine);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Record a failed engine and suspend it if needed
+    ///
+    /// An [`EngineFailure`] returned by the engine decides the error kind and
+    /// suspends the engine if it says for how long; other errors are
+    /// classified by `fallback` and never suspend it.
+    fn fail(
+        &self,
+        container: &ResultContainer,
+        engine_name: String,
+        e: &anyhow::Error,
+        fallback: EngineError,
+    ) {
+        let (error, suspend_for) = match e.downcast_ref::<EngineFailure>() {
+            Some(failure) => (failure.error.clone(), failure.suspend_for),
+            None => (fallback, None),
+        };
+
+        if let Some(duration) = suspend_for {
+            self.suspend(&engine_name, duration);
+        }
+        container.add_unresponsive(engine_name, error);
+    }
+
+    /// Get the external bang registry
+    fn bangs(&self) -> &BangRegistry {
+        match self.bangs {
+            Some(ref bangs) => bangs,
+            None => crate::bangs::default_registry(),
+        }
+    }
+
     /// Execute a search query across all specified engines
     pub async fn execute(&self, query: &SearchQuery) -> ResultContainer {
         // Get engine weights for scoring
@@ -59,7 +133,7 @@ impl Search {
 
         // Check for external bang redirect
         if let Some(ref bang) = query.external_bang {
-            if let Some(redirect_url) = self.get_external_bang_url(bang, &query.query) {
+            if let Some(redirect_url) = self.bangs().redirect_url(bang, &query.full_query()) {
                 container.set_redirect(redirect_url);
                 return container;
             }
@@ -76,6 +150,23 @@ impl Search {
             .iter()
             .filter_map(|engine_ref| {
                 let engine = self.registry.get(&engine_ref.name)?;
+                // Engines without paging only answer the first page, so
+                // later pages don't repeat their results
+                if query.pageno > 1 && !engine.supports_paging() {
+                    return None;
+                }
+                if engine.max_hops().is_some_and(|max| query.hops >= max) {
+                    debug!("Skipping {} after {} hops", engine.name(), query.hops);
+                    return None;
+                }
+                // Keyword triggers may include quoted phrases
+                if !engine.matches_query(&query.full_query()) {
+                    return None;
+                }
+                if self.is_suspended(engine.name()) {
+                    container.add_unresponsive(engine.name().to_string(), EngineError::Suspended);
+                    return None;
+                }
                 Some(self.search_engine(
                     engine.clone(),
                     engine_ref.clone(),
@@ -97,6 +188,42 @@ impl Search {
         container
     }
 
+    /// Start refreshing the local data of engines in the background
+    ///
+    /// Engines with a [`Engine::refresh_interval`] are refreshed right away
+    /// and then once per interval. Must be called within a Tokio runtime.
+    pub fn spawn_refresh_tasks(&self) {
+        for engine in self.registry.enabled() {
+            let Some(interval) = engine.refresh_interval() else {
+                continue;
+            };
+            let engine = engine.clone();
+            let client = self.client.clone();
+
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    Self::refresh_engine(&client, engine.clone()).await;
+                }
+            });
+        }
+    }
+
+    /// Fetch an engine's refresh requests and hand the responses to it
+    async fn refresh_engine(client: &HttpClient, engine: Arc<dyn Engine>) {
+        let requests = engine.refresh_requests();
+        let responses = join_all(requests.into_iter().map(|r| client.execute(r))).await;
+
+        // Rebuilding an index may be slow, keep it off the async workers
+        let name = engine.name().to_string();
+        match tokio::task::spawn_blocking(move || engine.refresh(responses)).await {
+            Ok(Ok(())) => debug!("Refreshed engine {}", name),
+            Ok(Err(e)) => warn!("Failed to refresh engine {}: {}", name, e),
+            Err(e) => error!("Refresh of engine {} panicked: {}", name, e),
+        }
+    }
+
     /// Search a single engine
     async fn search_engine(
         &self,
@@ -124,17 +251,56 @@ impl Search {
             engine_name, engine_timeout
         );
 
+        // Rewrite operators the engine understands into its own syntax;
+        // the rest are enforced by filtering its results
+        let (engine_query, local_filter) = Self::engine_query(engine.as_ref(), query);
+
         // Build request parameters
         let params = RequestParams {
-            query: query.query.clone(),
+            query: engine_query,
+            full_query: query.full_query(),
             pageno: query.pageno,
             lang: query.lang.clone(),
             safesearch: query.safesearch,
             time_range: query.time_range,
             category: engine_ref.category.clone(),
+            hops: query.hops,
             engine_data: HashMap::new(),
         };
 
+        // Offline engines answer from local data without any request. Local
+        // searches may be slow or hold locks, keep them off the async workers
+        if engine.engine_type() == EngineType::Offline {
+            let search = {
+                let engine = engine.clone();
+                let params = params.clone();
+                tokio::task::spawn_blocking(move || engine.search(&params))
+            };
+            match timeout(engine_timeout, search).await {
+                Ok(Ok(Ok(engine_results))) => Self::add_results(
+                    &container,
+                    &engine_name,
+                    &engine_ref,
+                    engine_results,
+                    &local_filter,
+                    start.elapsed(),
+                ),
+                Ok(Ok(Err(e))) => {
+                    warn!("Local search failed for {}: {}", engine_name, e);
+                    self.fail(&container, engine_name, &e, EngineError::Unknown);
+                }
+                Ok(Err(e)) => {
+                    error!("Local search of {} panicked: {}", engine_name, e);
+                    container.add_unresponsive(engine_name, EngineError::Unknown);
+                }
+                Err(_) => {
+                    warn!("Timeout for engine {}", engine_name);
+                    container.add_unresponsive(engine_name, EngineError::Timeout);
+                }
+            }
+            return;
+        }
+
         // Build the request
         let request = match engine.request(&params) {
             Ok(req) => req,
@@ -145,8 +311,8 @@ impl Search {
             }
         };
 
-        // Execute the request with timeout
-        let result = timeout(engine_timeout, self.client.execute(request)).await;
+        // Execute the request (and any follow-ups) with timeout
+        let result = timeout(engine_timeout, self.fetch(engine.as_ref(), request)).await;
 
         let elapsed = start.elapsed();
 
@@ -154,40 +320,17 @@ impl Search {
             Ok(Ok(response)) => {
                 // Parse the response
                 match engine.response(response) {
-                    Ok(engine_results) => {
-                        let result_count = engine_results.results.len();
-
-                        // Add results to container
-                        for mut result in engine_results.results {
-                            result.category = Some(engine_ref.category.clone());
-                            container.add_result(result);
-                        }
-
-                        // Add answers
-                        for answer in engine_results.answers {
-                            container.add_answer(answer);
-                        }
-
-                        // Add suggestions
-                        for suggestion in engine_results.suggestions {
-                            container.add_suggestion(suggestion);
+                    Ok(mut engine_results) => {
+                        if engine.filters_time_range_locally() {
+                            Self::filter_time_range(&mut engine_results, params.time_range);
                         }
-
-                        // Add infoboxes
-                        for infobox in engine_results.infoboxes {
-                            container.add_infobox(infobox);
-                        }
-
-                        // Record timing
-                        container.add_timing(Timing {
-                            engine: engine_name.clone(),
-                            time_ms: elapsed.as_millis() as u64,
-                            result_count,
-                        });
-
-                        debug!(
-                            "Engine {} returned {} results in {:?}",
-                            engine_name, result_count, elapsed
+                        Self::add_results(
+                            &container,
+                            &engine_name,
+                            &engine_ref,
+                            engine_results,
+                            &local_filter,
+                            elapsed,
                         );
                     }
                     Err(e) => {
@@ -197,7 +340,7 @@ impl Search {
                         } else {
                             EngineError::ParseError
                         };
-                        container.add_unresponsive(engine_name, error);
+                        self.fail(&container, engine_name, &e, error);
                     }
                 }
             }
@@ -212,7 +355,7 @@ impl Search {
                 } else {
                     EngineError::NetworkError
                 };
-                container.add_unresponsive(engine_name, error);
+                self.fail(&container, engine_name, &e, error);
             }
             Err(_) => {
                 warn!("Timeout for engine {}", engine_name);
@@ -221,37 +364,107 @@ impl Search {
         }
     }
 
-    /// Get redirect URL for external bang
-    fn get_external_bang_url(&self, bang: &str, query: &str) -> Option<String> {
-        let encoded_query = urlencoding::encode(query);
-        match bang {
-            "g" => Some(format!("https://www.google.com/search?q={}", encoded_query)),
-            "yt" => Some(format!(
-                "https://www.youtube.com/results?search_query={}",
-                encoded_query
-            )),
-            "w" | "wp" => Some(format!(
-                "https://en.wikipedia.org/wiki/Special:Search?search={}",
-                encoded_query
-            )),
-            "gh" => Some(format!("https://github.com/search?q={}", encoded_query)),
-            "so" => Some(format!(
-                "https://stackoverflow.com/search?q={}",
-                encoded_query
-            )),
-            "ddg" => Some(format!("https://duckduckgo.com/?q={}", encoded_query)),
-            "amazon" => Some(format!("https://www.amazon.com/s?k={}", encoded_query)),
-            "imdb" => Some(format!("https://www.imdb.com/find?q={}", encoded_query)),
-            _ => None,
+    /// Add an engine's results, answers, suggestions and infoboxes
+    fn add_results(
+        container: &ResultContainer,
+        engine_name: &str,
+        engine_ref: &EngineRef,
+        engine_results: EngineResults,
+        local_filter: &QueryFilter,
+        elapsed: Duration,
+    ) {
+        let mut result_count = 0;
+
+        // Add results to container
+        for mut result in engine_results.results {
+            result.category = Some(engine_ref.category.clone());
+            if container.add_result_filtered(result, local_filter) {
+                result_count += 1;
+            }
+        }
+
+        // Add answers
+        for answer in engine_results.answers {
+            container.add_answer(answer);
+        }
+
+        // Add suggestions
+        for suggestion in engine_results.suggestions {
+            container.add_suggestion(suggestion);
         }
+
+        // Add infoboxes
+        for infobox in engine_results.infoboxes {
+            container.add_infobox(infobox);
+        }
+
+        // Record timing
+        container.add_timing(Timing {
+            engine: engine_name.to_string(),
+            time_ms: elapsed.as_millis() as u64,
+            result_count,
+        });
+
+        debug!(
+            "Engine {} returned {} results in {:?}",
+            engine_name, result_count, elapsed
+        );
+    }
+
+    /// Drop results published before the time range
+    fn filter_time_range(engine_results: &mut EngineResults, time_range: Option<TimeRange>) {
+        let Some(time_range) = time_range else {
+            return;
+        };
+        let since = Utc::now() - time_range.duration();
+        engine_results
+            .results
+            .retain(|result| result.metadata.published_date.is_some_and(|d| d >= since));
+    }
+
+    /// Execute an engine request and the follow-up requests it asks for
+    async fn fetch(
+        &self,
+        engine: &dyn Engine,
+        request: EngineRequest,
+    ) -> anyhow::Result<EngineResponse> {
+        let mut response = self.client.execute(request).await?;
+        for _ in 0..MAX_FOLLOW_UPS {
+            match engine.next_request(&response)? {
+                Some(next) => response = self.client.execute(next).await?,
+                None => break,
+            }
+        }
+        Ok(response)
+    }
+
+    /// Build the query string for an engine and the filter to enforce locally
+    fn engine_query(engine: &dyn Engine, query: &SearchQuery) -> (String, QueryFilter) {
+        let (native, mut local) = query.filter.partition(|op| engine.supports_operator(op));
+
+        let mut terms = vec![query.query.as_str()];
+        terms.extend(local.fallback_terms());
+        let terms = terms
+            .into_iter()
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let (engine_query, unexpressed) = engine.format_query(&terms, &native.operators);
+        local.operators.extend(unexpressed);
+        (engine_query, local)
     }
 
     /// Execute search and return results for a specific category
+    ///
+    /// Private engines are skipped; callers with access tokens should build
+    /// a [`SearchQuery`] from the registry visible to them instead.
     pub async fn search_category(&self, query: &str, category: &str, page: u32) -> ResultContainer {
         let engines = self.registry.get_by_category(category);
 
         let engine_refs: Vec<EngineRef> = engines
             .iter()
+            .filter(|e| !self.registry.is_private(e.name()))
             .map(|e| EngineRef::new(e.name(), category))
             .collect();
 
@@ -265,7 +478,10 @@ impl Search {
             timeout_limit: None,
             external_bang: None,
             redirect_to_first: false,
+            filter: QueryFilter::default(),
+            hops: 0,
             engine_data: HashMap::new(),
+            typed_query: String::new(),
         };
 
         self.execute(&search_query).await
@@ -287,4 +503,462 @@ mod tests {
 
         assert_eq!(results.result_count(), 0); // No engines registered
     }
+
+    #[tokio::test]
+    async fn test_external_bang_redirect() {
+        let client = HttpClient::new().unwrap();
+        let mut bangs = BangRegistry::new();
+        bangs.add(crate::bangs::Bang::new(
+            "intra",
+            "Intranet",
+            "https://intranet.example/search?q={{{s}}}",
+        ));
+        let search =
+            Search::new(client, Arc::new(EngineRegistry::new())).with_bangs(Arc::new(bangs));
+
+        let mut query = SearchQuery::simple("expense policy");
+        query.external_bang = Some("intra".to_string());
+        let results = search.execute(&query).await;
+
+        assert_eq!(
+            results.get_redirect(),
+            Some("https://intranet.example/search?q=expense+policy".to_string())
+        );
+        // Search operators are passed on with the terms
+        let mut query = SearchQuery::from_parsed(
+            crate::query::ParsedQuery::parse(r#"expense policy site:hr.example "travel costs""#),
+            vec![],
+        );
+        query.external_bang = Some("intra".to_string());
+        let results = search.execute(&query).await;
+        assert_eq!(
+            results.get_redirect(),
+            Some(
+                "https://intranet.example/search?q=expense+policy+site%3Ahr.example+%22travel+costs%22"
+                    .to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_engine_suspension() {
+        let client = HttpClient::new().unwrap();
+        let registry =
+            crate::engines::EngineLoader::load(&crate::config::Settings::default()).unwrap();
+        let search = Search::new(client, Arc::new(registry));
+
+        search.suspend("github", Duration::from_secs(60));
+        assert!(search.is_suspended("github"));
+        search.suspend("wikipedia", Duration::ZERO);
+        assert!(!search.is_suspended("wikipedia"));
+
+        let mut query = SearchQuery::simple("tokio");
+        query.engine_refs = vec![EngineRef::new("github", "it")];
+        let results = search.execute(&query).await;
+
+        let unresponsive = results.get_unresponsive();
+        assert_eq!(unresponsive.len(), 1);
+        assert_eq!(unresponsive[0].error, EngineError::Suspended);
+    }
+
+    #[tokio::test]
+    async fn test_offline_engine() {
+        let config: crate::config::EngineConfig = serde_yaml::from_str(
+            "name: news\nengine: feed\nfeeds:\n  - https://blog.example.org/feed.xml",
+        )
+        .unwrap();
+        let mut feeds = crate::engines::feed::Feeds::new();
+        feeds.init(&config).unwrap();
+        feeds
+            .refresh(vec![Ok(EngineResponse::new(
+                200,
+                r#"<rss version="2.0"><channel><item><title>Offsite notes</title><link>https://blog.example.org/offsite</link></item></channel></rss>"#,
+            ))])
+            .unwrap();
+
+        let mut registry = EngineRegistry::new();
+        registry.register(Arc::new(feeds), config);
+        let search = Search::new(HttpClient::new().unwrap(), Arc::new(registry));
+
+        let mut query = SearchQuery::simple("offsite");
+        query.engine_refs = vec![EngineRef::new("news", "news")];
+        let results = search.execute(&query).await;
+
+        assert_eq!(results.result_count(), 1);
+        assert!(results.get_unresponsive().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_offline_engine_timeout() {
+        struct Slow;
+
+        impl Engine for Slow {
+            fn name(&self) -> &str {
+                "slow"
+            }
+
+            fn engine_type(&self) -> EngineType {
+                EngineType::Offline
+            }
+
+            fn search(&self, _params: &RequestParams) -> anyhow::Result<EngineResults> {
+                std::thread::sleep(Duration::from_millis(500));
+                Ok(EngineResults::new())
+            }
+
+            fn request(&self, _params: &RequestParams) -> anyhow::Result<EngineRequest> {
+                unreachable!()
+            }
+
+            fn response(&self, _response: EngineResponse) -> anyhow::Result<EngineResults> {
+                unreachable!()
+            }
+        }
+
+        let config: crate::config::EngineConfig =
+            serde_yaml::from_str("name: slow\nengine: slow").unwrap();
+        let mut registry = EngineRegistry::new();
+        registry.register(Arc::new(Slow), config);
+        let search = Search::new(HttpClient::new().unwrap(), Arc::new(registry));
+
+        let mut query = SearchQuery::simple("anything");
+        query.engine_refs = vec![EngineRef::new("slow", "general")];
+        query.timeout_limit = Some(0.05);
+        let start = Instant::now();
+        let results = search.execute(&query).await;
+
+        assert!(start.elapsed() < Duration::from_millis(400));
+        let unresponsive = results.get_unresponsive();
+        assert_eq!(unresponsive.len(), 1);
+        assert_eq!(unresponsive[0].error, EngineError::Timeout);
+    }
+
+    #[tokio::test]
+    async fn test_search_server_federation() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let meili = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/indexes/handbook/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"hits": [
+                    {"url": "https://intra.example.org/deploy", "title": "Deploy guide"},
+                    {"url": "https://intra.example.org/oncall", "title": "On-call"}
+                ]}"#,
+            ))
+            .mount(&meili)
+            .await;
+        let solr = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/solr/kb/select"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"response": {"numFound": 1, "docs": [
+                    {"url": "https://intra.example.org/deploy", "title": ["Deploy guide"]}
+                ]}}"#,
+            ))
+            .mount(&solr)
+            .await;
+
+        let settings = crate::config::Settings {
+            engines: serde_yaml::from_str(&format!(
+                "- {{name: handbook, engine: meilisearch, base_url: '{}', index: handbook}}\n\
+                 - {{name: kb, engine: solr, base_url: '{}/solr', index: kb}}",
+                meili.uri(),
+                solr.uri()
+            ))
+            .unwrap(),
+            ..Default::default()
+        };
+        let registry = crate::engines::EngineLoader::load(&settings).unwrap();
+        let search = Search::new(HttpClient::new().unwrap(), Arc::new(registry));
+
+        let mut query = SearchQuery::simple("deploy");
+        query.engine_refs = vec![
+            EngineRef::new("handbook", "general"),
+            EngineRef::new("kb", "general"),
+        ];
+        let results = search.execute(&query).await.get_ordered_results();
+
+        // The document found by both servers is merged and ranked first
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://intra.example.org/deploy");
+        assert_eq!(results[0].engines.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_searx_hop_limit() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // The remote instance must only be asked once, by the first hop
+        let remote = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .and(header("X-Searx-Hops", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"results": [{"url": "https://eu.example.org/page", "title": "Page",
+                    "engines": ["brave"]}]}"#,
+            ))
+            .expect(1)
+            .mount(&remote)
+            .await;
+
+        let settings = crate::config::Settings {
+            engines: serde_yaml::from_str(&format!(
+                "- {{name: eu, engine: searx, base_url: '{}'}}",
+                remote.uri()
+            ))
+            .unwrap(),
+            ..Default::default()
+        };
+        let registry = crate::engines::EngineLoader::load(&settings).unwrap();
+        let search = Search::new(HttpClient::new().unwrap(), Arc::new(registry));
+
+        let mut query = SearchQuery::simple("page");
+        query.add_engine("eu", "general");
+        let results = search.execute(&query).await.get_ordered_results();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].engines.contains("brave"));
+
+        // A search forwarded by another instance is not forwarded again
+        query.hops = 1;
+        let results = search.execute(&query).await;
+        assert_eq!(results.result_count(), 0);
+        assert!(results.get_unresponsive().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_engines_without_paging() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        struct FirstPageOnly {
+            url: String,
+        }
+
+        impl Engine for FirstPageOnly {
+            fn name(&self) -> &str {
+                "instant"
+            }
+
+            fn supports_paging(&self) -> bool {
+                false
+            }
+
+            fn request(&self, _params: &RequestParams) -> anyhow::Result<EngineRequest> {
+                Ok(EngineRequest::get(&self.url))
+            }
+
+            fn response(&self, _response: EngineResponse) -> anyhow::Result<EngineResults> {
+                Ok(EngineResults::with_results(vec![
+                    crate::results::Result::new(
+                        "https://example.org/".to_string(),
+                        "Example".to_string(),
+                        "instant".to_string(),
+                    ),
+                ]))
+            }
+        }
+
+        // Only the first page reaches an engine without paging
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config: crate::config::EngineConfig =
+            serde_yaml::from_str("name: instant\nengine: instant").unwrap();
+        let mut registry = EngineRegistry::new();
+        registry.register(Arc::new(FirstPageOnly { url: server.uri() }), config);
+        let search = Search::new(HttpClient::new().unwrap(), Arc::new(registry));
+
+        let mut query = SearchQuery::simple("hello");
+        query.add_engine("instant", "general");
+        assert_eq!(search.execute(&query).await.result_count(), 1);
+
+        let query = query.with_page(2);
+        let results = search.execute(&query).await;
+        assert_eq!(results.result_count(), 0);
+        assert!(results.get_unresponsive().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_keyword_triggered_engine() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Only the translation queries reach the server
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/translate"))
+            .and(body_partial_json(
+                serde_json::json!({"q": "good morning", "target": "de"}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{"translatedText": "Guten Morgen"}"#),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let settings = crate::config::Settings {
+            engines: serde_yaml::from_str(&format!(
+                "- {{name: libretranslate, engine: libretranslate, base_url: '{}'}}",
+                server.uri()
+            ))
+            .unwrap(),
+            ..Default::default()
+        };
+        let registry = crate::engines::EngineLoader::load(&settings).unwrap();
+        let search = Search::new(HttpClient::new().unwrap(), Arc::new(registry));
+
+        let mut query = SearchQuery::simple("en-de good morning");
+        query.add_engine("libretranslate", "general");
+        let answers = search.execute(&query).await.get_answers();
+        assert_eq!(answers.len(), 1);
+        assert_eq!(answers[0].answer, "Guten Morgen");
+
+        // Quoted text still triggers the engine
+        let mut quoted = SearchQuery::from_parsed(
+            crate::query::ParsedQuery::parse(r#"translate "good morning" to de"#),
+            vec![],
+        );
+        quoted.add_engine("libretranslate", "general");
+        let answers = search.execute(&quoted).await.get_answers();
+        assert_eq!(answers.len(), 1);
+
+        query.query = "good morning".to_string();
+        let results = search.execute(&query).await;
+        assert!(results.get_answers().is_empty());
+        assert!(results.get_unresponsive().is_empty());
+    }
+
+    #[test]
+    fn test_local_time_range_filter() {
+        use crate::results::Result;
+
+        let dated = |url: &str, days_ago: i64| {
+            let mut result = Result::new(url.to_string(), url.to_string(), "lobsters".to_string());
+            result.metadata.published_date = Some(Utc::now() - chrono::Duration::days(days_ago));
+            result
+        };
+        let mut engine_results = EngineResults::with_results(vec![
+            dated("https://a.example.org", 2),
+            dated("https://b.example.org", 20),
+            Result::new(
+                "https://c.example.org".to_string(),
+                "Undated".to_string(),
+                "lobsters".to_string(),
+            ),
+        ]);
+
+        Search::filter_time_range(&mut engine_results, None);
+        assert_eq!(engine_results.results.len(), 3);
+        Search::filter_time_range(&mut engine_results, Some(TimeRange::Week));
+        let urls: Vec<&str> = engine_results
+            .results
+            .iter()
+            .map(|r| r.url.as_str())
+            .collect();
+        assert_eq!(urls, vec!["https://a.example.org"]);
+    }
+
+    #[test]
+    fn test_failure_classification() {
+        let client = HttpClient::new().unwrap();
+        let search = Search::new(client, Arc::new(EngineRegistry::new()));
+        let container = ResultContainer::new();
+
+        // Engines that report a suspension time are suspended for it
+        let failure =
+            EngineFailure::new(EngineError::TooManyRequests).suspend_for(Duration::from_secs(42));
+        search.fail(
+            &container,
+            "github".to_string(),
+            &failure.into(),
+            EngineError::ParseError,
+        );
+        assert!(search.is_suspended("github"));
+        assert_eq!(
+            container.get_unresponsive()[0].error,
+            EngineError::TooManyRequests
+        );
+
+        // Failures without one, and network or HTTP errors, don't suspend it
+        search.fail(
+            &container,
+            "stackexchange".to_string(),
+            &EngineFailure::new(EngineError::TooManyRequests).into(),
+            EngineError::ParseError,
+        );
+        assert!(!search.is_suspended("stackexchange"));
+        search.fail(
+            &container,
+            "wikipedia".to_string(),
+            &anyhow::anyhow!("connection reset"),
+            EngineError::NetworkError,
+        );
+        assert!(!search.is_suspended("wikipedia"));
+        search.fail(
+            &container,
+            "bing".to_string(),
+            &anyhow::anyhow!("HTTP error: 503"),
+            EngineError::ServerError,
+        );
+        assert!(!search.is_suspended("bing"));
+        assert_eq!(container.get_unresponsive().len(), 4);
+    }
+
+    #[test]
+    fn test_engine_query_rewrite() {
+        let query = SearchQuery::from_parsed(
+            crate::query::ParsedQuery::parse(r#"serde site:docs.rs "zero copy""#),
+            vec![],
+        );
+
+        // Google understands every operator natively
+        let google = crate::engines::google::Google::new();
+        let (q, local) = Search::engine_query(&google, &query);
+        assert_eq!(q, r#"serde site:docs.rs "zero copy""#);
+        assert!(local.is_empty());
+
+        // Wikipedia gets plain terms and results are filtered locally
+        let wikipedia = crate::engines::wikipedia::Wikipedia::new();
+        let (q, local) = Search::engine_query(&wikipedia, &query);
+        assert_eq!(q, "serde zero copy");
+        assert_eq!(local.operators.len(), 2);
+
+        // Operators an engine can't express after all are filtered locally
+        let query = SearchQuery::from_parsed(crate::query::ParsedQuery::parse("-vision"), vec![]);
+        let arxiv = crate::engines::arxiv::ArXiv::new();
+        let (_, local) = Search::engine_query(&arxiv, &query);
+        assert_eq!(
+            local.operators,
+            vec![crate::query::QueryOperator::Exclude("vision".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_engine_query_field_prefixes() {
+        let arxiv = crate::engines::arxiv::ArXiv::new();
+        let search_query = |q: &str| {
+            let query = SearchQuery::from_parsed(crate::query::ParsedQuery::parse(q), vec![]);
+            let (engine_query, _) = Search::engine_query(&arxiv, &query);
+            let request = arxiv.request(&RequestParams::new(engine_query)).unwrap();
+            request.params["search_query"].clone()
+        };
+
+        assert_eq!(
+            search_query("au:hinton cat:cs.LG"),
+            "au:hinton AND cat:cs.LG"
+        );
+        assert_eq!(
+            search_query("au:hinton intitle:capsules"),
+            "au:hinton AND ti:capsules"
+        );
+        assert_eq!(search_query("capsule networks"), "all:capsule networks");
+    }
 }
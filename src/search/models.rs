@@ -1,6 +1,6 @@
 //! Search query and related data models
 
-use crate::query::{ParsedQuery, TimeRange};
+use crate::query::{ParsedQuery, QueryFilter, TimeRange};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -43,9 +43,19 @@ pub struct SearchQuery {
     pub external_bang: Option<String>,
     /// Redirect to first result
     pub redirect_to_first: bool,
+    /// Search operators to apply (natively or by post-filtering)
+    #[serde(default)]
+    pub filter: QueryFilter,
+    /// Number of instances the search was already forwarded through
+    #[serde(default)]
+    pub hops: u32,
     /// Per-engine state data
     #[serde(default)]
     pub engine_data: HashMap<String, serde_json::Value>,
+    /// Query as typed, operators in place, without bangs and modifiers
+    /// (empty if unknown)
+    #[serde(default)]
+    pub typed_query: String,
 }
 
 impl SearchQuery {
@@ -63,6 +73,9 @@ impl SearchQuery {
             default_engines
         };
 
+        let typed_query =
+            QueryFilter::in_place(&parsed.raw_query, &parsed.query).unwrap_or_default();
+
         Self {
             query: parsed.query,
             engine_refs,
@@ -77,7 +90,10 @@ impl SearchQuery {
             timeout_limit: parsed.timeout,
             external_bang: parsed.external_bang,
             redirect_to_first: parsed.redirect_to_first,
+            filter: parsed.filter,
+            hops: 0,
             engine_data: HashMap::new(),
+            typed_query,
         }
     }
 
@@ -93,7 +109,10 @@ impl SearchQuery {
             timeout_limit: None,
             external_bang: None,
             redirect_to_first: false,
+            filter: QueryFilter::default(),
+            hops: 0,
             engine_data: HashMap::new(),
+            typed_query: String::new(),
         }
     }
 
@@ -137,9 +156,30 @@ impl SearchQuery {
         self.timeout_limit.map(|t| t.min(max)).unwrap_or(default)
     }
 
+    /// Set search operators
+    pub fn with_filter(mut self, filter: QueryFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
     /// Check if query is empty
     pub fn is_empty(&self) -> bool {
-        self.query.trim().is_empty()
+        self.query.trim().is_empty() && self.filter.is_empty()
+    }
+
+    /// Query text with its search operators, for passing the search on as typed
+    ///
+    /// Operators stay where they were typed if the query was parsed and
+    /// follow the terms otherwise.
+    pub fn full_query(&self) -> String {
+        if !self.typed_query.is_empty() {
+            return self.typed_query.clone();
+        }
+        [self.query.clone(), self.filter.to_string()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
     }
 
     /// Get categories from engine refs
@@ -191,6 +231,25 @@ mod tests {
         assert_eq!(query.pageno, 3);
     }
 
+    #[test]
+    fn test_full_query() {
+        let parse = |raw: &str| SearchQuery::from_parsed(ParsedQuery::parse(raw), vec![]);
+
+        // Operators stay where they were typed, bangs and modifiers don't
+        let query = parse(r#"translate "good morning" to de :fr !day"#);
+        assert_eq!(query.query, "translate to de");
+        assert_eq!(query.full_query(), r#"translate "good morning" to de"#);
+        assert_eq!(
+            parse("rust -site:reddit.com async").full_query(),
+            "rust -site:reddit.com async"
+        );
+
+        // Unparsed queries have their operators after the terms
+        let query =
+            SearchQuery::simple("rust async").with_filter(QueryFilter::extract("site:docs.rs").1);
+        assert_eq!(query.full_query(), "rust async site:docs.rs");
+    }
+
     #[test]
     fn test_engine_refs() {
         let mut query = SearchQuery::simple("test");
@@ -1,11 +1,13 @@
 //! HTTP request handlers
 
 use super::state::AppState;
+use crate::engines::searx::HOPS_HEADER;
 use crate::query::ParsedQuery;
+use crate::results::ResultType;
 use crate::search::{EngineRef, SearchQuery};
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    extract::{Form, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::{Html, IntoResponse, Redirect, Response},
     Json,
 };
@@ -32,6 +34,8 @@ pub struct SearchParams {
     pub pageno: Option<u32>,
     /// Output format
     pub format: Option<String>,
+    /// Result order: `date` for the most recent first, by score otherwise
+    pub sort: Option<String>,
 }
 
 /// Search results response for JSON format
@@ -56,6 +60,122 @@ pub struct ResultResponse {
     pub score: f64,
     pub category: Option<String>,
     pub thumbnail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub img_src: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_date: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub views: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iframe_src: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_src: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boundingbox: Option<[f64; 4]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<crate::results::Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub osm_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub osm_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloads: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stars: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub votes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer_count: Option<u64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub accepted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boosts: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citations: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf_url: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub open_access: bool,
+}
+
+/// Header carrying access tokens for private engines
+pub const TOKEN_HEADER: &str = "x-searx-token";
+
+/// Preferences cookie carrying access tokens for private engines
+pub const TOKEN_COOKIE: &str = "tokens";
+
+/// Collect access tokens from the token header and preferences cookie
+///
+/// Both hold a comma-separated list.
+pub fn access_tokens(headers: &HeaderMap) -> Vec<String> {
+    let from_header = headers
+        .get_all(TOKEN_HEADER)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let from_cookie = headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .filter(|(name, _)| *name == TOKEN_COOKIE)
+        .filter_map(|(_, value)| urlencoding::decode(value).ok())
+        .map(|v| v.into_owned());
+
+    let mut tokens: Vec<String> = from_header
+        .chain(from_cookie)
+        .flat_map(|v| {
+            v.split(',')
+                .map(|t| t.trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|t| !t.is_empty())
+        .collect();
+    tokens.sort();
+    tokens.dedup();
+    tokens
 }
 
 /// Home page handler
@@ -64,7 +184,9 @@ pub async fn index(State(state): State<AppState>) -> impl IntoResponse {
     ctx.insert("instance_name", state.instance_name());
     ctx.insert(
         "categories",
-        &["general", "images", "videos", "news", "it", "science"],
+        &[
+            "general", "images", "videos", "news", "maps", "it", "science",
+        ],
     );
 
     match state.templates.render_with_context("index.html", &ctx) {
@@ -77,39 +199,47 @@ pub async fn index(State(state): State<AppState>) -> impl IntoResponse {
 }
 
 /// Search handler
-pub async fn search(State(state): State<AppState>, Query(params): Query<SearchParams>) -> Response {
+pub async fn search(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<SearchParams>,
+) -> Response {
     // Check for query
     let raw_query = match params.q {
         Some(q) if !q.trim().is_empty() => q,
         _ => return Redirect::to("/").into_response(),
     };
 
+    // Private engines only exist for users holding a matching token
+    let registry = state.registry_for(&access_tokens(&headers));
+
     // Parse query
-    let parsed = ParsedQuery::parse(&raw_query);
+    let parsed = ParsedQuery::parse_with(&raw_query, &registry, &state.bangs);
 
     // Build engine refs from categories or engines
     let engine_refs = if let Some(ref engines) = params.engines {
         engines
             .split(',')
-            .map(|e| EngineRef::new(e.trim(), "general"))
+            .filter_map(|e| registry.resolve_name(e.trim()))
+            .map(|e| EngineRef::new(e, "general"))
             .collect()
     } else {
-        let categories = params
-            .categories
-            .as_deref()
-            .unwrap_or("general")
-            .split(',')
-            .map(|c| c.trim())
-            .collect::<Vec<_>>();
+        // Explicit categories win over category bangs in the query
+        let categories = match params.categories {
+            Some(ref categories) => categories
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .collect::<Vec<_>>(),
+            None => parsed.effective_categories(&state.settings.search.default_categories),
+        };
 
         categories
             .iter()
             .flat_map(|cat| {
-                state
-                    .registry
+                registry
                     .get_by_category(cat)
                     .into_iter()
-                    .map(|e| EngineRef::new(e.name(), *cat))
+                    .map(move |e| EngineRef::new(e.name(), cat))
             })
             .collect()
     };
@@ -126,6 +256,13 @@ pub async fn search(State(state): State<AppState>, Query(params): Query<SearchPa
         search_query.safesearch = safesearch;
     }
 
+    // Searches forwarded by other instances carry their hop count
+    search_query.hops = headers
+        .get(HOPS_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0);
+
     // Execute search
     let results = state.search.execute(&search_query).await;
 
@@ -142,10 +279,15 @@ pub async fn search(State(state): State<AppState>, Query(params): Query<SearchPa
         }
     }
 
+    let ordered_results = || match params.sort.as_deref() {
+        Some("date") => results.get_results_by_date(),
+        _ => results.get_ordered_results(),
+    };
+
     // Format response based on requested format
     match params.format.as_deref() {
         Some("json") => {
-            let ordered = results.get_ordered_results();
+            let ordered = ordered_results();
             let response = SearchResponse {
                 query: raw_query,
                 number_of_results: ordered.len(),
@@ -160,6 +302,44 @@ pub async fn search(State(state): State<AppState>, Query(params): Query<SearchPa
                         score: r.score,
                         category: r.category,
                         thumbnail: r.metadata.thumbnail,
+                        img_src: r.metadata.img_src,
+                        resolution: r.metadata.resolution,
+                        file_size: r.metadata.file_size,
+                        author: r.metadata.author,
+                        published_date: r.metadata.published_date,
+                        duration: r.metadata.duration,
+                        views: r.metadata.views,
+                        iframe_src: r.metadata.iframe_src,
+                        audio_src: r.metadata.audio_src,
+                        snippet: r.metadata.snippet,
+                        latitude: r.metadata.latitude,
+                        longitude: r.metadata.longitude,
+                        boundingbox: r.metadata.boundingbox,
+                        address: r.metadata.address,
+                        osm_type: r.metadata.osm_type,
+                        osm_id: r.metadata.osm_id,
+                        version: r.metadata.version,
+                        downloads: r.metadata.downloads,
+                        license: r.metadata.license,
+                        repository: r.metadata.repository,
+                        updated_date: r.metadata.updated_date,
+                        stars: r.metadata.stars,
+                        language: r.metadata.language,
+                        votes: r.metadata.votes,
+                        answer_count: r.metadata.answer_count,
+                        accepted: r.metadata.accepted,
+                        comment_count: r.metadata.comment_count,
+                        comments_url: r.metadata.comments_url,
+                        boosts: r.metadata.boosts,
+                        instance: r.metadata.instance,
+                        tags: r.metadata.tags,
+                        authors: r.metadata.authors,
+                        journal: r.metadata.journal,
+                        doi: r.metadata.doi,
+                        comment: r.metadata.comment,
+                        citations: r.metadata.citations,
+                        pdf_url: r.metadata.pdf_url,
+                        open_access: r.metadata.open_access,
                     })
                     .collect(),
                 answers: results
@@ -172,7 +352,11 @@ pub async fn search(State(state): State<AppState>, Query(params): Query<SearchPa
                     .into_iter()
                     .map(|s| s.text)
                     .collect(),
-                infoboxes: vec![],
+                infoboxes: results
+                    .get_infoboxes()
+                    .into_iter()
+                    .filter_map(|i| serde_json::to_value(i).ok())
+                    .collect(),
                 unresponsive_engines: results
                     .get_unresponsive()
                     .into_iter()
@@ -182,7 +366,7 @@ pub async fn search(State(state): State<AppState>, Query(params): Query<SearchPa
             Json(response).into_response()
         }
         Some("csv") => {
-            let ordered = results.get_ordered_results();
+            let ordered = ordered_results();
             let mut csv = String::from("title,url,content,engine\n");
             for r in ordered {
                 csv.push_str(&format!(
@@ -197,11 +381,17 @@ pub async fn search(State(state): State<AppState>, Query(params): Query<SearchPa
         }
         _ => {
             // HTML response
-            let ordered = results.get_ordered_results();
+            let ordered = ordered_results();
 
             let mut ctx = Context::new();
             ctx.insert("instance_name", state.instance_name());
             ctx.insert("query", &raw_query);
+            ctx.insert("page_result_count", &ordered.len());
+            // Images are shown as a grid, the other results below it
+            let (image_results, ordered): (Vec<_>, Vec<_>) = ordered
+                .into_iter()
+                .partition(|r| r.result_type == ResultType::Image);
+            ctx.insert("image_results", &image_results);
             ctx.insert("results", &ordered);
             ctx.insert("answers", &results.get_answers());
             ctx.insert("suggestions", &results.get_suggestions());
@@ -212,7 +402,9 @@ pub async fn search(State(state): State<AppState>, Query(params): Query<SearchPa
             ctx.insert("pageno", &search_query.pageno);
             ctx.insert(
                 "categories",
-                &["general", "images", "videos", "news", "it", "science"],
+                &[
+                    "general", "images", "videos", "news", "maps", "it", "science",
+                ],
             );
 
             match state.templates.render_with_context("search.html", &ctx) {
@@ -227,11 +419,13 @@ pub async fn search(State(state): State<AppState>, Query(params): Query<SearchPa
 }
 
 /// About page handler
-pub async fn about(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn about(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    let registry = state.registry_for(&access_tokens(&headers));
+
     let mut ctx = Context::new();
     ctx.insert("instance_name", state.instance_name());
     ctx.insert("version", crate::VERSION);
-    ctx.insert("engines", &state.registry.names());
+    ctx.insert("engines", &registry.names());
 
     match state.templates.render_with_context("about.html", &ctx) {
         Ok(html) => Html(html),
@@ -242,13 +436,55 @@ pub async fn about(State(state): State<AppState>) -> impl IntoResponse {
     }
 }
 
+/// Submitted preferences
+#[derive(Debug, Deserialize)]
+pub struct PreferencesForm {
+    /// Access tokens for private engines (comma-separated)
+    pub tokens: Option<String>,
+}
+
 /// Preferences page handler
-pub async fn preferences(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn preferences(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    render_preferences(&state, &headers).into_response()
+}
+
+/// Save submitted preferences
+///
+/// Tokens are only taken from a POST body, so a link can't replace them.
+pub async fn save_preferences(
+    State(state): State<AppState>,
+    mut headers: HeaderMap,
+    Form(form): Form<PreferencesForm>,
+) -> Response {
+    let Some(tokens) = form.tokens else {
+        return render_preferences(&state, &headers).into_response();
+    };
+
+    // Saving tokens replaces the cookie and applies them to this page too
+    headers.remove(header::COOKIE);
+    if let Ok(value) = format!("{}={}", TOKEN_COOKIE, urlencoding::encode(&tokens)).parse() {
+        headers.insert(header::COOKIE, value);
+    }
+    let html = render_preferences(&state, &headers);
+
+    let cookie = format!(
+        "{}={}; Path=/; Max-Age=157680000; HttpOnly; SameSite=Lax",
+        TOKEN_COOKIE,
+        urlencoding::encode(&tokens)
+    );
+    ([(header::SET_COOKIE, cookie)], html).into_response()
+}
+
+fn render_preferences(state: &AppState, headers: &HeaderMap) -> Html<String> {
+    let tokens = access_tokens(headers);
+    let registry = state.registry_for(&tokens);
+
     let mut ctx = Context::new();
     ctx.insert("instance_name", state.instance_name());
     ctx.insert("themes", &state.settings.ui.themes);
-    ctx.insert("engines", &state.registry.names());
-    ctx.insert("categories", &state.registry.category_names());
+    ctx.insert("engines", &registry.names());
+    ctx.insert("categories", &registry.category_names());
+    ctx.insert("tokens", &tokens.join(","));
 
     match state
         .templates
@@ -263,11 +499,13 @@ pub async fn preferences(State(state): State<AppState>) -> impl IntoResponse {
 }
 
 /// Stats page handler
-pub async fn stats(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn stats(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    let registry = state.registry_for(&access_tokens(&headers));
+
     let mut ctx = Context::new();
     ctx.insert("instance_name", state.instance_name());
-    ctx.insert("engines", &state.registry.names());
-    ctx.insert("engine_count", &state.registry.len());
+    ctx.insert("engines", &registry.names());
+    ctx.insert("engine_count", &registry.len());
 
     match state.templates.render_with_context("stats.html", &ctx) {
         Ok(html) => Html(html),
@@ -326,6 +564,160 @@ pub async fn autocomplete(
     ])
 }
 
+/// Query parameters for the bang listing
+#[derive(Debug, Deserialize)]
+pub struct BangsParams {
+    /// Trigger prefix or name to search for
+    pub q: Option<String>,
+    /// Maximum number of bangs to return
+    pub limit: Option<usize>,
+}
+
+/// Bang listing handler
+pub async fn bangs(
+    State(state): State<AppState>,
+    Query(params): Query<BangsParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(50).min(500);
+    let matches = state.bangs.search(params.q.as_deref().unwrap_or_default());
+
+    Json(serde_json::json!({
+        "total": matches.len(),
+        "bangs": matches.into_iter().take(limit).collect::<Vec<_>>(),
+    }))
+}
+
+/// Instance description returned by `/config`
+#[derive(Debug, Serialize)]
+pub struct ConfigResponse {
+    pub instance_name: String,
+    pub version: String,
+    pub public_instance: bool,
+    pub default_locale: String,
+    pub default_theme: String,
+    pub themes: Vec<String>,
+    pub results_per_page: u32,
+    pub safe_search: u8,
+    pub default_lang: String,
+    pub max_page: u32,
+    pub autocomplete: Option<String>,
+    pub autocomplete_backends: Vec<String>,
+    pub formats: Vec<String>,
+    pub default_categories: Vec<String>,
+    pub categories: Vec<String>,
+    pub engines: Vec<EngineInfo>,
+    pub plugins: Vec<PluginInfoResponse>,
+    pub external_bangs: usize,
+    pub privacypolicy_url: Option<String>,
+    pub donation_url: Option<String>,
+    pub contact_url: Option<String>,
+}
+
+/// Engine description in `/config`
+#[derive(Debug, Serialize)]
+pub struct EngineInfo {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub engine: String,
+    pub categories: Vec<String>,
+    pub shortcut: String,
+    pub paging: bool,
+    pub time_range_support: bool,
+    pub safesearch: bool,
+    pub languages: Vec<String>,
+    #[serde(flatten)]
+    pub about: crate::engines::EngineAbout,
+    pub timeout: f64,
+    pub weight: f64,
+}
+
+/// Plugin description in `/config`
+#[derive(Debug, Serialize)]
+pub struct PluginInfoResponse {
+    #[serde(flatten)]
+    pub info: crate::plugins::PluginInfo,
+    pub enabled: bool,
+}
+
+/// Instance configuration handler
+pub async fn config(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    let registry = state.registry_for(&access_tokens(&headers));
+    let settings = &state.settings;
+
+    let mut names = registry.names();
+    names.sort_unstable();
+
+    let engines = names
+        .into_iter()
+        .filter_map(|name| {
+            let engine = registry.get(name)?;
+            let config = registry.get_config(name);
+            Some(EngineInfo {
+                name: name.to_string(),
+                display_name: config.and_then(|c| c.display_name.clone()),
+                engine: config.map(|c| c.engine.clone()).unwrap_or_default(),
+                categories: registry
+                    .categories_of(name)
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect(),
+                shortcut: config.map(|c| c.shortcut.clone()).unwrap_or_default(),
+                paging: engine.supports_paging(),
+                time_range_support: engine.supports_time_range(),
+                safesearch: engine.supports_safesearch(),
+                languages: engine.languages().into_iter().map(str::to_string).collect(),
+                about: engine.about(),
+                timeout: registry.get_timeout(name, settings.outgoing.request_timeout),
+                weight: registry.get_weight(name),
+            })
+        })
+        .collect();
+
+    let mut categories: Vec<String> = registry
+        .category_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    categories.sort();
+
+    let plugins = state
+        .plugins
+        .list()
+        .into_iter()
+        .map(|info| PluginInfoResponse {
+            enabled: state.plugins.is_enabled(&info.id),
+            info,
+        })
+        .collect();
+
+    Json(ConfigResponse {
+        instance_name: settings.general.instance_name.clone(),
+        version: crate::VERSION.to_string(),
+        public_instance: settings.server.public_instance,
+        default_locale: settings.ui.default_locale.clone(),
+        default_theme: settings.ui.default_theme.clone(),
+        themes: settings.ui.themes.clone(),
+        results_per_page: settings.ui.results_per_page,
+        safe_search: settings.search.safe_search,
+        default_lang: settings.search.default_lang.clone(),
+        max_page: settings.search.max_page,
+        autocomplete: settings.search.autocomplete.clone(),
+        autocomplete_backends: crate::autocomplete::list_backends()
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        formats: settings.search.formats.clone(),
+        default_categories: settings.search.default_categories.clone(),
+        categories,
+        engines,
+        plugins,
+        external_bangs: state.bangs.len(),
+        privacypolicy_url: settings.general.privacypolicy_url.clone(),
+        donation_url: settings.general.donation_url.clone(),
+        contact_url: settings.general.contact_url.clone(),
+    })
+}
+
 /// Robots.txt handler
 pub async fn robots_txt(State(state): State<AppState>) -> impl IntoResponse {
     let content = if state.is_public() {
@@ -349,3 +741,199 @@ pub async fn favicon() -> impl IntoResponse {
         FAVICON_SVG,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use crate::engines::EngineLoader;
+    use crate::network::HttpClient;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::Service;
+
+    /// Send a request through the router of an instance with `settings`
+    async fn send(settings: Settings, request: Request<Body>) -> Response {
+        let registry = EngineLoader::load(&settings).unwrap();
+        let state = AppState::new(settings, registry, HttpClient::new().unwrap()).unwrap();
+        let mut router = crate::web::routes::create_router(state);
+        std::future::poll_fn(|cx| {
+            <axum::Router as Service<Request<Body>>>::poll_ready(&mut router, cx)
+        })
+        .await
+        .unwrap();
+        router.call(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_preferences_tokens_only_from_post() {
+        let response = send(
+            Settings::default(),
+            Request::get("/preferences?tokens=evil")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::SET_COOKIE).is_none());
+
+        let response = send(
+            Settings::default(),
+            Request::post("/preferences")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from("tokens=s3cret"))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        assert!(cookie.starts_with("tokens=s3cret;"));
+        assert!(cookie.contains("HttpOnly"));
+    }
+
+    #[tokio::test]
+    async fn test_search_mixed_images() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let remote = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"results": [
+                    {"url": "https://a.example.org/crab", "title": "Crab photo",
+                     "img_src": "https://cdn.example.org/crab.jpg"},
+                    {"url": "https://b.example.org/crabs", "title": "All about crabs"}
+                ]}"#,
+            ))
+            .mount(&remote)
+            .await;
+        let settings = Settings {
+            engines: serde_yaml::from_str(&format!(
+                "- {{name: remote, engine: searx, base_url: '{}'}}",
+                remote.uri()
+            ))
+            .unwrap(),
+            ..Default::default()
+        };
+
+        let response = send(
+            settings,
+            Request::get("/search?q=crab&engines=remote")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+
+        // Images go to the grid, the other results are listed below it
+        let grid = html.find(r#"<div class="image-grid">"#).unwrap();
+        let list = html.find(r#"<div class="results">"#).unwrap();
+        assert!(grid < list);
+        assert!(html[grid..list].contains("Crab photo"));
+        assert!(!html[grid..list].contains("All about crabs"));
+        assert!(html[list..].contains("All about crabs"));
+        assert!(!html[list..].contains("Crab photo"));
+    }
+
+    async fn json(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn config_settings() -> Settings {
+        let mut settings = Settings {
+            engines: serde_yaml::from_str(
+                "- {name: wikipedia, engine: wikipedia, shortcut: wp, weight: 2.0, timeout: 3.0}\n\
+                 - {name: intranet, engine: searx, base_url: 'http://intranet.example', tokens: [s3cret]}",
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        settings.plugins.enabled = vec!["hash_plugin".to_string()];
+        settings.plugins.disabled = vec!["calculator".to_string()];
+        settings
+    }
+
+    #[tokio::test]
+    async fn test_config_engines() {
+        let response = send(
+            config_settings(),
+            Request::get("/config").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let config = json(response).await;
+
+        let engines = config["engines"].as_array().unwrap();
+        assert_eq!(engines.len(), 1);
+        let wikipedia = &engines[0];
+        assert_eq!(wikipedia["name"], "wikipedia");
+        assert_eq!(wikipedia["engine"], "wikipedia");
+        assert_eq!(wikipedia["shortcut"], "wp");
+        assert_eq!(wikipedia["categories"], serde_json::json!(["general"]));
+        assert_eq!(wikipedia["paging"], true);
+        assert_eq!(wikipedia["timeout"], 3.0);
+        assert_eq!(wikipedia["weight"], 2.0);
+        assert!(!wikipedia["languages"].as_array().unwrap().is_empty());
+        assert_eq!(config["categories"], serde_json::json!(["general"]));
+    }
+
+    #[tokio::test]
+    async fn test_config_private_engines() {
+        // Private engines are only listed for token holders
+        let response = send(
+            config_settings(),
+            Request::get("/config")
+                .header(TOKEN_HEADER, "s3cret")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        let config = json(response).await;
+        let names: Vec<&str> = config["engines"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["intranet", "wikipedia"]);
+
+        let response = send(
+            config_settings(),
+            Request::get("/config")
+                .header(TOKEN_HEADER, "wrong")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        let config = json(response).await;
+        assert_eq!(config["engines"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_config_plugins() {
+        let response = send(
+            config_settings(),
+            Request::get("/config").body(Body::empty()).unwrap(),
+        )
+        .await;
+        let config = json(response).await;
+        let enabled = |id: &str| {
+            config["plugins"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|p| p["id"] == id)
+                .map(|p| p["enabled"].clone())
+        };
+        assert_eq!(enabled("hash_plugin"), Some(serde_json::json!(true)));
+        assert_eq!(enabled("calculator"), Some(serde_json::json!(false)));
+    }
+}
@@ -19,12 +19,14 @@ pub fn create_router(state: AppState) -> Router {
         .route("/about", get(handlers::about))
         .route(
             "/preferences",
-            get(handlers::preferences).post(handlers::preferences),
+            get(handlers::preferences).post(handlers::save_preferences),
         )
         .route("/stats", get(handlers::stats))
         // API routes
         .route("/health", get(handlers::health))
         .route("/autocomplete", get(handlers::autocomplete))
+        .route("/bangs", get(handlers::bangs))
+        .route("/config", get(handlers::config))
         // Static routes
         .route("/robots.txt", get(handlers::robots_txt))
         .route("/favicon.ico", get(handlers::favicon))
@@ -1,10 +1,13 @@
 //! Application state shared across handlers
 
+use crate::bangs::BangRegistry;
 use crate::config::Settings;
 use crate::engines::EngineRegistry;
 use crate::network::HttpClient;
+use crate::plugins::PluginRegistry;
 use crate::search::Search;
 use std::sync::Arc;
+use tracing::warn;
 
 /// Shared application state
 #[derive(Clone)]
@@ -13,6 +16,10 @@ pub struct AppState {
     pub settings: Arc<Settings>,
     /// Engine registry
     pub registry: Arc<EngineRegistry>,
+    /// External bangs
+    pub bangs: Arc<BangRegistry>,
+    /// Plugins
+    pub plugins: Arc<PluginRegistry>,
     /// Search executor
     pub search: Arc<Search>,
     /// Template renderer
@@ -30,13 +37,23 @@ impl AppState {
     ) -> anyhow::Result<Self> {
         let settings = Arc::new(settings);
         let registry = Arc::new(registry);
+        let bangs = Arc::new(BangRegistry::load(&settings.bangs)?);
+        for (trigger, target) in registry.bang_collisions(&bangs) {
+            warn!(
+                "!{} resolves to {} and shadows the external bang; use !!{} for the redirect",
+                trigger, target, trigger
+            );
+        }
+        let plugins = Arc::new(PluginRegistry::from_settings(&settings.plugins));
         let http_client = Arc::new(client.clone());
-        let search = Arc::new(Search::new(client, registry.clone()));
+        let search = Arc::new(Search::new(client, registry.clone()).with_bangs(bangs.clone()));
         let templates = Arc::new(super::Templates::new()?);
 
         Ok(Self {
             settings,
             registry,
+            bangs,
+            plugins,
             search,
             templates,
             http_client,
@@ -53,6 +70,15 @@ impl AppState {
         self.settings.server.public_instance
     }
 
+    /// Get the engine registry visible to a user presenting `tokens`
+    pub fn registry_for(&self, tokens: &[String]) -> Arc<EngineRegistry> {
+        if self.registry.has_private() {
+            Arc::new(self.registry.visible_to(tokens))
+        } else {
+            self.registry.clone()
+        }
+    }
+
     /// Get configured autocomplete backend name
     pub fn autocomplete_backend(&self) -> Option<&str> {
         self.settings.search.autocomplete.as_deref()
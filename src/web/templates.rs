@@ -40,6 +40,38 @@ impl Templates {
             "components/infobox.html",
             include_str!("../templates/components/infobox.html"),
         )?;
+        tera.add_raw_template(
+            "components/map.html",
+            include_str!("../templates/components/map.html"),
+        )?;
+        tera.add_raw_template(
+            "components/package.html",
+            include_str!("../templates/components/package.html"),
+        )?;
+        tera.add_raw_template(
+            "components/paper.html",
+            include_str!("../templates/components/paper.html"),
+        )?;
+        tera.add_raw_template(
+            "components/discussion.html",
+            include_str!("../templates/components/discussion.html"),
+        )?;
+        tera.add_raw_template(
+            "components/videos.html",
+            include_str!("../templates/components/videos.html"),
+        )?;
+        tera.add_raw_template(
+            "components/audio.html",
+            include_str!("../templates/components/audio.html"),
+        )?;
+        tera.add_raw_template(
+            "components/news.html",
+            include_str!("../templates/components/news.html"),
+        )?;
+        tera.add_raw_template(
+            "components/images.html",
+            include_str!("../templates/components/images.html"),
+        )?;
         tera.add_raw_template(
             "components/pagination.html",
             include_str!("../templates/components/pagination.html"),